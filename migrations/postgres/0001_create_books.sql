@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS books (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    author TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    publisher TEXT NOT NULL,
+    page_count INTEGER NOT NULL,
+    read_page INTEGER NOT NULL,
+    reading BOOLEAN NOT NULL,
+    finished BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    inserted_at TIMESTAMPTZ NOT NULL
+);
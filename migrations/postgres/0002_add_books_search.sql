@@ -0,0 +1,9 @@
+-- The 'simple' configuration lowercases without stemming, matching how the
+-- other backends tokenize.
+ALTER TABLE books ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', name), 'A') ||
+    setweight(to_tsvector('simple', author), 'B') ||
+    setweight(to_tsvector('simple', summary), 'C')
+) STORED;
+
+CREATE INDEX books_search_idx ON books USING GIN (search);
@@ -0,0 +1,14 @@
+-- Keys are looked up by the SHA-256 of their value, `prefix` is the start of
+-- the key kept so owners can tell them apart. `scopes` is space separated.
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX api_keys_owner_id_idx ON api_keys (owner_id);
@@ -0,0 +1,4 @@
+-- Deleted books stay in the trash until restored or purged.
+ALTER TABLE books ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX books_deleted_at ON books (deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Append-only record of book changes. `changes` is a JSON object of the
+-- changed fields, each as `{"before": ..., "after": ...}`.
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL,
+    actor_id UUID NOT NULL,
+    action TEXT NOT NULL,
+    book_id UUID NOT NULL,
+    changes JSONB NOT NULL,
+    request_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX audit_log_book_id_idx ON audit_log (book_id);
+CREATE INDEX audit_log_created_at_idx ON audit_log (created_at);
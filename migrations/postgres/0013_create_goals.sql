@@ -0,0 +1,13 @@
+-- Reading goals: `target` books finished or pages read between `starts_at`
+-- and `ends_at`.
+CREATE TABLE goals (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL,
+    metric TEXT NOT NULL,
+    target BIGINT NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX goals_owner_id_idx ON goals (owner_id, created_at);
@@ -0,0 +1,5 @@
+-- When a book was last finished, unset while it isn't. Books finished before
+-- this column existed take their last update as the best guess.
+ALTER TABLE books ADD COLUMN finished_at TIMESTAMPTZ;
+
+UPDATE books SET finished_at = updated_at WHERE finished;
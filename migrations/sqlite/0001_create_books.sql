@@ -0,0 +1,16 @@
+-- Databases created before migrations existed already have this table,
+-- hence IF NOT EXISTS.
+CREATE TABLE IF NOT EXISTS books (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    author TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    publisher TEXT NOT NULL,
+    page_count INTEGER NOT NULL,
+    read_page INTEGER NOT NULL,
+    reading BOOLEAN NOT NULL,
+    finished BOOLEAN NOT NULL,
+    updated_at TEXT NOT NULL,
+    inserted_at TEXT NOT NULL
+);
@@ -0,0 +1,28 @@
+-- Full-text index over the searchable columns, kept in sync by triggers.
+-- Rows are keyed by book id instead of rowid because INSERT OR REPLACE gives
+-- the replaced book a new rowid without firing the delete trigger.
+CREATE VIRTUAL TABLE books_fts USING fts5(
+    book_id UNINDEXED,
+    name,
+    author,
+    summary
+);
+
+INSERT INTO books_fts (book_id, name, author, summary)
+SELECT id, name, author, summary FROM books;
+
+CREATE TRIGGER books_fts_insert AFTER INSERT ON books BEGIN
+    DELETE FROM books_fts WHERE book_id = new.id;
+    INSERT INTO books_fts (book_id, name, author, summary)
+    VALUES (new.id, new.name, new.author, new.summary);
+END;
+
+CREATE TRIGGER books_fts_update AFTER UPDATE ON books BEGIN
+    DELETE FROM books_fts WHERE book_id = old.id;
+    INSERT INTO books_fts (book_id, name, author, summary)
+    VALUES (new.id, new.name, new.author, new.summary);
+END;
+
+CREATE TRIGGER books_fts_delete AFTER DELETE ON books BEGIN
+    DELETE FROM books_fts WHERE book_id = old.id;
+END;
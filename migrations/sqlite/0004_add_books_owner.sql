@@ -0,0 +1,5 @@
+-- Books from before accounts existed have no owner and stay hidden until
+-- assigned with `UPDATE books SET owner_id = '<user id>'`.
+ALTER TABLE books ADD COLUMN owner_id TEXT;
+
+CREATE INDEX books_owner_id_idx ON books (owner_id);
@@ -0,0 +1,3 @@
+-- Existing accounts keep full control of their own shelf.
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'editor'
+    CHECK (role IN ('reader', 'editor', 'admin'));
@@ -0,0 +1,2 @@
+-- Bumped on every update so stale writes can be refused.
+ALTER TABLE books ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
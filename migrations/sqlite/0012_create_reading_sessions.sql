@@ -0,0 +1,14 @@
+-- Each sitting with a book, from which its `read_page` is derived.
+CREATE TABLE reading_sessions (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    book_id TEXT NOT NULL,
+    start_page INTEGER NOT NULL,
+    end_page INTEGER NOT NULL,
+    started_at TEXT NOT NULL,
+    ended_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX reading_sessions_book_id_idx ON reading_sessions (book_id, ended_at);
+CREATE INDEX reading_sessions_owner_id_idx ON reading_sessions (owner_id, ended_at);
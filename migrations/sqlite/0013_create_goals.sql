@@ -0,0 +1,13 @@
+-- Reading goals: `target` books finished or pages read between `starts_at`
+-- and `ends_at`.
+CREATE TABLE goals (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    target INTEGER NOT NULL,
+    starts_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX goals_owner_id_idx ON goals (owner_id, created_at);
@@ -1,42 +1,300 @@
 use std::sync::Arc;
 
+use anyhow::Context;
 use axum::{
     Router,
     extract::{MatchedPath, Request},
-    routing::{get, post},
+    routing::{delete, get, post, put},
+};
+use chrono::Duration;
+use tonic::service::Routes;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
 };
-use tower_http::trace::TraceLayer;
 use tracing::info_span;
+use uuid::Uuid;
 
+#[cfg(feature = "postgres")]
+use crate::repos::{
+    api_key::postgres::PgApiKeyRepo, audit::postgres::PgAuditRepo, book::postgres::PgBookRepo,
+    goal::postgres::PgGoalRepo, migrate::POSTGRES_MIGRATIONS, session::postgres::PgSessionRepo,
+    token::postgres::PgTokenRepo, user::postgres::PgUserRepo, webhook::postgres::PgWebhookRepo,
+};
 use crate::{
-    repos::book::inmemory::InMemoryBookRepo,
+    config::{Config, StorageConfig, TrashConfig, WebhookConfig},
+    repos::{
+        api_key::{ApiKeyRepo, inmemory::InMemoryApiKeyRepo, sqlite::SqliteApiKeyRepo},
+        audit::{AuditRepo, inmemory::InMemoryAuditRepo, sqlite::SqliteAuditRepo},
+        book::{BookRepo, inmemory::InMemoryBookRepo, sqlite::SqliteBookRepo},
+        goal::{GoalRepo, inmemory::InMemoryGoalRepo, sqlite::SqliteGoalRepo},
+        migrate::{self, SQLITE_MIGRATIONS},
+        session::{SessionRepo, inmemory::InMemorySessionRepo, sqlite::SqliteSessionRepo},
+        token::{TokenRepo, inmemory::InMemoryTokenRepo, sqlite::SqliteTokenRepo},
+        user::{UserRepo, inmemory::InMemoryUserRepo, sqlite::SqliteUserRepo},
+        webhook::{WebhookRepo, inmemory::InMemoryWebhookRepo, sqlite::SqliteWebhookRepo},
+    },
     services::{
-        auth::handler::{authorize, protected},
-        book::BookState,
+        admin::{
+            handler::{get_users, update_user_role},
+            promote_admins,
+        },
+        api_key::handler::{create_api_key, delete_api_key, get_api_keys},
+        audit::{AuditState, handler::get_audit},
+        auth::{
+            AuthState,
+            handler::{authorize, jwks, login, logout, protected, refresh, register},
+            keys::Keys,
+        },
+        book::{
+            BookState,
+            batch::batch_books,
+            events::{BookEvents, book_events, book_events_ws},
+            grpc::{BookGrpcService, proto::book_service_server::BookServiceServer},
+            handler::{
+                create_book, delete_book, get_book_by_id, get_books, patch_book, search_books,
+                update_book,
+            },
+            history::get_book_history,
+            sessions::{create_session, get_book_progress},
+            stats::get_shelf_stats,
+            transfer::{export_books, import_books},
+            trash::{get_trash, purge_trash, restore_book},
+        },
+        goal::{
+            GoalState,
+            handler::{create_goal, delete_goal, get_goal, get_goals},
+        },
+        webhook::{
+            WebhookState,
+            delivery::{self, DELIVERY_BATCH, DeliveryPolicy, deliver_webhooks},
+            handler::{
+                create_webhook, delete_webhook, get_dead_letters, get_webhook, get_webhooks,
+                retry_delivery, update_webhook,
+            },
+            target::TargetPolicy,
+        },
     },
 };
-use crate::{
-    repos::book::sqlite::SqliteBookRepo,
-    services::book::handler::{create_book, delete_book, get_book_by_id, get_books, update_book},
-};
 
-pub async fn app() -> Router {
-    let _inmemory_book_repo = InMemoryBookRepo::default();
-    let sqlite_book_repo = SqliteBookRepo::new("sqlite::memory:".to_string()).await;
+/// Every repository of one storage backend.
+struct Repos {
+    book: Arc<dyn BookRepo>,
+    user: Arc<dyn UserRepo>,
+    token: Arc<dyn TokenRepo>,
+    api_key: Arc<dyn ApiKeyRepo>,
+    audit: Arc<dyn AuditRepo>,
+    session: Arc<dyn SessionRepo>,
+    goal: Arc<dyn GoalRepo>,
+    webhook: Arc<dyn WebhookRepo>,
+}
+
+impl Repos {
+    async fn new(config: &Config) -> anyhow::Result<Self> {
+        match &config.storage {
+            StorageConfig::Memory => Ok(Repos {
+                book: Arc::new(InMemoryBookRepo::default()),
+                user: Arc::new(InMemoryUserRepo::default()),
+                token: Arc::new(InMemoryTokenRepo::default()),
+                api_key: Arc::new(InMemoryApiKeyRepo::default()),
+                audit: Arc::new(InMemoryAuditRepo::default()),
+                session: Arc::new(InMemorySessionRepo::default()),
+                goal: Arc::new(InMemoryGoalRepo::default()),
+                webhook: Arc::new(InMemoryWebhookRepo::default()),
+            }),
+            StorageConfig::Sqlite { path } => {
+                let pool = migrate::connect_sqlite(path)
+                    .await
+                    .with_context(|| format!("Failed to open SQLite database {}", path))?;
+                migrate::prepare(&pool, SQLITE_MIGRATIONS, config.migrations.auto_apply).await?;
+                Ok(Repos {
+                    book: Arc::new(SqliteBookRepo::new(pool.clone())),
+                    user: Arc::new(SqliteUserRepo::new(pool.clone())),
+                    token: Arc::new(SqliteTokenRepo::new(pool.clone())),
+                    api_key: Arc::new(SqliteApiKeyRepo::new(pool.clone())),
+                    audit: Arc::new(SqliteAuditRepo::new(pool.clone())),
+                    session: Arc::new(SqliteSessionRepo::new(pool.clone())),
+                    goal: Arc::new(SqliteGoalRepo::new(pool.clone())),
+                    webhook: Arc::new(SqliteWebhookRepo::new(pool)),
+                })
+            }
+            #[cfg(feature = "postgres")]
+            StorageConfig::Postgres { url } => {
+                let pool = migrate::connect_postgres(url)
+                    .await
+                    .context("Failed to connect to PostgreSQL")?;
+                migrate::prepare(&pool, POSTGRES_MIGRATIONS, config.migrations.auto_apply).await?;
+                Ok(Repos {
+                    book: Arc::new(PgBookRepo::new(pool.clone())),
+                    user: Arc::new(PgUserRepo::new(pool.clone())),
+                    token: Arc::new(PgTokenRepo::new(pool.clone())),
+                    api_key: Arc::new(PgApiKeyRepo::new(pool.clone())),
+                    audit: Arc::new(PgAuditRepo::new(pool.clone())),
+                    session: Arc::new(PgSessionRepo::new(pool.clone())),
+                    goal: Arc::new(PgGoalRepo::new(pool.clone())),
+                    webhook: Arc::new(PgWebhookRepo::new(pool)),
+                })
+            }
+            #[cfg(not(feature = "postgres"))]
+            StorageConfig::Postgres { .. } => {
+                anyhow::bail!("This build has no PostgreSQL support, enable the `postgres` feature")
+            }
+        }
+    }
+}
+
+fn ttl(secs: u64) -> anyhow::Result<Duration> {
+    seconds(secs).with_context(|| format!("Token lifetime of {} seconds is out of range", secs))
+}
+
+fn seconds(secs: u64) -> Option<Duration> {
+    i64::try_from(secs).ok().and_then(Duration::try_seconds)
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub book: BookState,
+    pub audit: AuditState,
+    pub goal: GoalState,
+    pub webhook: WebhookState,
+    pub auth: AuthState,
+}
+
+impl AppState {
+    pub async fn new(config: &Config) -> anyhow::Result<Self> {
+        let repos = Repos::new(config).await?;
+        promote_admins(repos.user.as_ref(), &config.auth.admin_usernames)
+            .await
+            .context("Failed to promote the configured admins")?;
+
+        let keys = if config.auth.keys.is_empty() {
+            let secret = match &config.auth.jwt_secret {
+                Some(secret) => secret.clone(),
+                None => {
+                    tracing::warn!("No JWT secret configured, tokens won't survive a restart");
+                    Uuid::new_v4().simple().to_string()
+                }
+            };
+            Keys::from_secret(secret.as_bytes())
+        } else {
+            Keys::from_config(&config.auth.keys).context("Failed to load JWT signing keys")?
+        };
+
+        let auth = AuthState {
+            keys: Arc::new(keys),
+            users: repos.user,
+            tokens: repos.token,
+            api_keys: repos.api_key,
+            access_token_ttl: ttl(config.auth.access_token_ttl_secs)?,
+            refresh_token_ttl: ttl(config.auth.refresh_token_ttl_secs)?,
+        };
+        Ok(AppState {
+            book: BookState {
+                repo: repos.book.clone(),
+                audit: repos.audit.clone(),
+                events: BookEvents::default(),
+                sessions: repos.session.clone(),
+                webhooks: repos.webhook.clone(),
+                auth: auth.clone(),
+            },
+            audit: AuditState {
+                repo: repos.audit,
+                auth: auth.clone(),
+            },
+            goal: GoalState {
+                repo: repos.goal,
+                books: repos.book,
+                sessions: repos.session,
+                auth: auth.clone(),
+            },
+            webhook: WebhookState {
+                repo: repos.webhook,
+                targets: TargetPolicy::new(&config.webhooks.allowed_hosts),
+                auth: auth.clone(),
+            },
+            auth,
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub async fn app(config: Config) -> Router {
+    let state = AppState::new(&config)
+        .await
+        .expect("Failed to initialize application");
+    router(state)
+}
+
+pub fn router(state: AppState) -> Router {
     let book_router = Router::new()
         .route("/", post(create_book).get(get_books))
+        .route("/search", get(search_books))
+        .route("/batch", post(batch_books))
+        .route("/import", post(import_books))
+        .route("/export", get(export_books))
+        .route("/events", get(book_events))
+        .route("/events/ws", get(book_events_ws))
+        .route("/trash", get(get_trash))
+        .route("/{id}/restore", post(restore_book))
+        .route("/{id}/history", get(get_book_history))
+        .route("/{id}/sessions", post(create_session))
+        .route("/{id}/progress", get(get_book_progress))
         .route(
             "/{id}",
-            get(get_book_by_id).put(update_book).delete(delete_book),
+            get(get_book_by_id)
+                .put(update_book)
+                .patch(patch_book)
+                .delete(delete_book),
         )
-        .with_state(BookState {
-            repo: Arc::new(sqlite_book_repo),
-        });
-    let auth_router = Router::new().route("/", post(authorize).get(protected));
+        .with_state(state.book.clone());
+    let stats_router = Router::new()
+        .route("/", get(get_shelf_stats))
+        .with_state(state.book);
+    let auth_router = Router::new()
+        .route("/", post(authorize).get(protected))
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .with_state(state.auth.clone());
+    let admin_router = Router::new()
+        .route("/users", get(get_users))
+        .route("/users/{id}/role", put(update_user_role))
+        .with_state(state.auth.clone());
+    let api_key_router = Router::new()
+        .route("/", post(create_api_key).get(get_api_keys))
+        .route("/{id}", delete(delete_api_key))
+        .with_state(state.auth.clone());
+    let audit_router = Router::new()
+        .route("/", get(get_audit))
+        .with_state(state.audit);
+    let goal_router = Router::new()
+        .route("/", post(create_goal).get(get_goals))
+        .route("/{id}", get(get_goal).delete(delete_goal))
+        .with_state(state.goal);
+    let webhook_router = Router::new()
+        .route("/", post(create_webhook).get(get_webhooks))
+        .route("/dead-letters", get(get_dead_letters))
+        .route("/deliveries/{id}/retry", post(retry_delivery))
+        .route(
+            "/{id}",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .with_state(state.webhook);
+    let well_known_router = Router::new()
+        .route("/jwks.json", get(jwks))
+        .with_state(state.auth);
 
-    let app = Router::new()
+    Router::new()
+        .nest("/.well-known", well_known_router)
+        .nest("/admin", admin_router)
+        .nest("/api-keys", api_key_router)
+        .nest("/audit", audit_router)
         .nest("/auth", auth_router)
         .nest("/books", book_router)
+        .nest("/goals", goal_router)
+        .nest("/stats", stats_router)
+        .nest("/webhooks", webhook_router)
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|request: &Request<_>| {
@@ -53,6 +311,70 @@ pub async fn app() -> Router {
                     )
                 })
                 .on_request(()),
-        );
-    app
+        )
+        // Outermost, so the id is set before anything else sees the request.
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// The job emptying the trash of books past their retention, to be spawned
+/// next to the servers.
+pub fn trash_purge(
+    state: &AppState,
+    config: &TrashConfig,
+) -> anyhow::Result<impl Future<Output = ()> + use<>> {
+    let retention = seconds(config.retention_secs).with_context(|| {
+        format!(
+            "Trash retention of {} seconds is out of range",
+            config.retention_secs
+        )
+    })?;
+    if config.purge_interval_secs == 0 {
+        anyhow::bail!("Trash purge interval must be at least one second");
+    }
+    let interval = std::time::Duration::from_secs(config.purge_interval_secs);
+    Ok(purge_trash(state.book.repo.clone(), retention, interval))
+}
+
+/// The job sending queued webhook deliveries, to be spawned next to the
+/// servers.
+pub fn webhook_delivery(
+    state: &AppState,
+    config: &WebhookConfig,
+) -> anyhow::Result<impl Future<Output = ()> + use<>> {
+    let retry_base = seconds(config.retry_base_secs).with_context(|| {
+        format!(
+            "Webhook retry base of {} seconds is out of range",
+            config.retry_base_secs
+        )
+    })?;
+    if config.poll_interval_secs == 0 {
+        anyhow::bail!("Webhook poll interval must be at least one second");
+    }
+    if config.max_attempts == 0 {
+        anyhow::bail!("Webhooks need at least one delivery attempt");
+    }
+    let targets = state.webhook.targets.clone();
+    let client = delivery::client(
+        std::time::Duration::from_secs(config.timeout_secs),
+        targets.clone(),
+    )
+    .context("Failed to build the webhook client")?;
+    let policy = DeliveryPolicy {
+        max_attempts: config.max_attempts,
+        retry_base,
+        batch: DELIVERY_BATCH,
+        targets,
+    };
+    let interval = std::time::Duration::from_secs(config.poll_interval_secs);
+    Ok(deliver_webhooks(
+        state.webhook.repo.clone(),
+        client,
+        policy,
+        interval,
+    ))
+}
+
+pub fn grpc(state: AppState) -> Routes {
+    Routes::new(BookServiceServer::new(BookGrpcService::new(state.book)))
 }
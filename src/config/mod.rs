@@ -0,0 +1,344 @@
+use std::{net::SocketAddr, path::Path};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+pub mod test;
+
+/// Runtime settings, read from an optional TOML file and overridden by
+/// `BOOKSHELF_*` environment variables.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+    pub migrations: MigrationsConfig,
+    pub log: LogConfig,
+    pub auth: AuthConfig,
+    pub trash: TrashConfig,
+    pub webhooks: WebhookConfig,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub addr: SocketAddr,
+    pub grpc_addr: SocketAddr,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "backend", rename_all = "lowercase", deny_unknown_fields)]
+pub enum StorageConfig {
+    Memory,
+    Sqlite {
+        path: String,
+    },
+    /// Needs the `postgres` cargo feature.
+    Postgres {
+        url: String,
+    },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct MigrationsConfig {
+    /// Apply pending migrations on startup instead of refusing to start.
+    pub auto_apply: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub filter: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// HMAC secret for signing tokens, a random one is generated when unset.
+    pub jwt_secret: Option<String>,
+    /// Lifetime of access tokens in seconds.
+    pub access_token_ttl_secs: u64,
+    /// Lifetime of refresh tokens in seconds.
+    pub refresh_token_ttl_secs: u64,
+    /// Asymmetric signing keys, replacing `jwt_secret` when set. The first
+    /// key signs new tokens, the others only verify so tokens issued before
+    /// a rotation stay valid until they expire.
+    pub keys: Vec<JwtKeyConfig>,
+    /// Existing accounts under these names are made admins at startup,
+    /// everyone registering starts as an editor. Register a name before
+    /// listing it: a listed name that nobody holds yet only becomes an admin
+    /// after the next restart, whoever registered it, so on an open
+    /// deployment that may well be someone else.
+    pub admin_usernames: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    /// Seconds a deleted book stays restorable before it is purged.
+    pub retention_secs: u64,
+    /// Seconds between two purges.
+    pub purge_interval_secs: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// Seconds between two looks for due deliveries.
+    pub poll_interval_secs: u64,
+    /// Attempts before a delivery is given up on and listed as dead.
+    pub max_attempts: u32,
+    /// Seconds before the first retry, doubled after every further failure.
+    pub retry_base_secs: u64,
+    /// Seconds a receiver has to answer a delivery.
+    pub timeout_secs: u64,
+    /// Hosts webhooks may target although they are or resolve to a
+    /// loopback, link-local or private address, such as a local receiver.
+    pub allowed_hosts: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct JwtKeyConfig {
+    /// Sent as the `kid` token header and listed in the JWKS.
+    pub kid: String,
+    pub algorithm: JwtAlgorithm,
+    /// Path of the PEM encoded public key.
+    pub public_key: String,
+    /// Path of the PEM encoded private key, needed by the first key only.
+    pub private_key: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum JwtAlgorithm {
+    #[serde(rename = "RS256")]
+    Rs256,
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            addr: SocketAddr::from(([127, 0, 0, 1], 5000)),
+            grpc_addr: SocketAddr::from(([127, 0, 0, 1], 50051)),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig::Sqlite {
+            path: "bookshelf.db".to_string(),
+        }
+    }
+}
+
+impl Default for MigrationsConfig {
+    fn default() -> Self {
+        MigrationsConfig { auto_apply: true }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            filter: format!("{}=debug,tower_http=debug", env!("CARGO_CRATE_NAME")),
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            jwt_secret: None,
+            access_token_ttl_secs: 15 * 60,
+            refresh_token_ttl_secs: 30 * 24 * 60 * 60,
+            keys: Vec::new(),
+            admin_usernames: Vec::new(),
+        }
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        TrashConfig {
+            retention_secs: 30 * 24 * 60 * 60,
+            purge_interval_secs: 60 * 60,
+        }
+    }
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            poll_interval_secs: 5,
+            max_attempts: 8,
+            retry_base_secs: 30,
+            timeout_secs: 10,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads `BOOKSHELF_CONFIG` (or `bookshelf.toml` when present) and
+    /// applies the environment on top of it.
+    pub fn load() -> anyhow::Result<Self> {
+        let path = std::env::var("BOOKSHELF_CONFIG").ok();
+        let config = match path.as_deref() {
+            Some(path) => Config::from_file(Path::new(path))?,
+            None if Path::new("bookshelf.toml").exists() => {
+                Config::from_file(Path::new("bookshelf.toml"))?
+            }
+            None => Config::default(),
+        };
+
+        config.with_env(|key| std::env::var(key).ok())
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Config::from_toml(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    pub fn from_toml(content: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Overrides settings with the variables returned by `var`.
+    pub fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        if let Some(addr) = var("BOOKSHELF_ADDR") {
+            self.server.addr = addr.parse().context("Invalid BOOKSHELF_ADDR")?;
+        }
+        if let Some(addr) = var("BOOKSHELF_GRPC_ADDR") {
+            self.server.grpc_addr = addr.parse().context("Invalid BOOKSHELF_GRPC_ADDR")?;
+        }
+
+        let path = var("BOOKSHELF_SQLITE_PATH");
+        let url = var("BOOKSHELF_DATABASE_URL");
+        match var("BOOKSHELF_STORAGE").as_deref() {
+            Some("memory") => self.storage = StorageConfig::Memory,
+            Some("sqlite") => {
+                let path = match (path, &self.storage) {
+                    (Some(path), _) => path,
+                    (None, StorageConfig::Sqlite { path }) => path.clone(),
+                    (None, _) => "bookshelf.db".to_string(),
+                };
+                self.storage = StorageConfig::Sqlite { path };
+            }
+            Some("postgres") => {
+                let url = match (url, &self.storage) {
+                    (Some(url), _) => url,
+                    (None, StorageConfig::Postgres { url }) => url.clone(),
+                    (None, _) => anyhow::bail!("BOOKSHELF_DATABASE_URL is required for postgres"),
+                };
+                self.storage = StorageConfig::Postgres { url };
+            }
+            Some(backend) => anyhow::bail!("Unknown BOOKSHELF_STORAGE backend `{}`", backend),
+            // Either location on its own picks its backend.
+            None => match (path, url) {
+                (Some(path), None) => self.storage = StorageConfig::Sqlite { path },
+                (None, Some(url)) => self.storage = StorageConfig::Postgres { url },
+                (Some(_), Some(_)) => anyhow::bail!(
+                    "Both BOOKSHELF_SQLITE_PATH and BOOKSHELF_DATABASE_URL are set, choose one with BOOKSHELF_STORAGE"
+                ),
+                (None, None) => {}
+            },
+        }
+
+        if let Some(auto_apply) = var("BOOKSHELF_AUTO_MIGRATE") {
+            self.migrations.auto_apply = auto_apply
+                .parse()
+                .context("Invalid BOOKSHELF_AUTO_MIGRATE, expected true or false")?;
+        }
+
+        if let Some(filter) = var("BOOKSHELF_LOG").or_else(|| var("RUST_LOG")) {
+            self.log.filter = filter;
+        }
+        if let Some(secret) = var("BOOKSHELF_JWT_SECRET") {
+            self.auth.jwt_secret = Some(secret);
+        }
+        if let Some(ttl) = var("BOOKSHELF_ACCESS_TOKEN_TTL") {
+            self.auth.access_token_ttl_secs = ttl
+                .parse()
+                .context("Invalid BOOKSHELF_ACCESS_TOKEN_TTL, expected seconds")?;
+        }
+        if let Some(names) = var("BOOKSHELF_ADMIN_USERNAMES") {
+            self.auth.admin_usernames = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        if let Some(ttl) = var("BOOKSHELF_REFRESH_TOKEN_TTL") {
+            self.auth.refresh_token_ttl_secs = ttl
+                .parse()
+                .context("Invalid BOOKSHELF_REFRESH_TOKEN_TTL, expected seconds")?;
+        }
+        if let Some(retention) = var("BOOKSHELF_TRASH_RETENTION") {
+            self.trash.retention_secs = retention
+                .parse()
+                .context("Invalid BOOKSHELF_TRASH_RETENTION, expected seconds")?;
+        }
+        if let Some(interval) = var("BOOKSHELF_TRASH_PURGE_INTERVAL") {
+            self.trash.purge_interval_secs = interval
+                .parse()
+                .context("Invalid BOOKSHELF_TRASH_PURGE_INTERVAL, expected seconds")?;
+        }
+        if let Some(interval) = var("BOOKSHELF_WEBHOOK_POLL_INTERVAL") {
+            self.webhooks.poll_interval_secs = interval
+                .parse()
+                .context("Invalid BOOKSHELF_WEBHOOK_POLL_INTERVAL, expected seconds")?;
+        }
+        if let Some(attempts) = var("BOOKSHELF_WEBHOOK_MAX_ATTEMPTS") {
+            self.webhooks.max_attempts = attempts
+                .parse()
+                .context("Invalid BOOKSHELF_WEBHOOK_MAX_ATTEMPTS, expected a count")?;
+        }
+        if let Some(base) = var("BOOKSHELF_WEBHOOK_RETRY_BASE") {
+            self.webhooks.retry_base_secs = base
+                .parse()
+                .context("Invalid BOOKSHELF_WEBHOOK_RETRY_BASE, expected seconds")?;
+        }
+        if let Some(timeout) = var("BOOKSHELF_WEBHOOK_TIMEOUT") {
+            self.webhooks.timeout_secs = timeout
+                .parse()
+                .context("Invalid BOOKSHELF_WEBHOOK_TIMEOUT, expected seconds")?;
+        }
+        if let Some(hosts) = var("BOOKSHELF_WEBHOOK_ALLOWED_HOSTS") {
+            self.webhooks.allowed_hosts = hosts
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        Ok(self)
+    }
+
+    /// Defaults with a throwaway in-memory SQLite database and a fixed
+    /// secret, handy for tests and demos.
+    #[allow(dead_code)]
+    pub fn ephemeral() -> Self {
+        Config {
+            storage: StorageConfig::Sqlite {
+                path: ":memory:".to_string(),
+            },
+            auth: AuthConfig {
+                jwt_secret: Some("test".to_string()),
+                ..AuthConfig::default()
+            },
+            webhooks: WebhookConfig {
+                allowed_hosts: vec!["127.0.0.1".to_string()],
+                ..WebhookConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+}
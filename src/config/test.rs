@@ -0,0 +1,250 @@
+#[cfg(test)]
+mod load_config {
+    use std::collections::HashMap;
+
+    use crate::config::{Config, JwtAlgorithm, JwtKeyConfig, StorageConfig};
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn defaults_should_use_sqlite_file_and_local_addresses() {
+        let config = Config::default();
+        assert_eq!(config.server.addr.to_string(), "127.0.0.1:5000");
+        assert_eq!(config.server.grpc_addr.to_string(), "127.0.0.1:50051");
+        assert_eq!(
+            config.storage,
+            StorageConfig::Sqlite {
+                path: "bookshelf.db".to_string()
+            }
+        );
+        assert!(config.auth.jwt_secret.is_none());
+        assert_eq!(config.auth.access_token_ttl_secs, 900);
+        assert_eq!(config.auth.refresh_token_ttl_secs, 2_592_000);
+        assert_eq!(config.trash.retention_secs, 2_592_000);
+        assert_eq!(config.trash.purge_interval_secs, 3600);
+        assert_eq!(config.webhooks.poll_interval_secs, 5);
+        assert_eq!(config.webhooks.max_attempts, 8);
+        assert_eq!(config.webhooks.retry_base_secs, 30);
+        assert_eq!(config.webhooks.timeout_secs, 10);
+    }
+
+    #[test]
+    fn toml_should_override_defaults() {
+        let config = Config::from_toml(
+            r#"
+            [server]
+            addr = "0.0.0.0:8080"
+
+            [storage]
+            backend = "memory"
+
+            [log]
+            filter = "info"
+
+            [auth]
+            jwt_secret = "s3cret"
+            access_token_ttl_secs = 60
+
+            [trash]
+            retention_secs = 86400
+
+            [webhooks]
+            max_attempts = 3
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.server.addr.to_string(), "0.0.0.0:8080");
+        assert_eq!(config.server.grpc_addr.to_string(), "127.0.0.1:50051");
+        assert_eq!(config.storage, StorageConfig::Memory);
+        assert_eq!(config.log.filter, "info");
+        assert_eq!(config.auth.jwt_secret.as_deref(), Some("s3cret"));
+        assert_eq!(config.auth.access_token_ttl_secs, 60);
+        assert_eq!(config.auth.refresh_token_ttl_secs, 2_592_000);
+        assert_eq!(config.trash.retention_secs, 86400);
+        assert_eq!(config.trash.purge_interval_secs, 3600);
+        assert_eq!(config.webhooks.max_attempts, 3);
+        assert_eq!(config.webhooks.retry_base_secs, 30);
+    }
+
+    #[test]
+    fn toml_should_read_signing_keys_in_order() {
+        let config = Config::from_toml(
+            r#"
+            [[auth.keys]]
+            kid = "2025-06"
+            algorithm = "EdDSA"
+            public_key = "keys/2025-06.pub.pem"
+            private_key = "keys/2025-06.pem"
+
+            [[auth.keys]]
+            kid = "2025-01"
+            algorithm = "RS256"
+            public_key = "keys/2025-01.pub.pem"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.auth.keys,
+            vec![
+                JwtKeyConfig {
+                    kid: "2025-06".to_string(),
+                    algorithm: JwtAlgorithm::EdDsa,
+                    public_key: "keys/2025-06.pub.pem".to_string(),
+                    private_key: Some("keys/2025-06.pem".to_string()),
+                },
+                JwtKeyConfig {
+                    kid: "2025-01".to_string(),
+                    algorithm: JwtAlgorithm::Rs256,
+                    public_key: "keys/2025-01.pub.pem".to_string(),
+                    private_key: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_with_unsupported_algorithm_should_fail() {
+        let result = Config::from_toml(
+            "[[auth.keys]]\nkid = \"a\"\nalgorithm = \"HS256\"\npublic_key = \"a.pem\"\n",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn toml_with_unknown_key_should_fail() {
+        assert!(Config::from_toml("[server]\nport = 5000\n").is_err());
+    }
+
+    #[test]
+    fn env_should_override_toml() {
+        let config = Config::from_toml("[storage]\nbackend = \"memory\"\n")
+            .unwrap()
+            .with_env(env(&[
+                ("BOOKSHELF_ADDR", "127.0.0.1:6000"),
+                ("BOOKSHELF_SQLITE_PATH", "/tmp/shelf.db"),
+                ("BOOKSHELF_JWT_SECRET", "from-env"),
+                ("BOOKSHELF_ACCESS_TOKEN_TTL", "300"),
+                ("BOOKSHELF_REFRESH_TOKEN_TTL", "86400"),
+                ("BOOKSHELF_ADMIN_USERNAMES", "alice, bob,"),
+                ("BOOKSHELF_TRASH_RETENTION", "3600"),
+                ("BOOKSHELF_TRASH_PURGE_INTERVAL", "60"),
+                ("BOOKSHELF_WEBHOOK_POLL_INTERVAL", "1"),
+                ("BOOKSHELF_WEBHOOK_MAX_ATTEMPTS", "5"),
+                ("BOOKSHELF_WEBHOOK_RETRY_BASE", "10"),
+                ("BOOKSHELF_WEBHOOK_TIMEOUT", "3"),
+                ("BOOKSHELF_WEBHOOK_ALLOWED_HOSTS", "localhost, 10.0.0.5"),
+                ("RUST_LOG", "warn"),
+            ]))
+            .unwrap();
+
+        assert_eq!(config.server.addr.to_string(), "127.0.0.1:6000");
+        assert_eq!(
+            config.storage,
+            StorageConfig::Sqlite {
+                path: "/tmp/shelf.db".to_string()
+            }
+        );
+        assert_eq!(config.auth.jwt_secret.as_deref(), Some("from-env"));
+        assert_eq!(config.auth.access_token_ttl_secs, 300);
+        assert_eq!(config.auth.refresh_token_ttl_secs, 86400);
+        assert_eq!(config.auth.admin_usernames, vec!["alice", "bob"]);
+        assert_eq!(config.trash.retention_secs, 3600);
+        assert_eq!(config.trash.purge_interval_secs, 60);
+        assert_eq!(config.webhooks.poll_interval_secs, 1);
+        assert_eq!(config.webhooks.max_attempts, 5);
+        assert_eq!(config.webhooks.retry_base_secs, 10);
+        assert_eq!(config.webhooks.timeout_secs, 3);
+        assert_eq!(config.webhooks.allowed_hosts, vec!["localhost", "10.0.0.5"]);
+        assert_eq!(config.log.filter, "warn");
+    }
+
+    #[test]
+    fn bookshelf_log_should_take_precedence_over_rust_log() {
+        let config = Config::default()
+            .with_env(env(&[("BOOKSHELF_LOG", "debug"), ("RUST_LOG", "warn")]))
+            .unwrap();
+        assert_eq!(config.log.filter, "debug");
+    }
+
+    #[test]
+    fn memory_storage_should_be_selectable_from_env() {
+        let config = Config::default()
+            .with_env(env(&[("BOOKSHELF_STORAGE", "memory")]))
+            .unwrap();
+        assert_eq!(config.storage, StorageConfig::Memory);
+    }
+
+    #[test]
+    fn unknown_storage_backend_should_fail() {
+        let result = Config::default().with_env(env(&[("BOOKSHELF_STORAGE", "oracle")]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_addr_should_fail() {
+        let result = Config::default().with_env(env(&[("BOOKSHELF_ADDR", "localhost")]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_token_ttl_should_fail() {
+        let result =
+            Config::default().with_env(env(&[("BOOKSHELF_ACCESS_TOKEN_TTL", "15 minutes")]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn postgres_storage_should_read_database_url() {
+        let config = Config::default()
+            .with_env(env(&[
+                ("BOOKSHELF_STORAGE", "postgres"),
+                ("BOOKSHELF_DATABASE_URL", "postgres://localhost/shelf"),
+            ]))
+            .unwrap();
+        assert_eq!(
+            config.storage,
+            StorageConfig::Postgres {
+                url: "postgres://localhost/shelf".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn database_url_alone_should_select_postgres() {
+        let config = Config::default()
+            .with_env(env(&[(
+                "BOOKSHELF_DATABASE_URL",
+                "postgres://localhost/shelf",
+            )]))
+            .unwrap();
+        assert_eq!(
+            config.storage,
+            StorageConfig::Postgres {
+                url: "postgres://localhost/shelf".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn sqlite_path_and_database_url_without_storage_should_fail() {
+        let result = Config::default().with_env(env(&[
+            ("BOOKSHELF_SQLITE_PATH", "/tmp/shelf.db"),
+            ("BOOKSHELF_DATABASE_URL", "postgres://localhost/shelf"),
+        ]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn postgres_storage_without_url_should_fail() {
+        let result = Config::default().with_env(env(&[("BOOKSHELF_STORAGE", "postgres")]));
+        assert!(result.is_err());
+    }
+}
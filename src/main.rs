@@ -1,9 +1,12 @@
 mod app;
+mod config;
 mod repos;
 mod services;
 mod utils;
 
-use app::app;
+use app::{AppState, grpc, router, trash_purge, webhook_delivery};
+use config::{Config, StorageConfig};
+use repos::migrate::{self, Migration, MigrationTarget, SQLITE_MIGRATIONS};
 use services::ApiDoc;
 use tracing_subscriber::prelude::*;
 pub use utils::error::AppError;
@@ -12,23 +15,110 @@ use utoipa_swagger_ui::SwaggerUi;
 
 #[tokio::main]
 async fn main() {
+    let config = Config::load().expect("Failed to load configuration");
+
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-                format!("{}=debug,tower_http=debug", env!("CARGO_CRATE_NAME")).into()
-            }),
-        )
+        .with(tracing_subscriber::EnvFilter::new(&config.log.filter))
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let app = app()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("migrate") => {
+            if let Err(error) = migrate_command(&config, &args[1..]).await {
+                eprintln!("{:#}", error);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(command) => {
+            eprintln!(
+                "Unknown command `{}`, expected `migrate [status|check]`",
+                command
+            );
+            std::process::exit(2);
+        }
+    }
+
+    let state = AppState::new(&config)
         .await
+        .expect("Failed to initialize application");
+    let purge = trash_purge(&state, &config.trash).expect("Invalid trash settings");
+    let delivery = webhook_delivery(&state, &config.webhooks).expect("Invalid webhook settings");
+    let app = router(state.clone())
         .merge(SwaggerUi::new("/").url("/api-docs/openapi.json", ApiDoc::openapi()));
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:5000")
+    let listener = tokio::net::TcpListener::bind(config.server.addr)
         .await
         .unwrap();
 
+    let grpc_addr = config.server.grpc_addr;
+
     tracing::info!("Server running on http:{}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+    tracing::info!("gRPC server running on http:{}", grpc_addr);
+    let rest = async { axum::serve(listener, app).await.unwrap() };
+    let grpc = async {
+        tonic::transport::Server::builder()
+            .add_routes(grpc(state))
+            .serve(grpc_addr)
+            .await
+            .unwrap()
+    };
+    tokio::spawn(purge);
+    tokio::spawn(delivery);
+    tokio::join!(rest, grpc);
+}
+
+/// `migrate` applies pending migrations, `migrate status` lists them and
+/// `migrate check` fails unless the schema is current.
+async fn migrate_command(config: &Config, args: &[String]) -> anyhow::Result<()> {
+    let (target, migrations): (Box<dyn MigrationTarget>, &[Migration]) = match &config.storage {
+        StorageConfig::Memory => {
+            println!("The memory backend has no schema to migrate");
+            return Ok(());
+        }
+        StorageConfig::Sqlite { path } => (
+            Box::new(migrate::connect_sqlite(path).await?),
+            SQLITE_MIGRATIONS,
+        ),
+        #[cfg(feature = "postgres")]
+        StorageConfig::Postgres { url } => (
+            Box::new(migrate::connect_postgres(url).await?),
+            migrate::POSTGRES_MIGRATIONS,
+        ),
+        #[cfg(not(feature = "postgres"))]
+        StorageConfig::Postgres { .. } => {
+            anyhow::bail!("This build has no PostgreSQL support, enable the `postgres` feature")
+        }
+    };
+
+    match args.first().map(String::as_str) {
+        None => {
+            let ran = migrate::migrate(&*target, migrations).await?;
+            if ran.is_empty() {
+                println!("Database is up to date");
+            }
+            for version in ran {
+                println!("Applied migration {:04}", version);
+            }
+        }
+        Some("status") => {
+            for migration in migrate::status(&*target, migrations).await? {
+                let state = if migration.applied {
+                    "applied"
+                } else {
+                    "pending"
+                };
+                println!("{:04}_{} {}", migration.version, migration.name, state);
+            }
+        }
+        Some("check") => {
+            migrate::prepare(&*target, migrations, false).await?;
+            println!("Database is up to date");
+        }
+        Some(command) => anyhow::bail!("Unknown migrate command `{}`", command),
+    }
+
+    Ok(())
 }
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{ApiKey, ApiKeyRepo, api_key_not_found};
+
+#[derive(Default, Clone)]
+pub struct InMemoryApiKeyRepo(Arc<Mutex<HashMap<Uuid, ApiKey>>>);
+
+#[async_trait]
+impl ApiKeyRepo for InMemoryApiKeyRepo {
+    async fn create_api_key(&self, key: &ApiKey) -> Result<Uuid, AppError> {
+        self.0.lock().await.insert(key.id, key.clone());
+        Ok(key.id)
+    }
+    async fn get_api_keys(&self, owner: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        let mut keys: Vec<ApiKey> = self
+            .0
+            .lock()
+            .await
+            .values()
+            .filter(|key| key.owner_id == owner)
+            .cloned()
+            .collect();
+        keys.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(keys)
+    }
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, AppError> {
+        Ok(self
+            .0
+            .lock()
+            .await
+            .values()
+            .find(|key| key.key_hash == key_hash)
+            .cloned())
+    }
+    async fn delete_api_key(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut keys = self.0.lock().await;
+        if keys.get(&id).is_none_or(|key| key.owner_id != owner) {
+            return Err(api_key_not_found());
+        }
+        keys.remove(&id);
+        Ok(id)
+    }
+}
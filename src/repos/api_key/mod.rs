@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::AppError;
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// A key for non-interactive clients. `key_hash` is the SHA-256 of the
+/// secret handed out once at creation, `prefix` its first characters.
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    /// Names of the granted [`Scope`](crate::services::auth::scope::Scope)s.
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait ApiKeyRepo: Send + Sync {
+    async fn create_api_key(&self, _key: &ApiKey) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Keys of `owner`, oldest first.
+    async fn get_api_keys(&self, _owner: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        unimplemented!()
+    }
+    async fn get_api_key_by_hash(&self, _key_hash: &str) -> Result<Option<ApiKey>, AppError> {
+        unimplemented!()
+    }
+    /// Fails with 404 unless `owner` has a key `id`.
+    async fn delete_api_key(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+}
+
+pub(crate) fn api_key_not_found() -> AppError {
+    AppError::ClientFail(
+        axum::http::StatusCode::NOT_FOUND,
+        "API key not found".to_string(),
+    )
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use sqlx::{Row, postgres::PgPool, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{ApiKey, ApiKeyRepo, api_key_not_found};
+
+#[derive(Clone)]
+pub struct PgApiKeyRepo(PgPool);
+
+impl PgApiKeyRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgApiKeyRepo(pool)
+    }
+}
+
+fn api_key_from_row(row: &PgRow) -> ApiKey {
+    ApiKey {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        name: row.get("name"),
+        prefix: row.get("prefix"),
+        key_hash: row.get("key_hash"),
+        scopes: row
+            .get::<String, _>("scopes")
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
+        expires_at: row.get("expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+const COLUMNS: &str = "id, owner_id, name, prefix, key_hash, scopes, expires_at, created_at";
+
+#[async_trait]
+impl ApiKeyRepo for PgApiKeyRepo {
+    async fn create_api_key(&self, key: &ApiKey) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO api_keys ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            COLUMNS
+        ))
+        .bind(key.id)
+        .bind(key.owner_id)
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(&key.key_hash)
+        .bind(key.scopes.join(" "))
+        .bind(key.expires_at)
+        .bind(key.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(key.id)
+    }
+
+    async fn get_api_keys(&self, owner: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE owner_id = $1 ORDER BY created_at, id",
+            COLUMNS
+        ))
+        .bind(owner)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(rows.iter().map(api_key_from_row).collect())
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE key_hash = $1",
+            COLUMNS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(row.as_ref().map(api_key_from_row))
+    }
+
+    async fn delete_api_key(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner)
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(api_key_not_found());
+        }
+        Ok(id)
+    }
+}
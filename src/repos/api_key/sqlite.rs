@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{ApiKey, ApiKeyRepo, api_key_not_found};
+
+#[derive(Clone)]
+pub struct SqliteApiKeyRepo(SqlitePool);
+
+impl SqliteApiKeyRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteApiKeyRepo(pool)
+    }
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, AppError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|_e| AppError::DatabaseError)
+}
+
+fn api_key_from_row(row: &SqliteRow) -> Result<ApiKey, AppError> {
+    let uuid = |column: &str| {
+        Uuid::parse_str(row.get::<String, _>(column).as_str()).map_err(|_e| AppError::DatabaseError)
+    };
+    Ok(ApiKey {
+        id: uuid("id")?,
+        owner_id: uuid("owner_id")?,
+        name: row.get("name"),
+        prefix: row.get("prefix"),
+        key_hash: row.get("key_hash"),
+        scopes: row
+            .get::<String, _>("scopes")
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
+        expires_at: row
+            .get::<Option<String>, _>("expires_at")
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+        created_at: parse_timestamp(&row.get::<String, _>("created_at"))?,
+    })
+}
+
+const COLUMNS: &str = "id, owner_id, name, prefix, key_hash, scopes, expires_at, created_at";
+
+#[async_trait]
+impl ApiKeyRepo for SqliteApiKeyRepo {
+    async fn create_api_key(&self, key: &ApiKey) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO api_keys ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            COLUMNS
+        ))
+        .bind(key.id.to_string())
+        .bind(key.owner_id.to_string())
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(&key.key_hash)
+        .bind(key.scopes.join(" "))
+        .bind(key.expires_at.map(|at| at.to_rfc3339()))
+        .bind(key.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(key.id)
+    }
+
+    async fn get_api_keys(&self, owner: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE owner_id = ? ORDER BY created_at, id",
+            COLUMNS
+        ))
+        .bind(owner.to_string())
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(api_key_from_row)
+        .collect()
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE key_hash = ?",
+            COLUMNS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(api_key_from_row)
+        .transpose()
+    }
+
+    async fn delete_api_key(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE id = ? AND owner_id = ?")
+            .bind(id.to_string())
+            .bind(owner.to_string())
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(api_key_not_found());
+        }
+        Ok(id)
+    }
+}
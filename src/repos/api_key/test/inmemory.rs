@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod inmemory_api_key_repo {
+    use std::sync::Arc;
+
+    use crate::repos::api_key::{
+        ApiKeyRepo, inmemory::InMemoryApiKeyRepo, test::api_key_repo_conformance,
+    };
+
+    async fn repo() -> Arc<dyn ApiKeyRepo> {
+        Arc::new(InMemoryApiKeyRepo::default())
+    }
+
+    api_key_repo_conformance!(repo());
+}
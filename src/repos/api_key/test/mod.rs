@@ -0,0 +1,134 @@
+//! Behaviour every [`ApiKeyRepo`] backend must share. Each backend module
+//! runs the whole suite through [`api_key_repo_conformance`].
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::api_key::{ApiKey, ApiKeyRepo},
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! api_key_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::api_key::test::api_key_repo_conformance!(@cases $repo;
+            created_key_should_be_found_by_hash,
+            keys_should_be_listed_per_owner_oldest_first,
+            deleted_key_should_not_be_found,
+            deleting_other_owners_key_should_be_404,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::api_key::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use api_key_repo_conformance;
+
+#[allow(dead_code)]
+fn api_key(owner: Uuid, name: &str) -> ApiKey {
+    ApiKey {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        name: name.to_string(),
+        prefix: "bks_abcd".to_string(),
+        key_hash: format!("hash-of-{}", name),
+        scopes: vec!["books:read".to_string(), "books:write".to_string()],
+        expires_at: Some(Utc::now() + Duration::days(30)),
+        created_at: Utc::now(),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn created_key_should_be_found_by_hash(repo: Arc<dyn ApiKeyRepo>) {
+    let key = api_key(Uuid::new_v4(), "importer");
+    let id = repo.create_api_key(&key).await.unwrap();
+    assert_eq!(id, key.id);
+
+    let stored = repo
+        .get_api_key_by_hash("hash-of-importer")
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(stored.id, key.id);
+    assert_eq!(stored.owner_id, key.owner_id);
+    assert_eq!(stored.name, "importer");
+    assert_eq!(stored.prefix, "bks_abcd");
+    assert_eq!(stored.scopes, key.scopes);
+    assert_eq!(
+        stored.expires_at.map(|at| at.timestamp_millis()),
+        key.expires_at.map(|at| at.timestamp_millis())
+    );
+    assert!(repo.get_api_key_by_hash("other").await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn keys_should_be_listed_per_owner_oldest_first(repo: Arc<dyn ApiKeyRepo>) {
+    let owner = Uuid::new_v4();
+    let mut older = api_key(owner, "older");
+    older.created_at = Utc::now() - Duration::days(1);
+    older.expires_at = None;
+    repo.create_api_key(&api_key(owner, "newer")).await.unwrap();
+    repo.create_api_key(&older).await.unwrap();
+    repo.create_api_key(&api_key(Uuid::new_v4(), "foreign"))
+        .await
+        .unwrap();
+
+    let keys = repo.get_api_keys(owner).await.unwrap();
+
+    let names: Vec<&str> = keys.iter().map(|key| key.name.as_str()).collect();
+    assert_eq!(names, vec!["older", "newer"]);
+    assert!(keys[0].expires_at.is_none());
+}
+
+#[allow(dead_code)]
+pub async fn deleted_key_should_not_be_found(repo: Arc<dyn ApiKeyRepo>) {
+    let key = api_key(Uuid::new_v4(), "importer");
+    repo.create_api_key(&key).await.unwrap();
+
+    let id = repo.delete_api_key(key.owner_id, key.id).await.unwrap();
+
+    assert_eq!(id, key.id);
+    assert!(
+        repo.get_api_key_by_hash(&key.key_hash)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn deleting_other_owners_key_should_be_404(repo: Arc<dyn ApiKeyRepo>) {
+    let key = api_key(Uuid::new_v4(), "importer");
+    repo.create_api_key(&key).await.unwrap();
+
+    let result = repo.delete_api_key(Uuid::new_v4(), key.id).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+    assert!(
+        repo.get_api_key_by_hash(&key.key_hash)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_api_key_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        api_key::{ApiKeyRepo, postgres::PgApiKeyRepo, test::api_key_repo_conformance},
+        test::postgres_pool,
+    };
+
+    async fn repo() -> Arc<dyn ApiKeyRepo> {
+        Arc::new(PgApiKeyRepo::new(postgres_pool().await))
+    }
+
+    api_key_repo_conformance!(repo());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_api_key_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        api_key::{ApiKeyRepo, sqlite::SqliteApiKeyRepo, test::api_key_repo_conformance},
+        test::sqlite_pool,
+    };
+
+    async fn repo() -> Arc<dyn ApiKeyRepo> {
+        Arc::new(SqliteApiKeyRepo::new(sqlite_pool().await))
+    }
+
+    api_key_repo_conformance!(repo());
+}
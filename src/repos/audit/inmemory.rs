@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{AuditEntry, AuditFilter, AuditRepo};
+
+#[derive(Default, Clone)]
+pub struct InMemoryAuditRepo(Arc<Mutex<Vec<AuditEntry>>>);
+
+#[async_trait]
+impl AuditRepo for InMemoryAuditRepo {
+    async fn record(&self, entry: &AuditEntry) -> Result<Uuid, AppError> {
+        self.0.lock().await.push(entry.clone());
+        Ok(entry.id)
+    }
+    async fn get_entries(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest,
+    ) -> Result<Page<AuditEntry>, AppError> {
+        let mut entries: Vec<AuditEntry> = self
+            .0
+            .lock()
+            .await
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        let total = entries.len() as u64;
+        let items = entries
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(page.limit as usize)
+            .collect();
+        Ok(Page { items, total })
+    }
+}
@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Book, Page, PageRequest};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// What was done to a book.
+#[derive(Serialize, Deserialize, ToSchema, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+}
+
+/// One change to a book, never altered once recorded.
+#[derive(Serialize, ToSchema, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// The user whose shelf the book is on.
+    pub owner_id: Uuid,
+    /// The user who made the change, the `sub` of their credentials.
+    pub actor_id: Uuid,
+    pub action: AuditAction,
+    pub book_id: Uuid,
+    /// Changed fields, each as `{"before": ..., "after": ...}`, see
+    /// [`book_changes`].
+    #[schema(value_type = Object)]
+    pub changes: Value,
+    /// `X-Request-Id` of the request that made the change.
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    /// An entry for `actor` going from `before` to `after`, at least one of
+    /// which is set.
+    pub fn new(
+        actor: Uuid,
+        action: AuditAction,
+        before: Option<&Book>,
+        after: Option<&Book>,
+        request_id: Option<String>,
+    ) -> Self {
+        let book = after.or(before).expect("An audited change needs a book");
+        AuditEntry {
+            id: Uuid::new_v4(),
+            owner_id: book.owner_id,
+            actor_id: actor,
+            action,
+            book_id: book.id,
+            changes: book_changes(before, after),
+            request_id,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// The fields that differ between `before` and `after` as
+/// `{"name": {"before": "A", "after": "B"}}`. A missing side reads as `null`
+/// for every field, so creating or deleting a book lists all of them.
+pub fn book_changes(before: Option<&Book>, after: Option<&Book>) -> Value {
+    let fields = |book: Option<&Book>| match book.map(serde_json::to_value) {
+        Some(Ok(Value::Object(fields))) => fields,
+        _ => Map::new(),
+    };
+    let before = fields(before);
+    let after = fields(after);
+
+    let mut changes = Map::new();
+    for name in before.keys().chain(after.keys()) {
+        let old = before.get(name).unwrap_or(&Value::Null);
+        let new = after.get(name).unwrap_or(&Value::Null);
+        if old != new && !changes.contains_key(name) {
+            changes.insert(name.clone(), json!({ "before": old, "after": new }));
+        }
+    }
+    Value::Object(changes)
+}
+
+/// Narrows [`AuditRepo::get_entries`], every field left `None` matches all
+/// entries. `since` is inclusive, `until` exclusive.
+#[derive(Default, Clone, Debug)]
+pub struct AuditFilter {
+    pub owner_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub book_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.owner_id.is_none_or(|owner| entry.owner_id == owner)
+            && self.actor_id.is_none_or(|actor| entry.actor_id == actor)
+            && self.book_id.is_none_or(|book| entry.book_id == book)
+            && self.action.is_none_or(|action| entry.action == action)
+            && self.since.is_none_or(|since| entry.created_at >= since)
+            && self.until.is_none_or(|until| entry.created_at < until)
+    }
+}
+
+/// Append-only store of [`AuditEntry`]s, kept when the books they describe
+/// are purged.
+#[async_trait]
+pub trait AuditRepo: Send + Sync {
+    async fn record(&self, _entry: &AuditEntry) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Entries matching `filter`, newest first. Only the paging of `page` is
+    /// used.
+    async fn get_entries(
+        &self,
+        _filter: &AuditFilter,
+        _page: &PageRequest,
+    ) -> Result<Page<AuditEntry>, AppError> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sqlx::{Postgres, QueryBuilder, Row, postgres::PgPool, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{AuditEntry, AuditFilter, AuditRepo};
+
+#[derive(Clone)]
+pub struct PgAuditRepo(PgPool);
+
+impl PgAuditRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgAuditRepo(pool)
+    }
+}
+
+fn entry_from_row(row: &PgRow) -> Result<AuditEntry, AppError> {
+    Ok(AuditEntry {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        actor_id: row.get("actor_id"),
+        action: row
+            .try_get("action")
+            .map_err(|_e| AppError::DatabaseError)?,
+        book_id: row.get("book_id"),
+        changes: row.get("changes"),
+        request_id: row.get("request_id"),
+        created_at: row.get("created_at"),
+    })
+}
+
+const COLUMNS: &str = "id, owner_id, actor_id, action, book_id, changes, request_id, created_at";
+
+fn push_filters(query: &mut QueryBuilder<Postgres>, filter: &AuditFilter) {
+    for (column, value) in [
+        ("owner_id", filter.owner_id),
+        ("actor_id", filter.actor_id),
+        ("book_id", filter.book_id),
+    ] {
+        if let Some(value) = value {
+            query.push(format!(" AND {} = ", column)).push_bind(value);
+        }
+    }
+    if let Some(action) = filter.action {
+        query.push(" AND action = ").push_bind(action);
+    }
+    if let Some(at) = filter.since {
+        query.push(" AND created_at >= ").push_bind(at);
+    }
+    if let Some(at) = filter.until {
+        query.push(" AND created_at < ").push_bind(at);
+    }
+}
+
+#[async_trait]
+impl AuditRepo for PgAuditRepo {
+    async fn record(&self, entry: &AuditEntry) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO audit_log ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            COLUMNS
+        ))
+        .bind(entry.id)
+        .bind(entry.owner_id)
+        .bind(entry.actor_id)
+        .bind(entry.action)
+        .bind(entry.book_id)
+        .bind(&entry.changes)
+        .bind(&entry.request_id)
+        .bind(entry.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(entry.id)
+    }
+
+    async fn get_entries(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest,
+    ) -> Result<Page<AuditEntry>, AppError> {
+        let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM audit_log WHERE TRUE");
+        push_filters(&mut count_query, filter);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        let mut query = QueryBuilder::new(format!("SELECT {} FROM audit_log WHERE TRUE", COLUMNS));
+        push_filters(&mut query, filter);
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
+
+        let items = query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(entry_from_row)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+}
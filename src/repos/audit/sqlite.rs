@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Row, Sqlite, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{AuditEntry, AuditFilter, AuditRepo};
+
+#[derive(Clone)]
+pub struct SqliteAuditRepo(SqlitePool);
+
+impl SqliteAuditRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteAuditRepo(pool)
+    }
+}
+
+fn entry_from_row(row: &SqliteRow) -> Result<AuditEntry, AppError> {
+    let uuid = |column: &str| {
+        Uuid::parse_str(row.get::<String, _>(column).as_str()).map_err(|_e| AppError::DatabaseError)
+    };
+    Ok(AuditEntry {
+        id: uuid("id")?,
+        owner_id: uuid("owner_id")?,
+        actor_id: uuid("actor_id")?,
+        action: row
+            .try_get("action")
+            .map_err(|_e| AppError::DatabaseError)?,
+        book_id: uuid("book_id")?,
+        changes: serde_json::from_str(&row.get::<String, _>("changes"))
+            .map_err(|_e| AppError::DatabaseError)?,
+        request_id: row.get("request_id"),
+        created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+            .map(|at| at.with_timezone(&Utc))
+            .map_err(|_e| AppError::DatabaseError)?,
+    })
+}
+
+const COLUMNS: &str = "id, owner_id, actor_id, action, book_id, changes, request_id, created_at";
+
+fn push_filters(query: &mut QueryBuilder<Sqlite>, filter: &AuditFilter) {
+    for (column, value) in [
+        ("owner_id", filter.owner_id),
+        ("actor_id", filter.actor_id),
+        ("book_id", filter.book_id),
+    ] {
+        if let Some(value) = value {
+            query
+                .push(format!(" AND {} = ", column))
+                .push_bind(value.to_string());
+        }
+    }
+    if let Some(action) = filter.action {
+        query.push(" AND action = ").push_bind(action);
+    }
+    // RFC 3339 text in UTC compares chronologically.
+    if let Some(at) = filter.since {
+        query.push(" AND created_at >= ").push_bind(at.to_rfc3339());
+    }
+    if let Some(at) = filter.until {
+        query.push(" AND created_at < ").push_bind(at.to_rfc3339());
+    }
+}
+
+#[async_trait]
+impl AuditRepo for SqliteAuditRepo {
+    async fn record(&self, entry: &AuditEntry) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO audit_log ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            COLUMNS
+        ))
+        .bind(entry.id.to_string())
+        .bind(entry.owner_id.to_string())
+        .bind(entry.actor_id.to_string())
+        .bind(entry.action)
+        .bind(entry.book_id.to_string())
+        .bind(entry.changes.to_string())
+        .bind(&entry.request_id)
+        .bind(entry.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(entry.id)
+    }
+
+    async fn get_entries(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest,
+    ) -> Result<Page<AuditEntry>, AppError> {
+        let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM audit_log WHERE 1 = 1");
+        push_filters(&mut count_query, filter);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        let mut query = QueryBuilder::new(format!("SELECT {} FROM audit_log WHERE 1 = 1", COLUMNS));
+        push_filters(&mut query, filter);
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
+
+        let items = query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(entry_from_row)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+}
@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod inmemory_audit_repo {
+    use std::sync::Arc;
+
+    use crate::repos::audit::{
+        AuditRepo, inmemory::InMemoryAuditRepo, test::audit_repo_conformance,
+    };
+
+    async fn repo() -> Arc<dyn AuditRepo> {
+        Arc::new(InMemoryAuditRepo::default())
+    }
+
+    audit_repo_conformance!(repo());
+}
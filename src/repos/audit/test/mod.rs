@@ -0,0 +1,220 @@
+//! Behaviour every [`AuditRepo`] backend must share. Each backend module
+//! runs the whole suite through [`audit_repo_conformance`].
+
+use std::sync::Arc;
+
+use chrono::{Duration, SubsecRound, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::repos::{
+    audit::{AuditAction, AuditEntry, AuditFilter, AuditRepo},
+    book::PageRequest,
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! audit_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::audit::test::audit_repo_conformance!(@cases $repo;
+            recorded_entry_should_be_listed_as_it_was,
+            entries_should_be_listed_newest_first,
+            entries_should_be_filtered,
+            entries_should_be_paged,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::audit::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use audit_repo_conformance;
+
+/// An entry on `book` of `owner`, made by the owner `minutes_ago`. Kept to
+/// milliseconds so every backend stores the time as it is.
+#[allow(dead_code)]
+fn entry(owner: Uuid, book: Uuid, action: AuditAction, minutes_ago: i64) -> AuditEntry {
+    AuditEntry {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        actor_id: owner,
+        action,
+        book_id: book,
+        changes: json!({ "readPage": { "before": 1, "after": 2 } }),
+        request_id: None,
+        created_at: (Utc::now() - Duration::minutes(minutes_ago)).trunc_subsecs(3),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn recorded_entry_should_be_listed_as_it_was(repo: Arc<dyn AuditRepo>) {
+    let mut recorded = entry(Uuid::new_v4(), Uuid::new_v4(), AuditAction::Update, 0);
+    recorded.actor_id = Uuid::new_v4();
+    recorded.changes = json!({
+        "name": { "before": "Buku A", "after": "Buku B" },
+        "reading": { "before": false, "after": true }
+    });
+    recorded.request_id = Some("req-1".to_string());
+
+    let id = repo.record(&recorded).await.unwrap();
+    assert_eq!(id, recorded.id);
+
+    let page = repo
+        .get_entries(&AuditFilter::default(), &PageRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(page.total, 1);
+    let stored = &page.items[0];
+    assert_eq!(stored.id, recorded.id);
+    assert_eq!(stored.owner_id, recorded.owner_id);
+    assert_eq!(stored.actor_id, recorded.actor_id);
+    assert_eq!(stored.action, AuditAction::Update);
+    assert_eq!(stored.book_id, recorded.book_id);
+    assert_eq!(stored.changes, recorded.changes);
+    assert_eq!(stored.request_id.as_deref(), Some("req-1"));
+    assert_eq!(
+        stored.created_at.timestamp_millis(),
+        recorded.created_at.timestamp_millis()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn entries_should_be_listed_newest_first(repo: Arc<dyn AuditRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    for (action, minutes_ago) in [
+        (AuditAction::Update, 5),
+        (AuditAction::Delete, 1),
+        (AuditAction::Create, 10),
+    ] {
+        repo.record(&entry(owner, book, action, minutes_ago))
+            .await
+            .unwrap();
+    }
+
+    let page = repo
+        .get_entries(&AuditFilter::default(), &PageRequest::default())
+        .await
+        .unwrap();
+
+    let actions: Vec<AuditAction> = page.items.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::Delete,
+            AuditAction::Update,
+            AuditAction::Create
+        ]
+    );
+}
+
+#[allow(dead_code)]
+pub async fn entries_should_be_filtered(repo: Arc<dyn AuditRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    let created = entry(owner, book, AuditAction::Create, 30);
+    let updated = entry(owner, book, AuditAction::Update, 20);
+    let other_book = entry(owner, Uuid::new_v4(), AuditAction::Create, 10);
+    let mut other_actor = entry(owner, book, AuditAction::Update, 5);
+    other_actor.actor_id = Uuid::new_v4();
+    let foreign = entry(Uuid::new_v4(), Uuid::new_v4(), AuditAction::Create, 1);
+    for entry in [&created, &updated, &other_book, &other_actor, &foreign] {
+        repo.record(entry).await.unwrap();
+    }
+
+    let ids = |filter: AuditFilter| {
+        let repo = repo.clone();
+        async move {
+            let page = repo
+                .get_entries(&filter, &PageRequest::default())
+                .await
+                .unwrap();
+            assert_eq!(page.total, page.items.len() as u64);
+            page.items
+                .iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<Uuid>>()
+        }
+    };
+
+    let by_owner = AuditFilter {
+        owner_id: Some(owner),
+        ..AuditFilter::default()
+    };
+    assert_eq!(
+        ids(by_owner.clone()).await,
+        vec![other_actor.id, other_book.id, updated.id, created.id]
+    );
+    let by_book = AuditFilter {
+        book_id: Some(book),
+        ..by_owner.clone()
+    };
+    assert_eq!(
+        ids(by_book.clone()).await,
+        vec![other_actor.id, updated.id, created.id]
+    );
+    let by_actor = AuditFilter {
+        actor_id: Some(owner),
+        ..by_book.clone()
+    };
+    assert_eq!(ids(by_actor).await, vec![updated.id, created.id]);
+    let by_action = AuditFilter {
+        action: Some(AuditAction::Update),
+        ..by_book.clone()
+    };
+    assert_eq!(ids(by_action).await, vec![other_actor.id, updated.id]);
+    let by_time = AuditFilter {
+        since: Some(created.created_at),
+        until: Some(other_actor.created_at),
+        ..AuditFilter::default()
+    };
+    assert_eq!(
+        ids(by_time).await,
+        vec![other_book.id, updated.id, created.id]
+    );
+}
+
+#[allow(dead_code)]
+pub async fn entries_should_be_paged(repo: Arc<dyn AuditRepo>) {
+    let owner = Uuid::new_v4();
+    for minutes_ago in 0..5 {
+        repo.record(&entry(
+            owner,
+            Uuid::new_v4(),
+            AuditAction::Create,
+            minutes_ago,
+        ))
+        .await
+        .unwrap();
+    }
+    let all = repo
+        .get_entries(&AuditFilter::default(), &PageRequest::default())
+        .await
+        .unwrap();
+
+    let page = PageRequest {
+        page: 2,
+        limit: 2,
+        ..PageRequest::default()
+    };
+    let second = repo
+        .get_entries(&AuditFilter::default(), &page)
+        .await
+        .unwrap();
+
+    assert_eq!(second.total, 5);
+    let ids: Vec<Uuid> = second.items.iter().map(|entry| entry.id).collect();
+    assert_eq!(ids, vec![all.items[2].id, all.items[3].id]);
+}
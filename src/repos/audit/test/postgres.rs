@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_audit_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        audit::{AuditRepo, postgres::PgAuditRepo, test::audit_repo_conformance},
+        test::postgres_pool,
+    };
+
+    async fn repo() -> Arc<dyn AuditRepo> {
+        Arc::new(PgAuditRepo::new(postgres_pool().await))
+    }
+
+    audit_repo_conformance!(repo());
+}
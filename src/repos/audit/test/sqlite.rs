@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_audit_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        audit::{AuditRepo, sqlite::SqliteAuditRepo, test::audit_repo_conformance},
+        test::sqlite_pool,
+    };
+
+    async fn repo() -> Arc<dyn AuditRepo> {
+        Arc::new(SqliteAuditRepo::new(sqlite_pool().await))
+    }
+
+    audit_repo_conformance!(repo());
+}
@@ -1,56 +1,376 @@
 use async_trait::async_trait;
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 use uuid::Uuid;
 
 use crate::AppError;
 
-use super::{Book, BookRepo, BookSummary};
+use super::search::{SearchIndex, search_terms, snippet};
+use super::stats::{NameCount, PeriodStats, ShelfStats, StatsRequest};
+use super::{
+    Book, BookFilter, BookRepo, BookSearchHit, BookSort, BookStream, BookSummary, BookTransaction,
+    Page, PageRequest, SortOrder, TrashedBook, version_conflict,
+};
+
+#[derive(Default)]
+struct Shelf {
+    books: HashMap<Uuid, Book>,
+    index: SearchIndex,
+    /// Deleted books with when they were deleted, kept out of `books` and
+    /// `index`.
+    trash: HashMap<Uuid, (Book, DateTime<Utc>)>,
+}
+
+impl Shelf {
+    /// Stores `book`, taking it out of the trash, and returns the version it
+    /// replaced.
+    fn put(&mut self, book: Book) -> Option<Book> {
+        self.trash.remove(&book.id);
+        self.index.insert(&book);
+        self.books.insert(book.id, book)
+    }
+
+    fn take(&mut self, id: Uuid) -> Option<Book> {
+        self.index.remove(id);
+        self.books.remove(&id)
+    }
+
+    fn get(&self, owner: Uuid, id: Uuid) -> Option<Book> {
+        self.books
+            .get(&id)
+            .filter(|book| book.owner_id == owner)
+            .cloned()
+    }
+
+    /// Stores `book` at the next version if the shelf still holds it at
+    /// `book.version`, returning the version it replaced.
+    fn update(&mut self, book: &Book) -> Result<Book, AppError> {
+        let Some(current) = self.get(book.owner_id, book.id) else {
+            let message = "Gagal memperbarui buku. Id tidak ditemukan".to_string();
+            return Err(AppError::ClientFail(StatusCode::NOT_FOUND, message));
+        };
+        if current.version != book.version {
+            return Err(version_conflict("Gagal memperbarui buku"));
+        }
+        self.put(Book {
+            version: book.version + 1,
+            ..book.clone()
+        });
+        Ok(current)
+    }
+
+    /// Trashes whatever the version unless `version` is given.
+    fn delete(&mut self, owner: Uuid, id: Uuid, version: Option<i64>) -> Result<Book, AppError> {
+        let Some(current) = self.get(owner, id) else {
+            let message = "Buku gagal dihapus. Id tidak ditemukan".to_string();
+            return Err(AppError::ClientFail(StatusCode::NOT_FOUND, message));
+        };
+        if version.is_some_and(|version| version != current.version) {
+            return Err(version_conflict("Buku gagal dihapus"));
+        }
+        let book = self.take(id).expect("book was just found");
+        self.trash.insert(id, (book.clone(), Utc::now()));
+        Ok(book)
+    }
+}
 
 #[derive(Default, Clone)]
-pub struct InMemoryBookRepo(Arc<Mutex<HashMap<Uuid, Book>>>);
+pub struct InMemoryBookRepo(Arc<Mutex<Shelf>>);
+
+/// Holds the shelf for its whole lifetime and writes straight to it,
+/// undoing the writes on drop unless committed.
+struct InMemoryBookTransaction {
+    shelf: OwnedMutexGuard<Shelf>,
+    /// Each changed book as it was before, `None` for added ones.
+    undo: Vec<(Uuid, Option<Book>)>,
+}
+
+#[async_trait]
+impl BookTransaction for InMemoryBookTransaction {
+    async fn save_book(&mut self, book: &Book) -> Result<Uuid, AppError> {
+        let previous = self.shelf.put(book.clone());
+        self.undo.push((book.id, previous));
+        Ok(book.id)
+    }
+    async fn update_book(&mut self, book: &Book) -> Result<i64, AppError> {
+        let previous = self.shelf.update(book)?;
+        self.undo.push((book.id, Some(previous)));
+        Ok(book.version + 1)
+    }
+    async fn get_book_by_id(&mut self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        Ok(self.shelf.get(owner, id))
+    }
+    async fn delete_book(&mut self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let previous = self.shelf.delete(owner, id, None)?;
+        self.undo.push((id, Some(previous)));
+        Ok(id)
+    }
+    async fn commit(mut self: Box<Self>) -> Result<(), AppError> {
+        self.undo.clear();
+        Ok(())
+    }
+}
+
+impl Drop for InMemoryBookTransaction {
+    fn drop(&mut self) {
+        while let Some((id, previous)) = self.undo.pop() {
+            match previous {
+                Some(book) => self.shelf.put(book),
+                None => self.shelf.take(id),
+            };
+        }
+    }
+}
+
+/// The `top` most frequent non-empty `names`, ties by name.
+fn top_names<'a>(names: impl Iterator<Item = &'a str>, top: u32) -> Vec<NameCount> {
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+    for name in names.filter(|name| !name.is_empty()) {
+        *counts.entry(name).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, u64)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_books), (b, b_books)| b_books.cmp(a_books).then(a.cmp(b)));
+    counts
+        .into_iter()
+        .take(top as usize)
+        .map(|(name, books)| NameCount {
+            name: name.to_string(),
+            books,
+        })
+        .collect()
+}
+
+fn summary(book: &Book) -> BookSummary {
+    BookSummary {
+        id: book.id,
+        name: book.name.clone(),
+        publisher: book.publisher.clone(),
+    }
+}
 
 #[async_trait]
 impl BookRepo for InMemoryBookRepo {
+    async fn begin(&self) -> Result<Box<dyn BookTransaction>, AppError> {
+        Ok(Box::new(InMemoryBookTransaction {
+            shelf: self.0.clone().lock_owned().await,
+            undo: Vec::new(),
+        }))
+    }
     async fn save_book(&self, book: &Book) -> Result<Uuid, AppError> {
-        self.0.lock().await.insert(book.id, book.clone());
+        self.0.lock().await.put(book.clone());
         Ok(book.id)
     }
+    async fn update_book(&self, book: &Book) -> Result<i64, AppError> {
+        self.0.lock().await.update(book)?;
+        Ok(book.version + 1)
+    }
     async fn get_books(
         &self,
-        name: Option<String>,
-        reading: Option<bool>,
-        finished: Option<bool>,
-    ) -> Result<Vec<BookSummary>, AppError> {
-        let all_books: Vec<Book> = self.0.lock().await.values().cloned().collect();
+        owner: Uuid,
+        filter: &BookFilter,
+        page: &PageRequest,
+    ) -> Result<Page<BookSummary>, AppError> {
+        let mut books: Vec<Book> = self
+            .0
+            .lock()
+            .await
+            .books
+            .values()
+            .filter(|book| book.owner_id == owner && filter.matches(book))
+            .cloned()
+            .collect();
 
-        let books = all_books
+        books.sort_by(|a, b| {
+            let ordering = match page.sort {
+                BookSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                BookSort::Year => a.year.cmp(&b.year),
+                BookSort::InsertedAt => a.inserted_at.cmp(&b.inserted_at),
+                BookSort::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            };
+            let ordering = match page.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            };
+            match ordering {
+                Ordering::Equal => a.id.cmp(&b.id),
+                ordering => ordering,
+            }
+        });
+
+        Ok(Page {
+            total: books.len() as u64,
+            items: books
+                .into_iter()
+                .skip(page.offset() as usize)
+                .take(page.limit as usize)
+                .map(|book| summary(&book))
+                .collect(),
+        })
+    }
+    async fn search_books(
+        &self,
+        owner: Uuid,
+        query: &str,
+        page: &PageRequest,
+    ) -> Result<Page<BookSearchHit>, AppError> {
+        let terms = search_terms(query);
+        let shelf = self.0.lock().await;
+        let ids: Vec<Uuid> = shelf
+            .index
+            .search(&terms)
             .into_iter()
-            .filter(|book| {
-                name.as_ref().map_or(true, |name_filter| {
-                    book.name
-                        .to_lowercase()
-                        .contains(&name_filter.to_lowercase())
-                })
-            })
-            .filter(|book| reading.map_or(true, |reading_filter| book.reading == reading_filter))
-            .filter(|book| {
-                finished.map_or(true, |finished_filter| book.finished == finished_filter)
-            });
+            .filter(|id| shelf.books[id].owner_id == owner)
+            .collect();
 
-        Ok(books
-            .map(|book| BookSummary {
-                id: book.id,
-                name: book.name.clone(),
-                publisher: book.publisher.clone(),
-            })
-            .collect())
+        Ok(Page {
+            total: ids.len() as u64,
+            items: ids
+                .iter()
+                .skip(page.offset() as usize)
+                .take(page.limit as usize)
+                .map(|id| {
+                    let book = &shelf.books[id];
+                    BookSearchHit {
+                        book: summary(book),
+                        snippet: snippet(book, &terms),
+                    }
+                })
+                .collect(),
+        })
+    }
+    async fn get_book_by_id(&self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        Ok(self.0.lock().await.get(owner, id))
     }
-    async fn get_book_by_id(&self, id: Uuid) -> Result<Option<Book>, AppError> {
-        Ok(self.0.lock().await.get(&id).cloned())
+    async fn stream_books(&self, owner: Uuid) -> Result<BookStream, AppError> {
+        let mut books: Vec<Book> = self
+            .0
+            .lock()
+            .await
+            .books
+            .values()
+            .filter(|book| book.owner_id == owner)
+            .cloned()
+            .collect();
+        books.sort_by(|a, b| a.inserted_at.cmp(&b.inserted_at).then(a.id.cmp(&b.id)));
+        Ok(Box::pin(tokio_stream::iter(books.into_iter().map(Ok))))
     }
-    async fn delete_book(&self, id: Uuid) -> Result<Uuid, AppError> {
-        self.0.lock().await.remove(&id);
+    async fn delete_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        self.0.lock().await.delete(owner, id, None)?;
         Ok(id)
     }
+    async fn delete_book_version(
+        &self,
+        owner: Uuid,
+        id: Uuid,
+        version: i64,
+    ) -> Result<Uuid, AppError> {
+        self.0.lock().await.delete(owner, id, Some(version))?;
+        Ok(id)
+    }
+    async fn get_trash(
+        &self,
+        owner: Uuid,
+        page: &PageRequest,
+    ) -> Result<Page<TrashedBook>, AppError> {
+        let shelf = self.0.lock().await;
+        let mut books: Vec<&(Book, DateTime<Utc>)> = shelf
+            .trash
+            .values()
+            .filter(|(book, _)| book.owner_id == owner)
+            .collect();
+        books.sort_by(|(a, a_deleted), (b, b_deleted)| {
+            b_deleted.cmp(a_deleted).then(a.id.cmp(&b.id))
+        });
+
+        Ok(Page {
+            total: books.len() as u64,
+            items: books
+                .into_iter()
+                .skip(page.offset() as usize)
+                .take(page.limit as usize)
+                .map(|(book, deleted_at)| TrashedBook {
+                    book: summary(book),
+                    deleted_at: *deleted_at,
+                })
+                .collect(),
+        })
+    }
+    async fn restore_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut shelf = self.0.lock().await;
+        match shelf.trash.get(&id) {
+            Some((book, _)) if book.owner_id == owner => {
+                let book = book.clone();
+                shelf.put(book);
+                Ok(id)
+            }
+            _ => {
+                let message = "Buku gagal dipulihkan. Id tidak ditemukan".to_string();
+                Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+            }
+        }
+    }
+    async fn purge_trash(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let mut shelf = self.0.lock().await;
+        let count = shelf.trash.len();
+        shelf
+            .trash
+            .retain(|_, (_, deleted_at)| *deleted_at >= before);
+        Ok((count - shelf.trash.len()) as u64)
+    }
+    async fn get_stats(&self, owner: Uuid, request: &StatsRequest) -> Result<ShelfStats, AppError> {
+        let shelf = self.0.lock().await;
+        let books: Vec<&Book> = shelf
+            .books
+            .values()
+            .filter(|book| book.owner_id == owner)
+            .collect();
+
+        let finishes: Vec<(&Book, DateTime<Utc>)> = books
+            .iter()
+            .filter(|book| book.finished)
+            .filter_map(|book| Some((*book, book.finished_at?)))
+            .collect();
+        let mut periods: BTreeMap<String, PeriodStats> = BTreeMap::new();
+        for (_, finished_at) in &finishes {
+            let period = request.period.label(*finished_at);
+            let stats = periods.entry(period.clone()).or_insert(PeriodStats {
+                period,
+                books_finished: 0,
+                pages_read: 0,
+            });
+            stats.books_finished += 1;
+        }
+
+        let completions: Vec<f64> = finishes
+            .iter()
+            .map(|(book, finished_at)| {
+                (*finished_at - book.inserted_at).num_milliseconds() as f64 / 1000.0
+            })
+            .collect();
+        let average_completion_secs = (!completions.is_empty())
+            .then(|| (completions.iter().sum::<f64>() / completions.len() as f64).round() as i64);
+
+        Ok(ShelfStats {
+            total_books: books.len() as u64,
+            finished_books: books.iter().filter(|book| book.finished).count() as u64,
+            currently_reading: books
+                .iter()
+                .filter(|book| book.reading && !book.finished)
+                .count() as u64,
+            pages_reached: books.iter().map(|book| book.read_page.max(0) as u64).sum(),
+            periods: periods.into_values().collect(),
+            average_completion_secs,
+            top_authors: top_names(books.iter().map(|book| book.author.as_str()), request.top),
+            top_publishers: top_names(
+                books.iter().map(|book| book.publisher.as_str()),
+                request.top,
+            ),
+        })
+    }
 }
@@ -1,17 +1,32 @@
+use std::pin::Pin;
+
 use async_trait::async_trait;
+use axum::http::StatusCode;
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::AppError;
 
+use self::stats::{ShelfStats, StatsRequest};
+
 pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod search;
 pub mod sqlite;
+pub mod stats;
+pub mod test;
 
 #[derive(Serialize, Clone, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct Book {
     pub id: Uuid,
+    /// The user whose shelf the book is on.
+    pub owner_id: Uuid,
     pub name: String,
     pub year: i32,
     pub author: String,
@@ -21,34 +36,293 @@ pub struct Book {
     pub read_page: i32,
     pub reading: bool,
     pub finished: bool,
+    /// When the book became finished, unset while it isn't.
+    pub finished_at: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
     pub inserted_at: DateTime<Utc>,
+    /// Starts at 1 and goes up with every update, see
+    /// [`BookRepo::update_book`].
+    pub version: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, sqlx::FromRow)]
 pub struct BookSummary {
     pub id: Uuid,
     pub name: String,
     pub publisher: String,
 }
 
+/// A `search_books` result, `snippet` is an excerpt of the best matching
+/// field with the matched words wrapped in `<mark>` tags.
+#[derive(Serialize)]
+pub struct BookSearchHit {
+    #[serde(flatten)]
+    pub book: BookSummary,
+    pub snippet: String,
+}
+
+/// A `get_trash` result, deleted at `deleted_at` and purged some time after.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedBook {
+    #[serde(flatten)]
+    pub book: BookSummary,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Criteria `get_books` matches on, unset fields match every book. Text
+/// fields are case-insensitive substrings and ranges are inclusive.
+#[derive(Default, Clone, Debug)]
+pub struct BookFilter {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub reading: Option<bool>,
+    pub finished: Option<bool>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub page_count_min: Option<i32>,
+    pub page_count_max: Option<i32>,
+    /// Strictly after.
+    pub inserted_after: Option<DateTime<Utc>>,
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Matches books finished at or after, so only finished ones.
+    pub finished_since: Option<DateTime<Utc>>,
+    /// Strictly before, matching only finished books too.
+    pub finished_before: Option<DateTime<Utc>>,
+    pub read_percent_min: Option<f64>,
+    pub read_percent_max: Option<f64>,
+}
+
+impl Book {
+    /// Share of the book read, 0 for books without pages.
+    pub fn read_percent(&self) -> f64 {
+        if self.page_count > 0 {
+            f64::from(self.read_page) * 100.0 / f64::from(self.page_count)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl BookFilter {
+    pub fn matches(&self, book: &Book) -> bool {
+        fn contains(haystack: &str, needle: &Option<String>) -> bool {
+            needle
+                .as_ref()
+                .is_none_or(|needle| haystack.to_lowercase().contains(&needle.to_lowercase()))
+        }
+
+        contains(&book.name, &self.name)
+            && contains(&book.author, &self.author)
+            && contains(&book.publisher, &self.publisher)
+            && self.reading.is_none_or(|reading| book.reading == reading)
+            && self
+                .finished
+                .is_none_or(|finished| book.finished == finished)
+            && self.year_from.is_none_or(|year| book.year >= year)
+            && self.year_to.is_none_or(|year| book.year <= year)
+            && self
+                .page_count_min
+                .is_none_or(|count| book.page_count >= count)
+            && self
+                .page_count_max
+                .is_none_or(|count| book.page_count <= count)
+            && self.inserted_after.is_none_or(|at| book.inserted_at > at)
+            && self.updated_since.is_none_or(|at| book.updated_at >= at)
+            && self
+                .finished_since
+                .is_none_or(|at| book.finished_at.is_some_and(|finished| finished >= at))
+            && self
+                .finished_before
+                .is_none_or(|at| book.finished_at.is_some_and(|finished| finished < at))
+            && self
+                .read_percent_min
+                .is_none_or(|p| book.read_percent() >= p)
+            && self
+                .read_percent_max
+                .is_none_or(|p| book.read_percent() <= p)
+    }
+}
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 20;
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Column `get_books` orders by, ties are broken by id.
+#[derive(Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BookSort {
+    Name,
+    Year,
+    #[default]
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Which slice of the matching books `get_books` returns, `page` is 1-based.
+#[derive(Clone, Copy, Debug)]
+pub struct PageRequest {
+    pub page: u32,
+    pub limit: u32,
+    pub sort: BookSort,
+    pub order: SortOrder,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        PageRequest {
+            page: 1,
+            limit: DEFAULT_PAGE_LIMIT,
+            sort: BookSort::default(),
+            order: SortOrder::default(),
+        }
+    }
+}
+
+impl PageRequest {
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page.saturating_sub(1)) * u64::from(self.limit)
+    }
+}
+
+/// One page of results along with the number of matches across all pages.
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
+/// Books handed out one at a time, ending early on the first error.
+pub type BookStream = Pin<Box<dyn Stream<Item = Result<Book, AppError>> + Send>>;
+
+/// Rows buffered ahead of a slow [`BookStream`] reader.
+const STREAM_BUFFER: usize = 64;
+
+/// Runs `produce` on its own task and streams what it sends, so the stream
+/// doesn't borrow the repository.
+pub(crate) fn spawn_book_stream<F, Fut>(produce: F) -> BookStream
+where
+    F: FnOnce(mpsc::Sender<Result<Book, AppError>>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+    tokio::spawn(produce(sender));
+    Box::pin(ReceiverStream::new(receiver))
+}
+
+/// The 412 of a write based on an outdated version of a book, `failure`
+/// opens the message.
+pub(crate) fn version_conflict(failure: &str) -> AppError {
+    let message = format!("{}. Versi buku sudah berubah", failure);
+    AppError::ClientFail(StatusCode::PRECONDITION_FAILED, message)
+}
+
+/// Writes applied all at once by [`BookTransaction::commit`], or not at all
+/// when the transaction is dropped first. Reads see the pending writes.
+#[async_trait]
+pub trait BookTransaction: Send {
+    async fn save_book(&mut self, book: &Book) -> Result<Uuid, AppError>;
+    async fn update_book(&mut self, book: &Book) -> Result<i64, AppError>;
+    async fn get_book_by_id(&mut self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError>;
+    async fn delete_book(&mut self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError>;
+    async fn commit(self: Box<Self>) -> Result<(), AppError>;
+}
+
+/// Every lookup is scoped to the shelf of `owner`, books of other users
+/// behave as if they did not exist. Deleted books go to the trash, where
+/// only the trash methods see them.
 #[async_trait]
 pub trait BookRepo: Send + Sync {
+    /// Starts a [`BookTransaction`], which may block other writers until it
+    /// ends.
+    async fn begin(&self) -> Result<Box<dyn BookTransaction>, AppError> {
+        unimplemented!()
+    }
+    /// Inserts `book`, or overwrites it whatever version is stored. Changes
+    /// to an existing book go through [`BookRepo::update_book`] instead.
     async fn save_book(&self, _book: &Book) -> Result<Uuid, AppError> {
         unimplemented!()
     }
+    /// Overwrites the stored book only while it is still at `book.version`,
+    /// returning the version it moves to. Fails with 404 if the book is gone
+    /// and with 412 if it was changed in the meantime.
+    async fn update_book(&self, _book: &Book) -> Result<i64, AppError> {
+        unimplemented!()
+    }
     async fn get_books(
         &self,
-        _name: Option<String>,
-        _reading: Option<bool>,
-        _finished: Option<bool>,
-    ) -> Result<Vec<BookSummary>, AppError> {
+        _owner: Uuid,
+        _filter: &BookFilter,
+        _page: &PageRequest,
+    ) -> Result<Page<BookSummary>, AppError> {
         unimplemented!()
     }
-    async fn get_book_by_id(&self, _id: Uuid) -> Result<Option<Book>, AppError> {
+    /// Books matching every word of `query` in their name, author or summary,
+    /// best match first. `page.sort` and `page.order` are ignored.
+    async fn search_books(
+        &self,
+        _owner: Uuid,
+        _query: &str,
+        _page: &PageRequest,
+    ) -> Result<Page<BookSearchHit>, AppError> {
         unimplemented!()
     }
-    async fn delete_book(&self, _id: Uuid) -> Result<Uuid, AppError> {
+    async fn get_book_by_id(&self, _owner: Uuid, _id: Uuid) -> Result<Option<Book>, AppError> {
+        unimplemented!()
+    }
+    /// Every book of `owner` in full, oldest first, without loading the
+    /// whole shelf at once.
+    async fn stream_books(&self, _owner: Uuid) -> Result<BookStream, AppError> {
+        unimplemented!()
+    }
+    /// Moves the book to the trash.
+    async fn delete_book(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// [`BookRepo::delete_book`] that fails with 412 unless the book is still
+    /// at `version`.
+    async fn delete_book_version(
+        &self,
+        _owner: Uuid,
+        _id: Uuid,
+        _version: i64,
+    ) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Books in the trash of `owner`, most recently deleted first.
+    /// `page.sort` and `page.order` are ignored.
+    async fn get_trash(
+        &self,
+        _owner: Uuid,
+        _page: &PageRequest,
+    ) -> Result<Page<TrashedBook>, AppError> {
+        unimplemented!()
+    }
+    /// Takes a book out of the trash as it was deleted. Fails with 404 unless
+    /// it is in the trash of `owner`.
+    async fn restore_book(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Removes for good the books of every owner trashed before `before`,
+    /// returning how many.
+    async fn purge_trash(&self, _before: DateTime<Utc>) -> Result<u64, AppError> {
+        unimplemented!()
+    }
+    /// Aggregates over the books on the shelf of `owner`, leaving out the
+    /// trash. Periods are those books were finished in, their `pages_read`
+    /// is left to [`ShelfStats::add_pages_read`].
+    async fn get_stats(
+        &self,
+        _owner: Uuid,
+        _request: &StatsRequest,
+    ) -> Result<ShelfStats, AppError> {
         unimplemented!()
     }
 }
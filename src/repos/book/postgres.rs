@@ -0,0 +1,593 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, Postgres, QueryBuilder, Row, Transaction, postgres::PgPool};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::search::{
+    HIGHLIGHT_END, HIGHLIGHT_START, SNIPPET_ELLIPSIS, SNIPPET_TOKENS, search_terms,
+};
+use super::stats::{NameCount, PeriodStats, ShelfStats, StatsPeriod, StatsRequest};
+use super::{
+    Book, BookFilter, BookRepo, BookSearchHit, BookSort, BookStream, BookSummary, BookTransaction,
+    Page, PageRequest, SortOrder, TrashedBook, spawn_book_stream, version_conflict,
+};
+
+#[derive(Clone)]
+pub struct PgBookRepo(PgPool);
+
+impl PgBookRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgBookRepo(pool)
+    }
+}
+
+const READ_PERCENT: &str =
+    "(CASE WHEN page_count > 0 THEN read_page * 100.0 / page_count ELSE 0 END)";
+
+/// The `top` most frequent non-empty values of `column`, ties by value in
+/// byte order like the other backends.
+async fn top_names(
+    pool: &PgPool,
+    owner: Uuid,
+    column: &str,
+    top: u32,
+) -> Result<Vec<NameCount>, AppError> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {column} AS name, COUNT(*) AS books FROM books
+        WHERE owner_id = $1 AND deleted_at IS NULL AND {column} <> ''
+        GROUP BY {column}
+        ORDER BY books DESC, {column} COLLATE "C" ASC
+        LIMIT $2
+        "#
+    ))
+    .bind(owner)
+    .bind(i64::from(top))
+    .fetch_all(pool)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    Ok(rows
+        .iter()
+        .map(|row| NameCount {
+            name: row.get("name"),
+            books: row.get::<i64, _>("books") as u64,
+        })
+        .collect())
+}
+
+fn push_filters(query: &mut QueryBuilder<Postgres>, filter: &BookFilter) {
+    for (column, value) in [
+        ("name", &filter.name),
+        ("author", &filter.author),
+        ("publisher", &filter.publisher),
+    ] {
+        if let Some(value) = value {
+            query
+                .push(format!(" AND {} ILIKE ", column))
+                .push_bind(format!("%{}%", value));
+        }
+    }
+    if let Some(reading) = filter.reading {
+        query.push(" AND reading = ").push_bind(reading);
+    }
+    if let Some(finished) = filter.finished {
+        query.push(" AND finished = ").push_bind(finished);
+    }
+    if let Some(year) = filter.year_from {
+        query.push(" AND year >= ").push_bind(year);
+    }
+    if let Some(year) = filter.year_to {
+        query.push(" AND year <= ").push_bind(year);
+    }
+    if let Some(count) = filter.page_count_min {
+        query.push(" AND page_count >= ").push_bind(count);
+    }
+    if let Some(count) = filter.page_count_max {
+        query.push(" AND page_count <= ").push_bind(count);
+    }
+    if let Some(at) = filter.inserted_after {
+        query.push(" AND inserted_at > ").push_bind(at);
+    }
+    if let Some(at) = filter.updated_since {
+        query.push(" AND updated_at >= ").push_bind(at);
+    }
+    if let Some(at) = filter.finished_since {
+        query.push(" AND finished_at >= ").push_bind(at);
+    }
+    if let Some(at) = filter.finished_before {
+        query.push(" AND finished_at < ").push_bind(at);
+    }
+    if let Some(percent) = filter.read_percent_min {
+        query
+            .push(format!(" AND {} >= ", READ_PERCENT))
+            .push_bind(percent);
+    }
+    if let Some(percent) = filter.read_percent_max {
+        query
+            .push(format!(" AND {} <= ", READ_PERCENT))
+            .push_bind(percent);
+    }
+}
+
+async fn save_book<'e>(executor: impl PgExecutor<'e>, book: &Book) -> Result<Uuid, AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO books
+        (id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        ON CONFLICT (id) DO UPDATE SET
+            name = EXCLUDED.name,
+            year = EXCLUDED.year,
+            author = EXCLUDED.author,
+            summary = EXCLUDED.summary,
+            publisher = EXCLUDED.publisher,
+            page_count = EXCLUDED.page_count,
+            read_page = EXCLUDED.read_page,
+            reading = EXCLUDED.reading,
+            finished = EXCLUDED.finished,
+            finished_at = EXCLUDED.finished_at,
+            updated_at = EXCLUDED.updated_at,
+            inserted_at = EXCLUDED.inserted_at,
+            version = EXCLUDED.version,
+            deleted_at = NULL
+        WHERE books.owner_id = EXCLUDED.owner_id
+        "#,
+    )
+    .bind(book.id)
+    .bind(book.owner_id)
+    .bind(&book.name)
+    .bind(book.year)
+    .bind(&book.author)
+    .bind(&book.summary)
+    .bind(&book.publisher)
+    .bind(book.page_count)
+    .bind(book.read_page)
+    .bind(book.reading)
+    .bind(book.finished)
+    .bind(book.finished_at)
+    .bind(book.updated_at)
+    .bind(book.inserted_at)
+    .bind(book.version)
+    .execute(executor)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    Ok(book.id)
+}
+
+async fn get_book_by_id<'e>(
+    executor: impl PgExecutor<'e>,
+    owner: Uuid,
+    id: Uuid,
+) -> Result<Option<Book>, AppError> {
+    sqlx::query_as::<_, Book>(
+        r#"
+        SELECT id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version
+        FROM books WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_optional(executor)
+    .await
+    .map_err(|_e| AppError::DatabaseError)
+}
+
+/// Tells a write that matched no row because of its version from one whose
+/// book is gone.
+async fn book_exists(conn: &mut PgConnection, owner: Uuid, id: Uuid) -> Result<bool, AppError> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM books WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL)",
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_one(conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)
+}
+
+async fn update_book(conn: &mut PgConnection, book: &Book) -> Result<i64, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE books SET
+            name = $1, year = $2, author = $3, summary = $4, publisher = $5, page_count = $6,
+            read_page = $7, reading = $8, finished = $9, finished_at = $10, updated_at = $11,
+            version = version + 1
+        WHERE id = $12 AND owner_id = $13 AND version = $14 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(&book.name)
+    .bind(book.year)
+    .bind(&book.author)
+    .bind(&book.summary)
+    .bind(&book.publisher)
+    .bind(book.page_count)
+    .bind(book.read_page)
+    .bind(book.reading)
+    .bind(book.finished)
+    .bind(book.finished_at)
+    .bind(book.updated_at)
+    .bind(book.id)
+    .bind(book.owner_id)
+    .bind(book.version)
+    .execute(&mut *conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    if result.rows_affected() > 0 {
+        Ok(book.version + 1)
+    } else if book_exists(conn, book.owner_id, book.id).await? {
+        Err(version_conflict("Gagal memperbarui buku"))
+    } else {
+        let message = "Gagal memperbarui buku. Id tidak ditemukan".to_string();
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+    }
+}
+
+/// Trashes whatever the version unless `version` is given.
+async fn delete_book(
+    conn: &mut PgConnection,
+    owner: Uuid,
+    id: Uuid,
+    version: Option<i64>,
+) -> Result<Uuid, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE books SET deleted_at = now()
+        WHERE id = $1 AND owner_id = $2 AND version = COALESCE($3, version) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(version)
+    .execute(&mut *conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    if result.rows_affected() > 0 {
+        Ok(id)
+    } else if version.is_some() && book_exists(conn, owner, id).await? {
+        Err(version_conflict("Buku gagal dihapus"))
+    } else {
+        let message = "Buku gagal dihapus. Id tidak ditemukan".to_string();
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+    }
+}
+
+/// Rolls back when dropped without [`BookTransaction::commit`].
+struct PgBookTransaction(Transaction<'static, Postgres>);
+
+#[async_trait]
+impl BookTransaction for PgBookTransaction {
+    async fn save_book(&mut self, book: &Book) -> Result<Uuid, AppError> {
+        save_book(&mut *self.0, book).await
+    }
+    async fn update_book(&mut self, book: &Book) -> Result<i64, AppError> {
+        update_book(&mut self.0, book).await
+    }
+    async fn get_book_by_id(&mut self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        get_book_by_id(&mut *self.0, owner, id).await
+    }
+    async fn delete_book(&mut self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        delete_book(&mut self.0, owner, id, None).await
+    }
+    async fn commit(self: Box<Self>) -> Result<(), AppError> {
+        self.0.commit().await.map_err(|_e| AppError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl BookRepo for PgBookRepo {
+    async fn begin(&self) -> Result<Box<dyn BookTransaction>, AppError> {
+        let transaction = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        Ok(Box::new(PgBookTransaction(transaction)))
+    }
+
+    async fn save_book(&self, book: &Book) -> Result<Uuid, AppError> {
+        save_book(&self.0, book).await
+    }
+
+    async fn update_book(&self, book: &Book) -> Result<i64, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        update_book(&mut conn, book).await
+    }
+
+    async fn get_books(
+        &self,
+        owner: Uuid,
+        filter: &BookFilter,
+        page: &PageRequest,
+    ) -> Result<Page<BookSummary>, AppError> {
+        let mut count_query = QueryBuilder::new(
+            "SELECT COUNT(*) FROM books WHERE deleted_at IS NULL AND owner_id = ",
+        );
+        count_query.push_bind(owner);
+        push_filters(&mut count_query, filter);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        let mut query = QueryBuilder::new(
+            "SELECT id, name, publisher FROM books WHERE deleted_at IS NULL AND owner_id = ",
+        );
+        query.push_bind(owner);
+        push_filters(&mut query, filter);
+        let column = match page.sort {
+            BookSort::Name => "LOWER(name)",
+            BookSort::Year => "year",
+            BookSort::InsertedAt => "inserted_at",
+            BookSort::UpdatedAt => "updated_at",
+        };
+        let direction = match page.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        query
+            .push(format!(" ORDER BY {} {}, id ASC", column, direction))
+            .push(" LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
+
+        let items = query
+            .build_query_as::<BookSummary>()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+
+    async fn search_books(
+        &self,
+        owner: Uuid,
+        query: &str,
+        page: &PageRequest,
+    ) -> Result<Page<BookSearchHit>, AppError> {
+        let terms = search_terms(query).join(" ");
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM books WHERE search @@ plainto_tsquery('simple', $1) AND owner_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(&terms)
+        .bind(owner)
+        .fetch_one(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let options = format!(
+            r#"StartSel="{}", StopSel="{}", MaxFragments=1, MaxWords={}, MinWords={}, FragmentDelimiter="{}""#,
+            HIGHLIGHT_START,
+            HIGHLIGHT_END,
+            SNIPPET_TOKENS,
+            SNIPPET_TOKENS / 2,
+            SNIPPET_ELLIPSIS
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, publisher,
+                ts_headline('simple', concat_ws(' ', name, author, summary), query, $2) AS snippet
+            FROM books, plainto_tsquery('simple', $1) AS query
+            WHERE search @@ query AND owner_id = $3 AND deleted_at IS NULL
+            ORDER BY ts_rank(search, query) DESC, id ASC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(&terms)
+        .bind(options)
+        .bind(owner)
+        .bind(i64::from(page.limit))
+        .bind(page.offset() as i64)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(Page {
+            items: rows
+                .iter()
+                .map(|row| BookSearchHit {
+                    book: BookSummary {
+                        id: row.get("id"),
+                        name: row.get("name"),
+                        publisher: row.get("publisher"),
+                    },
+                    snippet: row.get("snippet"),
+                })
+                .collect(),
+            total: total as u64,
+        })
+    }
+
+    async fn get_book_by_id(&self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        get_book_by_id(&self.0, owner, id).await
+    }
+
+    async fn stream_books(&self, owner: Uuid) -> Result<BookStream, AppError> {
+        let pool = self.0.clone();
+        Ok(spawn_book_stream(move |sender| async move {
+            let mut rows = sqlx::query_as::<_, Book>(
+                r#"
+                SELECT id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version
+                FROM books WHERE owner_id = $1 AND deleted_at IS NULL ORDER BY inserted_at, id
+                "#,
+            )
+            .bind(owner)
+            .fetch(&pool);
+            while let Some(book) = rows.next().await {
+                let book = book.map_err(|_e| AppError::DatabaseError);
+                let failed = book.is_err();
+                if sender.send(book).await.is_err() || failed {
+                    break;
+                }
+            }
+        }))
+    }
+
+    async fn delete_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        delete_book(&mut conn, owner, id, None).await
+    }
+
+    async fn delete_book_version(
+        &self,
+        owner: Uuid,
+        id: Uuid,
+        version: i64,
+    ) -> Result<Uuid, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        delete_book(&mut conn, owner, id, Some(version)).await
+    }
+
+    async fn get_trash(
+        &self,
+        owner: Uuid,
+        page: &PageRequest,
+    ) -> Result<Page<TrashedBook>, AppError> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM books WHERE owner_id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(owner)
+        .fetch_one(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, publisher, deleted_at FROM books
+            WHERE owner_id = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(owner)
+        .bind(i64::from(page.limit))
+        .bind(page.offset() as i64)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(Page {
+            items: rows
+                .iter()
+                .map(|row| TrashedBook {
+                    book: BookSummary {
+                        id: row.get("id"),
+                        name: row.get("name"),
+                        publisher: row.get("publisher"),
+                    },
+                    deleted_at: row.get("deleted_at"),
+                })
+                .collect(),
+            total: total as u64,
+        })
+    }
+
+    async fn restore_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query(
+            "UPDATE books SET deleted_at = NULL WHERE id = $1 AND owner_id = $2 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .bind(owner)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        if result.rows_affected() == 0 {
+            let message = "Buku gagal dipulihkan. Id tidak ditemukan".to_string();
+            Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+        } else {
+            Ok(id)
+        }
+    }
+
+    async fn purge_trash(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result =
+            sqlx::query("DELETE FROM books WHERE deleted_at IS NOT NULL AND deleted_at < $1")
+                .bind(before)
+                .execute(&self.0)
+                .await
+                .map_err(|_e| AppError::DatabaseError)?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_stats(&self, owner: Uuid, request: &StatsRequest) -> Result<ShelfStats, AppError> {
+        let totals = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE finished) AS finished,
+                COUNT(*) FILTER (WHERE reading AND NOT finished) AS reading,
+                COALESCE(SUM(GREATEST(read_page, 0)), 0)::BIGINT AS pages,
+                ROUND(AVG(EXTRACT(EPOCH FROM finished_at - inserted_at))
+                    FILTER (WHERE finished))::FLOAT8 AS completion
+            FROM books WHERE owner_id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(owner)
+        .fetch_one(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let format = match request.period {
+            StatsPeriod::Month => "YYYY-MM",
+            StatsPeriod::Year => "YYYY",
+        };
+        let periods = sqlx::query(
+            r#"
+            SELECT to_char(finished_at AT TIME ZONE 'UTC', $1) AS period, COUNT(*) AS finished
+            FROM books
+            WHERE owner_id = $2 AND deleted_at IS NULL AND finished AND finished_at IS NOT NULL
+            GROUP BY period
+            ORDER BY period ASC
+            "#,
+        )
+        .bind(format)
+        .bind(owner)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(ShelfStats {
+            total_books: totals.get::<i64, _>("total") as u64,
+            finished_books: totals.get::<i64, _>("finished") as u64,
+            currently_reading: totals.get::<i64, _>("reading") as u64,
+            pages_reached: totals.get::<i64, _>("pages") as u64,
+            periods: periods
+                .iter()
+                .map(|row| PeriodStats {
+                    period: row.get("period"),
+                    books_finished: row.get::<i64, _>("finished") as u64,
+                    pages_read: 0,
+                })
+                .collect(),
+            average_completion_secs: totals
+                .get::<Option<f64>, _>("completion")
+                .map(|secs| secs as i64),
+            top_authors: top_names(&self.0, owner, "author", request.top).await?,
+            top_publishers: top_names(&self.0, owner, "publisher", request.top).await?,
+        })
+    }
+}
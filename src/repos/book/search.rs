@@ -0,0 +1,220 @@
+//! Tokenizing and ranking shared by the search implementations. SQL backends
+//! only use [`search_terms`], the in-memory one keeps a [`SearchIndex`].
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use uuid::Uuid;
+
+use super::Book;
+
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+pub const SNIPPET_ELLIPSIS: &str = "…";
+/// Tokens per snippet.
+pub const SNIPPET_TOKENS: usize = 10;
+
+/// Relative weight of a hit in name, author and summary.
+const FIELD_WEIGHTS: [f64; 3] = [10.0, 5.0, 1.0];
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Byte ranges of the alphanumeric runs in `text`.
+fn token_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    token_spans(text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_lowercase())
+}
+
+/// Lowercased, deduplicated words of a search query. Every term has to match
+/// for a book to be found. Punctuation is dropped so user input can never
+/// form query syntax.
+pub fn search_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for token in tokens(query) {
+        if !terms.contains(&token) {
+            terms.push(token);
+        }
+    }
+    terms
+}
+
+fn fields(book: &Book) -> [&str; 3] {
+    [&book.name, &book.author, &book.summary]
+}
+
+struct Document {
+    lengths: [usize; 3],
+    terms: Vec<String>,
+}
+
+/// Inverted index over name, author and summary, ranked with BM25.
+#[derive(Default)]
+pub struct SearchIndex {
+    /// Term to the books containing it and its count per field.
+    postings: HashMap<String, HashMap<Uuid, [usize; 3]>>,
+    documents: HashMap<Uuid, Document>,
+}
+
+impl SearchIndex {
+    /// Indexes `book`, replacing any previous version of it.
+    pub fn insert(&mut self, book: &Book) {
+        self.remove(book.id);
+
+        let mut lengths = [0; 3];
+        let mut terms = Vec::new();
+        for (field, text) in fields(book).into_iter().enumerate() {
+            for token in tokens(text) {
+                lengths[field] += 1;
+                let counts = self
+                    .postings
+                    .entry(token.clone())
+                    .or_default()
+                    .entry(book.id)
+                    .or_default();
+                counts[field] += 1;
+                if !terms.contains(&token) {
+                    terms.push(token);
+                }
+            }
+        }
+        self.documents.insert(book.id, Document { lengths, terms });
+    }
+
+    pub fn remove(&mut self, id: Uuid) {
+        let Some(document) = self.documents.remove(&id) else {
+            return;
+        };
+        for term in document.terms {
+            if let Some(books) = self.postings.get_mut(&term) {
+                books.remove(&id);
+                if books.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Books containing every term, best match first, ties broken by id.
+    pub fn search(&self, terms: &[String]) -> Vec<Uuid> {
+        let Some((first, rest)) = terms.split_first() else {
+            return Vec::new();
+        };
+        let Some(candidates) = self.postings.get(first) else {
+            return Vec::new();
+        };
+
+        let total = self.documents.len() as f64;
+        let average = self
+            .documents
+            .values()
+            .map(|d| d.lengths.iter().sum::<usize>() as f64)
+            .sum::<f64>()
+            / total;
+
+        let mut scored: Vec<(Uuid, f64)> = candidates
+            .keys()
+            .filter(|id| {
+                rest.iter()
+                    .all(|term| self.postings.get(term).is_some_and(|b| b.contains_key(id)))
+            })
+            .map(|id| {
+                let length = self.documents[id].lengths.iter().sum::<usize>() as f64;
+                let score = terms
+                    .iter()
+                    .map(|term| {
+                        let books = &self.postings[term];
+                        let matching = books.len() as f64;
+                        let idf = ((total - matching + 0.5) / (matching + 0.5)).ln().max(1e-6);
+                        let frequency: f64 = books[id]
+                            .iter()
+                            .zip(FIELD_WEIGHTS)
+                            .map(|(&count, weight)| count as f64 * weight)
+                            .sum();
+                        idf * frequency * (K1 + 1.0)
+                            / (frequency + K1 * (1.0 - B + B * length / average))
+                    })
+                    .sum();
+                (*id, score)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.into_iter().map(|(id, _)| id).collect()
+    }
+}
+
+/// Excerpt of the field with the most hits, matched words wrapped in
+/// [`HIGHLIGHT_START`] and [`HIGHLIGHT_END`].
+pub fn snippet(book: &Book, terms: &[String]) -> String {
+    let is_hit = |text: &str, (start, end): (usize, usize)| {
+        let token = text[start..end].to_lowercase();
+        terms.contains(&token)
+    };
+
+    let (_, text, spans) = fields(book)
+        .into_iter()
+        .enumerate()
+        .map(|(field, text)| (field, text, token_spans(text)))
+        .max_by_key(|(field, text, spans)| {
+            let hits = spans.iter().filter(|span| is_hit(text, **span)).count();
+            // Prefer the earliest field on ties.
+            (hits, Reverse(*field))
+        })
+        .expect("books have text fields");
+
+    let first_hit = spans
+        .iter()
+        .position(|span| is_hit(text, *span))
+        .unwrap_or(0);
+    let start = first_hit
+        .saturating_sub(2)
+        .min(spans.len().saturating_sub(SNIPPET_TOKENS));
+    let end = (start + SNIPPET_TOKENS).min(spans.len());
+    let window = &spans[start..end];
+
+    let Some((&(from, _), &(_, to))) = window.first().zip(window.last()) else {
+        return text.to_string();
+    };
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push_str(SNIPPET_ELLIPSIS);
+    }
+    let mut cursor = from;
+    for &(token_start, token_end) in window {
+        snippet.push_str(&text[cursor..token_start]);
+        if is_hit(text, (token_start, token_end)) {
+            snippet.push_str(HIGHLIGHT_START);
+            snippet.push_str(&text[token_start..token_end]);
+            snippet.push_str(HIGHLIGHT_END);
+        } else {
+            snippet.push_str(&text[token_start..token_end]);
+        }
+        cursor = token_end;
+    }
+    if end < spans.len() {
+        snippet.push_str(SNIPPET_ELLIPSIS);
+    } else {
+        snippet.push_str(&text[to..]);
+    }
+    snippet
+}
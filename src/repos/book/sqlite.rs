@@ -1,112 +1,372 @@
 use async_trait::async_trait;
 use axum::http::StatusCode;
 use chrono::{DateTime, Utc};
-use sqlx::{Row, sqlite::SqlitePool};
-use std::ops::DerefMut;
+use sqlx::{
+    QueryBuilder, Row, Sqlite, SqliteConnection, SqliteExecutor, Transaction, sqlite::SqlitePool,
+    sqlite::SqliteRow,
+};
+use tokio_stream::StreamExt;
 use uuid::Uuid;
 
 use crate::AppError;
 
-use super::{Book, BookRepo, BookSummary};
+use super::search::{
+    HIGHLIGHT_END, HIGHLIGHT_START, SNIPPET_ELLIPSIS, SNIPPET_TOKENS, search_terms,
+};
+use super::stats::{NameCount, PeriodStats, ShelfStats, StatsPeriod, StatsRequest};
+use super::{
+    Book, BookFilter, BookRepo, BookSearchHit, BookSort, BookStream, BookSummary, BookTransaction,
+    Page, PageRequest, SortOrder, TrashedBook, spawn_book_stream, version_conflict,
+};
 #[derive(Clone)]
 pub struct SqliteBookRepo(SqlitePool);
 
 impl SqliteBookRepo {
-    pub async fn new(url: String) -> Self {
-        let pool = SqlitePool::connect_lazy(&url).expect("Failed to create SQLite pool");
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteBookRepo(pool)
+    }
+}
 
-        let mut conn = pool
-            .acquire()
-            .await
-            .expect("Failed to acquire connection for migration");
+const READ_PERCENT: &str =
+    "(CASE WHEN page_count > 0 THEN read_page * 100.0 / page_count ELSE 0 END)";
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS books (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                year INTEGER NOT NULL,
-                author TEXT NOT NULL,
-                summary TEXT NOT NULL,
-                publisher TEXT NOT NULL,
-                page_count INTEGER NOT NULL,
-                read_page INTEGER NOT NULL,
-                reading BOOLEAN NOT NULL,
-                finished BOOLEAN NOT NULL,
-                updated_at TEXT NOT NULL,
-                inserted_at TEXT NOT NULL
-            );
-            "#,
-        )
-        .execute(conn.deref_mut())
-        .await
-        .expect("Failed to initialize database");
+fn push_filters(query: &mut QueryBuilder<Sqlite>, filter: &BookFilter) {
+    // LIKE is case-insensitive for ASCII in SQLite.
+    for (column, value) in [
+        ("name", &filter.name),
+        ("author", &filter.author),
+        ("publisher", &filter.publisher),
+    ] {
+        if let Some(value) = value {
+            query
+                .push(format!(" AND {} LIKE ", column))
+                .push_bind(format!("%{}%", value));
+        }
+    }
+    if let Some(reading_val) = filter.reading {
+        query.push(" AND reading = ").push_bind(reading_val);
+    }
+    if let Some(finished_val) = filter.finished {
+        query.push(" AND finished = ").push_bind(finished_val);
+    }
+    if let Some(year) = filter.year_from {
+        query.push(" AND year >= ").push_bind(year);
+    }
+    if let Some(year) = filter.year_to {
+        query.push(" AND year <= ").push_bind(year);
+    }
+    if let Some(count) = filter.page_count_min {
+        query.push(" AND page_count >= ").push_bind(count);
+    }
+    if let Some(count) = filter.page_count_max {
+        query.push(" AND page_count <= ").push_bind(count);
+    }
+    // RFC 3339 text in UTC compares chronologically.
+    if let Some(at) = filter.inserted_after {
+        query.push(" AND inserted_at > ").push_bind(at.to_rfc3339());
+    }
+    if let Some(at) = filter.updated_since {
+        query.push(" AND updated_at >= ").push_bind(at.to_rfc3339());
+    }
+    if let Some(at) = filter.finished_since {
+        query
+            .push(" AND finished_at >= ")
+            .push_bind(at.to_rfc3339());
+    }
+    if let Some(at) = filter.finished_before {
+        query.push(" AND finished_at < ").push_bind(at.to_rfc3339());
+    }
+    if let Some(percent) = filter.read_percent_min {
+        query
+            .push(format!(" AND {} >= ", READ_PERCENT))
+            .push_bind(percent);
+    }
+    if let Some(percent) = filter.read_percent_max {
+        query
+            .push(format!(" AND {} <= ", READ_PERCENT))
+            .push_bind(percent);
+    }
+}
 
-        SqliteBookRepo(pool)
+/// The `top` most frequent non-empty values of `column`, ties by value.
+async fn top_names(
+    pool: &SqlitePool,
+    owner: Uuid,
+    column: &str,
+    top: u32,
+) -> Result<Vec<NameCount>, AppError> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {column} AS name, COUNT(*) AS books FROM books
+        WHERE owner_id = ? AND deleted_at IS NULL AND {column} <> ''
+        GROUP BY {column}
+        ORDER BY books DESC, {column} ASC
+        LIMIT ?
+        "#
+    ))
+    .bind(owner.to_string())
+    .bind(i64::from(top))
+    .fetch_all(pool)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    Ok(rows
+        .iter()
+        .map(|row| NameCount {
+            name: row.get("name"),
+            books: row.get::<i64, _>("books") as u64,
+        })
+        .collect())
+}
+
+fn book_from_row(row: &SqliteRow) -> Result<Book, AppError> {
+    Ok(Book {
+        id: Uuid::parse_str(row.get::<String, _>("id").as_str())
+            .map_err(|_e| AppError::DatabaseError)?,
+        owner_id: Uuid::parse_str(row.get::<String, _>("owner_id").as_str())
+            .map_err(|_e| AppError::DatabaseError)?,
+        name: row.get("name"),
+        year: row.get("year"),
+        author: row.get("author"),
+        summary: row.get("summary"),
+        publisher: row.get("publisher"),
+        page_count: row.get("page_count"),
+        read_page: row.get("read_page"),
+        reading: row.get("reading"),
+        finished: row.get("finished"),
+        finished_at: row
+            .get::<Option<String>, _>("finished_at")
+            .map(|at| DateTime::parse_from_rfc3339(&at).map(|at| at.with_timezone(&Utc)))
+            .transpose()
+            .map_err(|_e| AppError::DatabaseError)?,
+        updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))
+            .map_err(|_e| AppError::DatabaseError)?
+            .with_timezone(&Utc),
+        inserted_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("inserted_at"))
+            .map_err(|_e| AppError::DatabaseError)?
+            .with_timezone(&Utc),
+        version: row.get("version"),
+    })
+}
+
+async fn save_book<'e>(executor: impl SqliteExecutor<'e>, book: &Book) -> Result<Uuid, AppError> {
+    sqlx::query(
+        r#"
+        INSERT OR REPLACE INTO books 
+        (id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(book.id.to_string())
+    .bind(book.owner_id.to_string())
+    .bind(&book.name)
+    .bind(book.year)
+    .bind(&book.author)
+    .bind(&book.summary)
+    .bind(&book.publisher)
+    .bind(book.page_count)
+    .bind(book.read_page)
+    .bind(book.reading)
+    .bind(book.finished)
+    .bind(book.finished_at.map(|at| at.to_rfc3339()))
+    .bind(book.updated_at.to_rfc3339())
+    .bind(book.inserted_at.to_rfc3339())
+    .bind(book.version)
+    .execute(executor)
+    .await.map_err(|_e| AppError::DatabaseError)?;
+
+    Ok(book.id)
+}
+
+async fn get_book_by_id<'e>(
+    executor: impl SqliteExecutor<'e>,
+    owner: Uuid,
+    id: Uuid,
+) -> Result<Option<Book>, AppError> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version
+        FROM books WHERE id = ? AND owner_id = ? AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id.to_string())
+    .bind(owner.to_string())
+    .fetch_optional(executor)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+    row.as_ref().map(book_from_row).transpose()
+}
+
+/// Tells a write that matched no row because of its version from one whose
+/// book is gone.
+async fn book_exists(conn: &mut SqliteConnection, owner: Uuid, id: Uuid) -> Result<bool, AppError> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM books WHERE id = ? AND owner_id = ? AND deleted_at IS NULL",
+    )
+    .bind(id.to_string())
+    .bind(owner.to_string())
+    .fetch_one(conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+    Ok(count > 0)
+}
+
+async fn update_book(conn: &mut SqliteConnection, book: &Book) -> Result<i64, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE books SET
+            name = ?, year = ?, author = ?, summary = ?, publisher = ?, page_count = ?,
+            read_page = ?, reading = ?, finished = ?, finished_at = ?, updated_at = ?,
+            version = version + 1
+        WHERE id = ? AND owner_id = ? AND version = ? AND deleted_at IS NULL
+        "#,
+    )
+    .bind(&book.name)
+    .bind(book.year)
+    .bind(&book.author)
+    .bind(&book.summary)
+    .bind(&book.publisher)
+    .bind(book.page_count)
+    .bind(book.read_page)
+    .bind(book.reading)
+    .bind(book.finished)
+    .bind(book.finished_at.map(|at| at.to_rfc3339()))
+    .bind(book.updated_at.to_rfc3339())
+    .bind(book.id.to_string())
+    .bind(book.owner_id.to_string())
+    .bind(book.version)
+    .execute(&mut *conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    if result.rows_affected() > 0 {
+        Ok(book.version + 1)
+    } else if book_exists(conn, book.owner_id, book.id).await? {
+        Err(version_conflict("Gagal memperbarui buku"))
+    } else {
+        let message = "Gagal memperbarui buku. Id tidak ditemukan".to_string();
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+    }
+}
+
+/// Trashes whatever the version unless `version` is given.
+async fn delete_book(
+    conn: &mut SqliteConnection,
+    owner: Uuid,
+    id: Uuid,
+    version: Option<i64>,
+) -> Result<Uuid, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE books SET deleted_at = ?
+        WHERE id = ? AND owner_id = ? AND version = COALESCE(?, version) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(Utc::now().to_rfc3339())
+    .bind(id.to_string())
+    .bind(owner.to_string())
+    .bind(version)
+    .execute(&mut *conn)
+    .await
+    .map_err(|_e| AppError::DatabaseError)?;
+
+    if result.rows_affected() > 0 {
+        Ok(id)
+    } else if version.is_some() && book_exists(conn, owner, id).await? {
+        Err(version_conflict("Buku gagal dihapus"))
+    } else {
+        let message = "Buku gagal dihapus. Id tidak ditemukan".to_string();
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
+    }
+}
+
+/// Rolls back when dropped without [`BookTransaction::commit`].
+struct SqliteBookTransaction(Transaction<'static, Sqlite>);
+
+#[async_trait]
+impl BookTransaction for SqliteBookTransaction {
+    async fn save_book(&mut self, book: &Book) -> Result<Uuid, AppError> {
+        save_book(&mut *self.0, book).await
+    }
+    async fn update_book(&mut self, book: &Book) -> Result<i64, AppError> {
+        update_book(&mut self.0, book).await
+    }
+    async fn get_book_by_id(&mut self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        get_book_by_id(&mut *self.0, owner, id).await
+    }
+    async fn delete_book(&mut self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        delete_book(&mut self.0, owner, id, None).await
+    }
+    async fn commit(self: Box<Self>) -> Result<(), AppError> {
+        self.0.commit().await.map_err(|_e| AppError::DatabaseError)
     }
 }
 
 #[async_trait]
 impl BookRepo for SqliteBookRepo {
+    async fn begin(&self) -> Result<Box<dyn BookTransaction>, AppError> {
+        let transaction = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        Ok(Box::new(SqliteBookTransaction(transaction)))
+    }
+
     async fn save_book(&self, book: &super::Book) -> Result<Uuid, AppError> {
-        let pool = &self.0;
-        sqlx::query(
-            r#"
-            INSERT OR REPLACE INTO books 
-            (id, name, year, author, summary, publisher, page_count, read_page, reading, finished, updated_at, inserted_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(book.id.to_string())
-        .bind(&book.name)
-        .bind(book.year)
-        .bind(&book.author)
-        .bind(&book.summary)
-        .bind(&book.publisher)
-        .bind(book.page_count)
-        .bind(book.read_page)
-        .bind(book.reading)
-        .bind(book.finished)
-        .bind(book.updated_at.to_rfc3339())
-        .bind(book.inserted_at.to_rfc3339())
-        .execute(pool)
-        .await.map_err(|_e| AppError::DatabaseError)?;
-
-        Ok(book.id)
+        save_book(&self.0, book).await
+    }
+
+    async fn update_book(&self, book: &Book) -> Result<i64, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        update_book(&mut conn, book).await
     }
 
     async fn get_books(
         &self,
-        name: Option<String>,
-        reading: Option<bool>,
-        finished: Option<bool>,
-    ) -> Result<Vec<BookSummary>, AppError> {
+        owner: Uuid,
+        filter: &BookFilter,
+        page: &PageRequest,
+    ) -> Result<Page<BookSummary>, AppError> {
         let pool = &self.0;
 
-        let mut query = "SELECT id, name, publisher FROM books WHERE 1=1".to_string();
-        if name.is_some() {
-            query.push_str(" AND name LIKE ?");
-        }
-        if reading.is_some() {
-            query.push_str(" AND reading = ?");
-        }
-        if finished.is_some() {
-            query.push_str(" AND finished = ?");
-        }
-
-        let mut query_builder = sqlx::query(&query);
+        let mut count_query = QueryBuilder::new(
+            "SELECT COUNT(*) FROM books WHERE deleted_at IS NULL AND owner_id = ",
+        );
+        count_query.push_bind(owner.to_string());
+        push_filters(&mut count_query, filter);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(pool)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
 
-        if let Some(name_val) = name {
-            query_builder = query_builder.bind(format!("%{}%", name_val));
-        }
-        if let Some(reading_val) = reading {
-            query_builder = query_builder.bind(reading_val);
-        }
-        if let Some(finished_val) = finished {
-            query_builder = query_builder.bind(finished_val);
-        }
+        let mut query = QueryBuilder::new(
+            "SELECT id, name, publisher FROM books WHERE deleted_at IS NULL AND owner_id = ",
+        );
+        query.push_bind(owner.to_string());
+        push_filters(&mut query, filter);
+        let column = match page.sort {
+            BookSort::Name => "name COLLATE NOCASE",
+            BookSort::Year => "year",
+            // RFC 3339 text in UTC sorts chronologically.
+            BookSort::InsertedAt => "inserted_at",
+            BookSort::UpdatedAt => "updated_at",
+        };
+        let direction = match page.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        query
+            .push(format!(" ORDER BY {} {}, id ASC", column, direction))
+            .push(" LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
 
-        let books = query_builder
+        let books = query
+            .build()
             .fetch_all(pool)
             .await
             .map_err(|_e| AppError::DatabaseError)?
@@ -124,70 +384,280 @@ impl BookRepo for SqliteBookRepo {
             })
             .collect::<Result<Vec<BookSummary>, AppError>>()?;
 
-        Ok(books)
-    }
-
-    async fn get_book_by_id(&self, id: Uuid) -> Result<Option<Book>, AppError> {
-        // let pool = &self.0;
-        // let book: Option<Book> = sqlx::query_as(
-        //     r#"
-        //     SELECT id, name, year, author, summary, publisher, page_count, read_page
-        //     FROM books WHERE id = ?
-        //     "#,
-        // ).bind(id.to_string()).fetch_optional(pool)
-        //     .await
-        //     .map_err(|_e| AppError::DatabaseError)?;
-        //
-        // Ok(book)
+        Ok(Page {
+            items: books,
+            total: total as u64,
+        })
+    }
+
+    async fn search_books(
+        &self,
+        owner: Uuid,
+        query: &str,
+        page: &PageRequest,
+    ) -> Result<Page<BookSearchHit>, AppError> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Page {
+                items: Vec::new(),
+                total: 0,
+            });
+        }
+        // Quoted so every term is matched literally and all must be present.
+        let expression = terms
+            .iter()
+            .map(|term| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" ");
         let pool = &self.0;
-        let row = sqlx::query(
+
+        let total: i64 = sqlx::query_scalar(
             r#"
-            SELECT id, name, year, author, summary, publisher, page_count, read_page, reading, finished, updated_at, inserted_at
-            FROM books WHERE id = ?
+            SELECT COUNT(*)
+            FROM books_fts JOIN books ON books.id = books_fts.book_id
+            WHERE books_fts MATCH ? AND books.owner_id = ? AND books.deleted_at IS NULL
             "#,
         )
-        .bind(id.to_string())
-        .fetch_optional(pool)
+        .bind(&expression)
+        .bind(owner.to_string())
+        .fetch_one(pool)
         .await
         .map_err(|_e| AppError::DatabaseError)?;
-        if let Some(row) = row {
-            let book = Book {
-                id: Uuid::parse_str(row.get::<String, _>("id").as_str())
-                    .map_err(|_e| AppError::DatabaseError)?,
-                name: row.get("name"),
-                year: row.get("year"),
-                author: row.get("author"),
-                summary: row.get("summary"),
-                publisher: row.get("publisher"),
-                page_count: row.get("page_count"),
-                read_page: row.get("read_page"),
-                reading: row.get("reading"),
-                finished: row.get("finished"),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))
-                    .map_err(|_e| AppError::DatabaseError)?
-                    .with_timezone(&Utc),
-                inserted_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("inserted_at"))
-                    .map_err(|_e| AppError::DatabaseError)?
-                    .with_timezone(&Utc),
-            };
-            Ok(Some(book))
-        } else {
-            Ok(None)
-        }
+
+        // Weights follow the column order: book_id, name, author, summary.
+        let rows = sqlx::query(
+            r#"
+            SELECT books.id, books.name, books.publisher,
+                snippet(books_fts, -1, ?, ?, ?, ?) AS snippet
+            FROM books_fts JOIN books ON books.id = books_fts.book_id
+            WHERE books_fts MATCH ? AND books.owner_id = ? AND books.deleted_at IS NULL
+            ORDER BY bm25(books_fts, 0.0, 10.0, 5.0, 1.0), books.id ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(HIGHLIGHT_START)
+        .bind(HIGHLIGHT_END)
+        .bind(SNIPPET_ELLIPSIS)
+        .bind(SNIPPET_TOKENS as i64)
+        .bind(&expression)
+        .bind(owner.to_string())
+        .bind(i64::from(page.limit))
+        .bind(page.offset() as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let hits = rows
+            .iter()
+            .map(|row| {
+                Ok(BookSearchHit {
+                    book: BookSummary {
+                        id: row
+                            .get::<String, _>("id")
+                            .parse()
+                            .map_err(|_e| AppError::DatabaseError)?,
+                        name: row.get("name"),
+                        publisher: row.get("publisher"),
+                    },
+                    snippet: row.get("snippet"),
+                })
+            })
+            .collect::<Result<Vec<BookSearchHit>, AppError>>()?;
+
+        Ok(Page {
+            items: hits,
+            total: total as u64,
+        })
+    }
+
+    async fn get_book_by_id(&self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+        get_book_by_id(&self.0, owner, id).await
+    }
+
+    async fn stream_books(&self, owner: Uuid) -> Result<BookStream, AppError> {
+        let pool = self.0.clone();
+        Ok(spawn_book_stream(move |sender| async move {
+            let mut rows = sqlx::query(
+                r#"
+                SELECT id, owner_id, name, year, author, summary, publisher, page_count, read_page, reading, finished, finished_at, updated_at, inserted_at, version
+                FROM books WHERE owner_id = ? AND deleted_at IS NULL ORDER BY inserted_at, id
+                "#,
+            )
+            .bind(owner.to_string())
+            .fetch(&pool);
+            while let Some(row) = rows.next().await {
+                let book = row
+                    .map_err(|_e| AppError::DatabaseError)
+                    .and_then(|row| book_from_row(&row));
+                let failed = book.is_err();
+                if sender.send(book).await.is_err() || failed {
+                    break;
+                }
+            }
+        }))
+    }
+
+    async fn delete_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        delete_book(&mut conn, owner, id, None).await
     }
 
-    async fn delete_book(&self, id: Uuid) -> Result<Uuid, AppError> {
-        let result = sqlx::query("DELETE FROM books WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.0)
+    async fn delete_book_version(
+        &self,
+        owner: Uuid,
+        id: Uuid,
+        version: i64,
+    ) -> Result<Uuid, AppError> {
+        let mut conn = self
+            .0
+            .acquire()
             .await
             .map_err(|_e| AppError::DatabaseError)?;
+        delete_book(&mut conn, owner, id, Some(version)).await
+    }
+
+    async fn get_trash(
+        &self,
+        owner: Uuid,
+        page: &PageRequest,
+    ) -> Result<Page<TrashedBook>, AppError> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM books WHERE owner_id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(owner.to_string())
+        .fetch_one(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, publisher, deleted_at FROM books
+            WHERE owner_id = ? AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(owner.to_string())
+        .bind(i64::from(page.limit))
+        .bind(page.offset() as i64)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        let items = rows
+            .iter()
+            .map(|row| {
+                Ok(TrashedBook {
+                    book: BookSummary {
+                        id: row
+                            .get::<String, _>("id")
+                            .parse()
+                            .map_err(|_e| AppError::DatabaseError)?,
+                        name: row.get("name"),
+                        publisher: row.get("publisher"),
+                    },
+                    deleted_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("deleted_at"))
+                        .map_err(|_e| AppError::DatabaseError)?
+                        .with_timezone(&Utc),
+                })
+            })
+            .collect::<Result<Vec<TrashedBook>, AppError>>()?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+
+    async fn restore_book(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query(
+            "UPDATE books SET deleted_at = NULL WHERE id = ? AND owner_id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id.to_string())
+        .bind(owner.to_string())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
 
         if result.rows_affected() == 0 {
-            let message = "Buku gagal dihapus. Id tidak ditemukan".to_string();
+            let message = "Buku gagal dipulihkan. Id tidak ditemukan".to_string();
             Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
         } else {
             Ok(id)
         }
     }
+
+    async fn purge_trash(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        // RFC 3339 text in UTC compares chronologically.
+        let result =
+            sqlx::query("DELETE FROM books WHERE deleted_at IS NOT NULL AND deleted_at < ?")
+                .bind(before.to_rfc3339())
+                .execute(&self.0)
+                .await
+                .map_err(|_e| AppError::DatabaseError)?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_stats(&self, owner: Uuid, request: &StatsRequest) -> Result<ShelfStats, AppError> {
+        let totals = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COALESCE(SUM(finished), 0) AS finished,
+                COALESCE(SUM(reading AND NOT finished), 0) AS reading,
+                COALESCE(SUM(MAX(read_page, 0)), 0) AS pages,
+                ROUND(AVG(CASE WHEN finished
+                    THEN (julianday(finished_at) - julianday(inserted_at)) * 86400 END)) AS completion
+            FROM books WHERE owner_id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(owner.to_string())
+        .fetch_one(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        // The period is the leading `YYYY-MM` or `YYYY` of the RFC 3339 text.
+        let label_len = match request.period {
+            StatsPeriod::Month => 7,
+            StatsPeriod::Year => 4,
+        };
+        let periods = sqlx::query(
+            r#"
+            SELECT substr(finished_at, 1, ?) AS period, COUNT(*) AS finished
+            FROM books
+            WHERE owner_id = ? AND deleted_at IS NULL AND finished AND finished_at IS NOT NULL
+            GROUP BY period
+            ORDER BY period ASC
+            "#,
+        )
+        .bind(label_len)
+        .bind(owner.to_string())
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(ShelfStats {
+            total_books: totals.get::<i64, _>("total") as u64,
+            finished_books: totals.get::<i64, _>("finished") as u64,
+            currently_reading: totals.get::<i64, _>("reading") as u64,
+            pages_reached: totals.get::<i64, _>("pages") as u64,
+            periods: periods
+                .iter()
+                .map(|row| PeriodStats {
+                    period: row.get("period"),
+                    books_finished: row.get::<i64, _>("finished") as u64,
+                    pages_read: 0,
+                })
+                .collect(),
+            average_completion_secs: totals
+                .get::<Option<f64>, _>("completion")
+                .map(|secs| secs as i64),
+            top_authors: top_names(&self.0, owner, "author", request.top).await?,
+            top_publishers: top_names(&self.0, owner, "publisher", request.top).await?,
+        })
+    }
 }
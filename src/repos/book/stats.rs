@@ -0,0 +1,118 @@
+//! Aggregates over a whole shelf, see [`BookRepo::get_stats`]. A book counts
+//! as finished in the period of its `finished_at`, pages count as read in the
+//! period the reading session they were read in ended.
+//!
+//! [`BookRepo::get_stats`]: super::BookRepo::get_stats
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const DEFAULT_TOP_LIMIT: u32 = 5;
+pub const MAX_TOP_LIMIT: u32 = 50;
+
+/// How [`ShelfStats::periods`] are grouped.
+#[derive(Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    #[default]
+    Month,
+    Year,
+}
+
+impl StatsPeriod {
+    /// The period `at` falls in, as `2024-05` or `2024` in UTC.
+    pub fn label(self, at: DateTime<Utc>) -> String {
+        match self {
+            StatsPeriod::Month => at.format("%Y-%m").to_string(),
+            StatsPeriod::Year => at.format("%Y").to_string(),
+        }
+    }
+}
+
+/// What `get_stats` groups by and how many top authors and publishers it
+/// lists.
+#[derive(Clone, Copy, Debug)]
+pub struct StatsRequest {
+    pub period: StatsPeriod,
+    pub top: u32,
+}
+
+impl Default for StatsRequest {
+    fn default() -> Self {
+        StatsRequest {
+            period: StatsPeriod::default(),
+            top: DEFAULT_TOP_LIMIT,
+        }
+    }
+}
+
+/// Reading activity within one period.
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodStats {
+    /// `2024-05` by month, `2024` by year.
+    pub period: String,
+    pub books_finished: u64,
+    /// Pages read in the sessions that ended within the period, re-read
+    /// pages and those of books since trashed included. The periods so don't
+    /// add up to [`ShelfStats::pages_reached`].
+    pub pages_read: u64,
+}
+
+/// Pages read in the sessions that ended within one period, labelled as in
+/// [`PeriodStats`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodPages {
+    pub period: String,
+    pub pages: u64,
+}
+
+/// An author or publisher with the number of their books on the shelf.
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq, Eq)]
+pub struct NameCount {
+    pub name: String,
+    pub books: u64,
+}
+
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelfStats {
+    pub total_books: u64,
+    pub finished_books: u64,
+    /// Books being read and not finished yet.
+    pub currently_reading: u64,
+    /// Sum of the page each book is on, however it got there.
+    pub pages_reached: u64,
+    /// Periods with a book finished or pages read, oldest first.
+    pub periods: Vec<PeriodStats>,
+    /// Mean time from adding a book to finishing it, in whole seconds, unset
+    /// without finished books.
+    pub average_completion_secs: Option<i64>,
+    /// Most frequent first, ties by name. Books without one are left out.
+    pub top_authors: Vec<NameCount>,
+    pub top_publishers: Vec<NameCount>,
+}
+
+impl ShelfStats {
+    /// Adds `pages` to the matching `periods`, inserting the periods with
+    /// pages read but no book finished in order.
+    pub fn add_pages_read(&mut self, pages: Vec<PeriodPages>) {
+        for PeriodPages { period, pages } in pages {
+            match self
+                .periods
+                .binary_search_by(|stats| stats.period.as_str().cmp(&period))
+            {
+                Ok(at) => self.periods[at].pages_read += pages,
+                Err(at) => self.periods.insert(
+                    at,
+                    PeriodStats {
+                        period,
+                        books_finished: 0,
+                        pages_read: pages,
+                    },
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod inmemory_book_repo {
+    use std::sync::Arc;
+
+    use crate::repos::book::{BookRepo, inmemory::InMemoryBookRepo, test::book_repo_conformance};
+
+    async fn repo() -> Arc<dyn BookRepo> {
+        Arc::new(InMemoryBookRepo::default())
+    }
+
+    book_repo_conformance!(repo());
+}
@@ -0,0 +1,1466 @@
+//! Behaviour every [`BookRepo`] backend must share. Each backend module
+//! runs the whole suite through [`book_repo_conformance`].
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, SubsecRound, TimeZone, Utc};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::book::{
+        Book, BookFilter, BookRepo, BookSort, BookTransaction, PageRequest, SortOrder,
+        stats::{NameCount, PeriodStats, StatsPeriod, StatsRequest},
+    },
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! book_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::book::test::book_repo_conformance!(@cases $repo;
+            saved_book_should_be_retrievable,
+            save_should_replace_existing_book,
+            missing_book_should_be_none,
+            save_should_keep_latest_updated_at,
+            get_books_without_filters_should_return_all,
+            get_books_should_return_summaries,
+            get_books_should_filter_by_name,
+            name_filter_should_be_case_insensitive,
+            get_books_should_filter_by_reading,
+            get_books_should_filter_by_finished,
+            get_books_should_combine_filters,
+            get_books_should_filter_by_author_and_publisher,
+            get_books_should_filter_by_year_range,
+            get_books_should_filter_by_page_count_range,
+            get_books_should_filter_by_timestamps,
+            get_books_should_filter_by_read_percent,
+            pages_should_split_results_and_report_total,
+            page_past_the_end_should_be_empty,
+            paging_should_apply_after_filters,
+            sort_by_name_should_ignore_case,
+            sort_by_year_should_break_ties_by_id,
+            sort_by_timestamps_should_follow_order,
+            search_should_match_name_author_and_summary,
+            search_should_require_every_term,
+            search_should_rank_better_matches_first,
+            search_should_highlight_matches,
+            search_should_follow_updates_and_deletes,
+            search_should_page_results_and_report_total,
+            search_should_treat_syntax_as_text,
+            deleted_book_should_be_gone,
+            delete_missing_book_should_be_not_found,
+            update_should_bump_version,
+            stale_update_should_be_refused,
+            update_missing_book_should_be_not_found,
+            stale_delete_should_be_refused,
+            deleted_book_should_only_be_in_trash,
+            trash_should_list_latest_deletion_first,
+            restored_book_should_be_back_as_it_was,
+            restore_should_need_a_trashed_book,
+            purge_should_only_remove_books_trashed_before,
+            other_owners_books_should_not_be_listed,
+            other_owners_books_should_not_be_found,
+            other_owners_books_should_not_be_searchable,
+            other_owners_books_should_not_be_deletable,
+            stream_should_yield_full_books_oldest_first,
+            stream_should_skip_other_owners_books,
+            committed_transaction_should_keep_writes,
+            dropped_transaction_should_undo_writes,
+            transaction_should_read_its_own_writes,
+            stats_should_total_and_group_by_period,
+            stats_should_count_a_finish_when_it_happened,
+            stats_should_rank_authors_and_publishers,
+            stats_should_skip_trash_and_other_owners,
+            stats_of_empty_shelf_should_be_zero,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::book::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use book_repo_conformance;
+
+/// Owner of the books made by [`book`].
+#[allow(dead_code)]
+pub const OWNER: Uuid = Uuid::from_u128(1);
+
+#[allow(dead_code)]
+pub fn book(name: &str) -> Book {
+    let now = Utc::now();
+    Book {
+        id: Uuid::new_v4(),
+        owner_id: OWNER,
+        name: name.to_string(),
+        year: 2010,
+        author: "John Doe".to_string(),
+        summary: "Lorem ipsum dolor sit amet".to_string(),
+        publisher: "Dicoding Indonesia".to_string(),
+        page_count: 100,
+        read_page: 25,
+        reading: false,
+        finished: false,
+        finished_at: None,
+        updated_at: now,
+        inserted_at: now - Duration::days(1),
+        version: 1,
+    }
+}
+
+#[allow(dead_code)]
+fn assert_same_book(actual: &Book, expected: &Book) {
+    assert_eq!(actual.id, expected.id);
+    assert_eq!(actual.owner_id, expected.owner_id);
+    assert_eq!(actual.name, expected.name);
+    assert_eq!(actual.year, expected.year);
+    assert_eq!(actual.author, expected.author);
+    assert_eq!(actual.summary, expected.summary);
+    assert_eq!(actual.publisher, expected.publisher);
+    assert_eq!(actual.page_count, expected.page_count);
+    assert_eq!(actual.read_page, expected.read_page);
+    assert_eq!(actual.reading, expected.reading);
+    assert_eq!(actual.finished, expected.finished);
+    // Backends keep at least millisecond precision.
+    assert_eq!(
+        actual.finished_at.map(|at| at.timestamp_millis()),
+        expected.finished_at.map(|at| at.timestamp_millis())
+    );
+    assert_eq!(
+        actual.updated_at.timestamp_millis(),
+        expected.updated_at.timestamp_millis()
+    );
+    assert_eq!(
+        actual.inserted_at.timestamp_millis(),
+        expected.inserted_at.timestamp_millis()
+    );
+    assert_eq!(actual.version, expected.version);
+}
+
+#[allow(dead_code)]
+pub async fn saved_book_should_be_retrievable(repo: Arc<dyn BookRepo>) {
+    let book = book("Buku A");
+
+    let id = repo.save_book(&book).await.unwrap();
+
+    assert_eq!(id, book.id);
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_same_book(&stored, &book);
+}
+
+#[allow(dead_code)]
+pub async fn save_should_replace_existing_book(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+
+    book.name = "Buku Revisi".to_string();
+    book.read_page = 100;
+    book.reading = true;
+    book.finished = true;
+    book.finished_at = Some(Utc::now());
+    repo.save_book(&book).await.unwrap();
+
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_same_book(&stored, &book);
+    assert_eq!(
+        repo.get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+            .await
+            .unwrap()
+            .items
+            .len(),
+        1
+    );
+}
+
+#[allow(dead_code)]
+pub async fn missing_book_should_be_none(repo: Arc<dyn BookRepo>) {
+    assert!(
+        repo.get_book_by_id(OWNER, Uuid::new_v4())
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn save_should_keep_latest_updated_at(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+
+    book.updated_at += Duration::milliseconds(1500);
+    repo.save_book(&book).await.unwrap();
+
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_eq!(
+        stored.updated_at.timestamp_millis(),
+        book.updated_at.timestamp_millis()
+    );
+    assert_eq!(
+        stored.inserted_at.timestamp_millis(),
+        book.inserted_at.timestamp_millis()
+    );
+    assert!(stored.inserted_at < stored.updated_at);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_without_filters_should_return_all(repo: Arc<dyn BookRepo>) {
+    assert!(
+        repo.get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty()
+    );
+
+    for name in ["Buku A", "Buku B", "Buku C"] {
+        repo.save_book(&book(name)).await.unwrap();
+    }
+
+    assert_eq!(
+        repo.get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+            .await
+            .unwrap()
+            .items
+            .len(),
+        3
+    );
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_return_summaries(repo: Arc<dyn BookRepo>) {
+    let book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+
+    let books = repo
+        .get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+        .await
+        .unwrap()
+        .items;
+
+    assert_eq!(books.len(), 1);
+    assert_eq!(books[0].id, book.id);
+    assert_eq!(books[0].name, book.name);
+    assert_eq!(books[0].publisher, book.publisher);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_name(repo: Arc<dyn BookRepo>) {
+    repo.save_book(&book("Dicoding Rust")).await.unwrap();
+    repo.save_book(&book("Belajar Go")).await.unwrap();
+
+    let books = repo
+        .get_books(
+            OWNER,
+            &BookFilter {
+                name: Some("rust".to_string()),
+                ..BookFilter::default()
+            },
+            &PageRequest::default(),
+        )
+        .await
+        .unwrap()
+        .items;
+
+    assert_eq!(books.len(), 1);
+    assert_eq!(books[0].name, "Dicoding Rust");
+}
+
+#[allow(dead_code)]
+pub async fn name_filter_should_be_case_insensitive(repo: Arc<dyn BookRepo>) {
+    repo.save_book(&book("dicoding rust")).await.unwrap();
+
+    for filter in ["DICODING", "Rust", "coding r"] {
+        let books = repo
+            .get_books(
+                OWNER,
+                &BookFilter {
+                    name: Some(filter.to_string()),
+                    ..BookFilter::default()
+                },
+                &PageRequest::default(),
+            )
+            .await
+            .unwrap()
+            .items;
+        assert_eq!(books.len(), 1, "filter `{}`", filter);
+    }
+}
+
+/// Saves one book per reading/finished combination, named after it.
+#[allow(dead_code)]
+async fn save_progress_matrix(repo: &Arc<dyn BookRepo>) {
+    for (name, reading, finished) in [
+        ("idle", false, false),
+        ("reading", true, false),
+        ("finished", false, true),
+        ("rereading", true, true),
+    ] {
+        let mut book = book(name);
+        book.reading = reading;
+        book.finished = finished;
+        repo.save_book(&book).await.unwrap();
+    }
+}
+
+#[allow(dead_code)]
+async fn names(
+    repo: &Arc<dyn BookRepo>,
+    reading: Option<bool>,
+    finished: Option<bool>,
+) -> Vec<String> {
+    let filter = BookFilter {
+        reading,
+        finished,
+        ..BookFilter::default()
+    };
+    filtered_names(repo, &filter).await
+}
+
+#[allow(dead_code)]
+async fn filtered_names(repo: &Arc<dyn BookRepo>, filter: &BookFilter) -> Vec<String> {
+    let mut names: Vec<String> = repo
+        .get_books(OWNER, filter, &PageRequest::default())
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|book| book.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_reading(repo: Arc<dyn BookRepo>) {
+    save_progress_matrix(&repo).await;
+
+    assert_eq!(
+        names(&repo, Some(true), None).await,
+        ["reading", "rereading"]
+    );
+    assert_eq!(names(&repo, Some(false), None).await, ["finished", "idle"]);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_finished(repo: Arc<dyn BookRepo>) {
+    save_progress_matrix(&repo).await;
+
+    assert_eq!(
+        names(&repo, None, Some(true)).await,
+        ["finished", "rereading"]
+    );
+    assert_eq!(names(&repo, None, Some(false)).await, ["idle", "reading"]);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_combine_filters(repo: Arc<dyn BookRepo>) {
+    save_progress_matrix(&repo).await;
+
+    assert_eq!(names(&repo, Some(true), Some(false)).await, ["reading"]);
+    let books = repo
+        .get_books(
+            OWNER,
+            &BookFilter {
+                name: Some("READ".to_string()),
+                reading: Some(true),
+                finished: Some(true),
+                ..BookFilter::default()
+            },
+            &PageRequest::default(),
+        )
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(books.len(), 1);
+    assert_eq!(books[0].name, "rereading");
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_author_and_publisher(repo: Arc<dyn BookRepo>) {
+    for (name, author, publisher) in [
+        ("a", "Andrea Hirata", "Bentang Pustaka"),
+        ("b", "Pramoedya Ananta Toer", "Lentera Dipantara"),
+        ("c", "Tere Liye", "Gramedia Pustaka Utama"),
+    ] {
+        let mut book = book(name);
+        book.author = author.to_string();
+        book.publisher = publisher.to_string();
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let filter = BookFilter {
+        author: Some("ananta".to_string()),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["b"]);
+
+    let filter = BookFilter {
+        publisher: Some("PUSTAKA".to_string()),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["a", "c"]);
+
+    let filter = BookFilter {
+        author: Some("tere".to_string()),
+        publisher: Some("bentang".to_string()),
+        ..BookFilter::default()
+    };
+    assert!(filtered_names(&repo, &filter).await.is_empty());
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_year_range(repo: Arc<dyn BookRepo>) {
+    for (name, year) in [("a", 1999), ("b", 2000), ("c", 2005), ("d", 2010)] {
+        let mut book = book(name);
+        book.year = year;
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let filter = BookFilter {
+        year_from: Some(2000),
+        year_to: Some(2005),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["b", "c"]);
+
+    let filter = BookFilter {
+        year_from: Some(2005),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["c", "d"]);
+
+    let filter = BookFilter {
+        year_to: Some(1999),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["a"]);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_page_count_range(repo: Arc<dyn BookRepo>) {
+    for (name, page_count) in [("short", 80), ("medium", 250), ("long", 900)] {
+        let mut book = book(name);
+        book.page_count = page_count;
+        book.read_page = 0;
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let filter = BookFilter {
+        page_count_min: Some(250),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["long", "medium"]);
+
+    let filter = BookFilter {
+        page_count_min: Some(81),
+        page_count_max: Some(250),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["medium"]);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_timestamps(repo: Arc<dyn BookRepo>) {
+    // Whole seconds so every backend stores the exact boundary.
+    let now = Utc::now().trunc_subsecs(0);
+    for (name, age) in [("old", 10), ("recent", 5), ("new", 0)] {
+        let mut book = book(name);
+        book.inserted_at = now - Duration::seconds(age);
+        book.updated_at = now - Duration::seconds(age);
+        book.finished_at = Some(now - Duration::seconds(age));
+        repo.save_book(&book).await.unwrap();
+    }
+    let mut unfinished = book("unfinished");
+    unfinished.inserted_at = now - Duration::seconds(20);
+    unfinished.updated_at = now - Duration::seconds(20);
+    repo.save_book(&unfinished).await.unwrap();
+
+    let filter = BookFilter {
+        inserted_after: Some(now - Duration::seconds(5)),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["new"]);
+
+    let filter = BookFilter {
+        updated_since: Some(now - Duration::seconds(5)),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["new", "recent"]);
+
+    let filter = BookFilter {
+        finished_since: Some(now - Duration::seconds(10)),
+        finished_before: Some(now),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["old", "recent"]);
+}
+
+#[allow(dead_code)]
+pub async fn get_books_should_filter_by_read_percent(repo: Arc<dyn BookRepo>) {
+    for (name, page_count, read_page) in [
+        ("empty", 0, 0),
+        ("started", 200, 20),
+        ("halfway", 200, 100),
+        ("done", 200, 200),
+    ] {
+        let mut book = book(name);
+        book.page_count = page_count;
+        book.read_page = read_page;
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let filter = BookFilter {
+        read_percent_min: Some(50.0),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["done", "halfway"]);
+
+    let filter = BookFilter {
+        read_percent_max: Some(10.0),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["empty", "started"]);
+
+    let filter = BookFilter {
+        read_percent_min: Some(10.5),
+        read_percent_max: Some(99.9),
+        ..BookFilter::default()
+    };
+    assert_eq!(filtered_names(&repo, &filter).await, ["halfway"]);
+}
+
+#[allow(dead_code)]
+async fn searched_names(repo: &Arc<dyn BookRepo>, query: &str) -> Vec<String> {
+    repo.search_books(OWNER, query, &PageRequest::default())
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|hit| hit.book.name)
+        .collect()
+}
+
+#[allow(dead_code)]
+pub async fn search_should_match_name_author_and_summary(repo: Arc<dyn BookRepo>) {
+    let mut by_name = book("Belajar Rust");
+    by_name.summary = "Pemrograman sistem".to_string();
+    let mut by_author = book("Buku B");
+    by_author.author = "Steve Klabnik".to_string();
+    let mut by_summary = book("Buku C");
+    by_summary.summary = "Panduan lengkap tentang ownership".to_string();
+    for book in [&by_name, &by_author, &by_summary] {
+        repo.save_book(book).await.unwrap();
+    }
+
+    assert_eq!(searched_names(&repo, "rust").await, ["Belajar Rust"]);
+    assert_eq!(searched_names(&repo, "KLABNIK").await, ["Buku B"]);
+    assert_eq!(searched_names(&repo, "ownership").await, ["Buku C"]);
+    assert!(searched_names(&repo, "python").await.is_empty());
+}
+
+#[allow(dead_code)]
+pub async fn search_should_require_every_term(repo: Arc<dyn BookRepo>) {
+    let mut both = book("Rust Async");
+    both.summary = "Tokio dan futures".to_string();
+    repo.save_book(&both).await.unwrap();
+    repo.save_book(&book("Rust Dasar")).await.unwrap();
+
+    assert_eq!(searched_names(&repo, "rust tokio").await, ["Rust Async"]);
+    assert_eq!(searched_names(&repo, "rust").await.len(), 2);
+}
+
+#[allow(dead_code)]
+pub async fn search_should_rank_better_matches_first(repo: Arc<dyn BookRepo>) {
+    let mut in_summary = book("Pemrograman Sistem");
+    in_summary.summary =
+        "Buku tentang banyak bahasa pemrograman seperti C, Go, Zig dan juga rust".to_string();
+    let mut in_name = book("Rust");
+    in_name.summary = "Belajar rust dari nol".to_string();
+    let mut elsewhere = book("Resep Masakan");
+    elsewhere.summary = "Tidak ada hubungannya".to_string();
+    for book in [&in_summary, &in_name, &elsewhere] {
+        repo.save_book(book).await.unwrap();
+    }
+
+    assert_eq!(
+        searched_names(&repo, "rust").await,
+        ["Rust", "Pemrograman Sistem"]
+    );
+}
+
+#[allow(dead_code)]
+pub async fn search_should_highlight_matches(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    book.summary = "Mengenal Rust untuk pemula".to_string();
+    repo.save_book(&book).await.unwrap();
+
+    let hits = repo
+        .search_books(OWNER, "rust", &PageRequest::default())
+        .await
+        .unwrap()
+        .items;
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].book.id, book.id);
+    assert_eq!(hits[0].book.publisher, book.publisher);
+    assert!(
+        hits[0].snippet.contains("<mark>Rust</mark>"),
+        "snippet `{}`",
+        hits[0].snippet
+    );
+}
+
+#[allow(dead_code)]
+pub async fn search_should_follow_updates_and_deletes(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku Lama");
+    repo.save_book(&book).await.unwrap();
+
+    book.name = "Buku Baru".to_string();
+    repo.save_book(&book).await.unwrap();
+    assert!(searched_names(&repo, "lama").await.is_empty());
+    assert_eq!(searched_names(&repo, "baru").await, ["Buku Baru"]);
+
+    repo.delete_book(OWNER, book.id).await.unwrap();
+    assert!(searched_names(&repo, "baru").await.is_empty());
+}
+
+#[allow(dead_code)]
+pub async fn search_should_page_results_and_report_total(repo: Arc<dyn BookRepo>) {
+    for name in ["Rust A", "Rust B", "Rust C"] {
+        repo.save_book(&book(name)).await.unwrap();
+    }
+
+    let page = PageRequest {
+        page: 2,
+        limit: 2,
+        ..PageRequest::default()
+    };
+    let hits = repo.search_books(OWNER, "rust", &page).await.unwrap();
+
+    assert_eq!(hits.total, 3);
+    assert_eq!(hits.items.len(), 1);
+}
+
+#[allow(dead_code)]
+pub async fn search_should_treat_syntax_as_text(repo: Arc<dyn BookRepo>) {
+    repo.save_book(&book("Rust OR Go")).await.unwrap();
+
+    for query in ["\"rust", "rust*", "go OR (rust", "-rust", "or:rust"] {
+        assert_eq!(
+            searched_names(&repo, query).await,
+            ["Rust OR Go"],
+            "query `{}`",
+            query
+        );
+    }
+}
+
+#[allow(dead_code)]
+pub async fn deleted_book_should_be_gone(repo: Arc<dyn BookRepo>) {
+    let book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+
+    let id = repo.delete_book(OWNER, book.id).await.unwrap();
+
+    assert_eq!(id, book.id);
+    assert!(repo.get_book_by_id(OWNER, book.id).await.unwrap().is_none());
+    assert!(
+        repo.get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn delete_missing_book_should_be_not_found(repo: Arc<dyn BookRepo>) {
+    let result = repo.delete_book(OWNER, Uuid::new_v4()).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
+
+#[allow(dead_code)]
+pub async fn update_should_bump_version(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+
+    book.name = "Buku Revisi".to_string();
+    book.updated_at += Duration::seconds(1);
+    let version = repo.update_book(&book).await.unwrap();
+
+    assert_eq!(version, 2);
+    book.version = version;
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_same_book(&stored, &book);
+    let hits = repo
+        .search_books(OWNER, "Revisi", &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(hits.total, 1);
+}
+
+#[allow(dead_code)]
+pub async fn stale_update_should_be_refused(repo: Arc<dyn BookRepo>) {
+    let book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+    let mut first = book.clone();
+    first.name = "Buku Pertama".to_string();
+    repo.update_book(&first).await.unwrap();
+
+    let mut second = book.clone();
+    second.name = "Buku Kedua".to_string();
+    let result = repo.update_book(&second).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::PRECONDITION_FAILED, _))
+    ));
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_eq!(stored.name, "Buku Pertama");
+    assert_eq!(stored.version, 2);
+}
+
+#[allow(dead_code)]
+pub async fn update_missing_book_should_be_not_found(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+    let mut stolen = foreign.clone();
+    stolen.owner_id = OWNER;
+
+    for book in [book("Buku Hilang"), stolen] {
+        let result = repo.update_book(&book).await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+        ));
+    }
+    let stored = repo
+        .get_book_by_id(foreign.owner_id, foreign.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_same_book(&stored, &foreign);
+}
+
+#[allow(dead_code)]
+pub async fn stale_delete_should_be_refused(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+    book.version = repo.update_book(&book).await.unwrap();
+
+    let result = repo.delete_book_version(OWNER, book.id, 1).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::PRECONDITION_FAILED, _))
+    ));
+    assert!(repo.get_book_by_id(OWNER, book.id).await.unwrap().is_some());
+    let id = repo.delete_book_version(OWNER, book.id, 2).await.unwrap();
+    assert_eq!(id, book.id);
+    assert!(repo.get_book_by_id(OWNER, book.id).await.unwrap().is_none());
+    let result = repo.delete_book_version(OWNER, book.id, 2).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
+
+#[allow(dead_code)]
+pub async fn deleted_book_should_only_be_in_trash(repo: Arc<dyn BookRepo>) {
+    let book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+    let before = Utc::now().trunc_subsecs(3);
+
+    repo.delete_book(OWNER, book.id).await.unwrap();
+
+    let page = PageRequest::default();
+    let trash = repo.get_trash(OWNER, &page).await.unwrap();
+    assert_eq!(trash.total, 1);
+    assert_eq!(trash.items[0].book.id, book.id);
+    assert_eq!(trash.items[0].book.name, "Buku A");
+    assert!(trash.items[0].deleted_at >= before);
+    assert!(trash.items[0].deleted_at <= Utc::now());
+    let hits = repo.search_books(OWNER, "Buku", &page).await.unwrap();
+    assert_eq!(hits.total, 0);
+    let streamed: Vec<Book> = repo
+        .stream_books(OWNER)
+        .await
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+    assert!(streamed.is_empty());
+    let result = repo.update_book(&book).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+    let result = repo.delete_book(OWNER, book.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
+
+#[allow(dead_code)]
+pub async fn trash_should_list_latest_deletion_first(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+    repo.delete_book(foreign.owner_id, foreign.id)
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in ["Buku A", "Buku B", "Buku C"] {
+        let book = book(name);
+        repo.save_book(&book).await.unwrap();
+        repo.delete_book(OWNER, book.id).await.unwrap();
+        ids.push(book.id);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+
+    let page = PageRequest {
+        limit: 2,
+        ..PageRequest::default()
+    };
+    let first = repo.get_trash(OWNER, &page).await.unwrap();
+    let second = repo
+        .get_trash(OWNER, &PageRequest { page: 2, ..page })
+        .await
+        .unwrap();
+
+    assert_eq!(first.total, 3);
+    let listed: Vec<Uuid> = first
+        .items
+        .iter()
+        .chain(&second.items)
+        .map(|trashed| trashed.book.id)
+        .collect();
+    ids.reverse();
+    assert_eq!(listed, ids);
+}
+
+#[allow(dead_code)]
+pub async fn restored_book_should_be_back_as_it_was(repo: Arc<dyn BookRepo>) {
+    let mut book = book("Buku A");
+    repo.save_book(&book).await.unwrap();
+    book.version = repo.update_book(&book).await.unwrap();
+    repo.delete_book(OWNER, book.id).await.unwrap();
+
+    let id = repo.restore_book(OWNER, book.id).await.unwrap();
+
+    assert_eq!(id, book.id);
+    let stored = repo.get_book_by_id(OWNER, book.id).await.unwrap().unwrap();
+    assert_same_book(&stored, &book);
+    let page = PageRequest::default();
+    assert_eq!(repo.get_trash(OWNER, &page).await.unwrap().total, 0);
+    let hits = repo.search_books(OWNER, "Buku", &page).await.unwrap();
+    assert_eq!(hits.total, 1);
+}
+
+#[allow(dead_code)]
+pub async fn restore_should_need_a_trashed_book(repo: Arc<dyn BookRepo>) {
+    let live = book("Buku A");
+    repo.save_book(&live).await.unwrap();
+    let foreign = save_foreign_book(&repo).await;
+    repo.delete_book(foreign.owner_id, foreign.id)
+        .await
+        .unwrap();
+
+    for id in [live.id, foreign.id, Uuid::new_v4()] {
+        let result = repo.restore_book(OWNER, id).await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+        ));
+    }
+    let trash = repo
+        .get_trash(foreign.owner_id, &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(trash.total, 1);
+}
+
+#[allow(dead_code)]
+pub async fn purge_should_only_remove_books_trashed_before(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+    repo.delete_book(foreign.owner_id, foreign.id)
+        .await
+        .unwrap();
+    let trashed = book("Buku A");
+    let live = book("Buku B");
+    repo.save_book(&trashed).await.unwrap();
+    repo.save_book(&live).await.unwrap();
+    repo.delete_book(OWNER, trashed.id).await.unwrap();
+
+    let purged = repo
+        .purge_trash(Utc::now() - Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(purged, 0);
+    let purged = repo
+        .purge_trash(Utc::now() + Duration::seconds(1))
+        .await
+        .unwrap();
+
+    assert_eq!(purged, 2);
+    let page = PageRequest::default();
+    assert_eq!(repo.get_trash(OWNER, &page).await.unwrap().total, 0);
+    let result = repo.restore_book(OWNER, trashed.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+    assert!(repo.get_book_by_id(OWNER, live.id).await.unwrap().is_some());
+}
+
+#[allow(dead_code)]
+async fn sorted_names(repo: &Arc<dyn BookRepo>, sort: BookSort, order: SortOrder) -> Vec<String> {
+    let page = PageRequest {
+        sort,
+        order,
+        ..PageRequest::default()
+    };
+    repo.get_books(OWNER, &BookFilter::default(), &page)
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|book| book.name)
+        .collect()
+}
+
+#[allow(dead_code)]
+pub async fn pages_should_split_results_and_report_total(repo: Arc<dyn BookRepo>) {
+    for name in ["a", "b", "c", "d", "e"] {
+        repo.save_book(&book(name)).await.unwrap();
+    }
+
+    let mut seen = Vec::new();
+    for (number, expected) in [(1, 2), (2, 2), (3, 1)] {
+        let page = PageRequest {
+            page: number,
+            limit: 2,
+            sort: BookSort::Name,
+            order: SortOrder::Asc,
+        };
+        let books = repo
+            .get_books(OWNER, &BookFilter::default(), &page)
+            .await
+            .unwrap();
+        assert_eq!(books.total, 5);
+        assert_eq!(books.items.len(), expected, "page {}", number);
+        seen.extend(books.items.into_iter().map(|book| book.name));
+    }
+    assert_eq!(seen, ["a", "b", "c", "d", "e"]);
+}
+
+#[allow(dead_code)]
+pub async fn page_past_the_end_should_be_empty(repo: Arc<dyn BookRepo>) {
+    repo.save_book(&book("a")).await.unwrap();
+
+    let page = PageRequest {
+        page: 3,
+        limit: 10,
+        ..PageRequest::default()
+    };
+    let books = repo
+        .get_books(OWNER, &BookFilter::default(), &page)
+        .await
+        .unwrap();
+
+    assert_eq!(books.total, 1);
+    assert!(books.items.is_empty());
+}
+
+#[allow(dead_code)]
+pub async fn paging_should_apply_after_filters(repo: Arc<dyn BookRepo>) {
+    for (name, reading) in [("a", true), ("b", false), ("c", true), ("d", true)] {
+        let mut book = book(name);
+        book.reading = reading;
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let page = PageRequest {
+        page: 2,
+        limit: 2,
+        sort: BookSort::Name,
+        order: SortOrder::Asc,
+    };
+    let books = repo
+        .get_books(
+            OWNER,
+            &BookFilter {
+                reading: Some(true),
+                ..BookFilter::default()
+            },
+            &page,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(books.total, 3);
+    assert_eq!(books.items.len(), 1);
+    assert_eq!(books.items[0].name, "d");
+}
+
+#[allow(dead_code)]
+pub async fn sort_by_name_should_ignore_case(repo: Arc<dyn BookRepo>) {
+    for name in ["beta", "Alpha", "charlie"] {
+        repo.save_book(&book(name)).await.unwrap();
+    }
+
+    assert_eq!(
+        sorted_names(&repo, BookSort::Name, SortOrder::Asc).await,
+        ["Alpha", "beta", "charlie"]
+    );
+    assert_eq!(
+        sorted_names(&repo, BookSort::Name, SortOrder::Desc).await,
+        ["charlie", "beta", "Alpha"]
+    );
+}
+
+#[allow(dead_code)]
+pub async fn sort_by_year_should_break_ties_by_id(repo: Arc<dyn BookRepo>) {
+    let mut books = Vec::new();
+    for (name, year) in [
+        ("new", 2020),
+        ("old", 1990),
+        ("same-1", 2000),
+        ("same-2", 2000),
+    ] {
+        let mut book = book(name);
+        book.year = year;
+        repo.save_book(&book).await.unwrap();
+        books.push(book);
+    }
+    let mut same: Vec<&Book> = books.iter().filter(|b| b.year == 2000).collect();
+    same.sort_by_key(|b| b.id);
+
+    let names = sorted_names(&repo, BookSort::Year, SortOrder::Asc).await;
+    assert_eq!(names[0], "old");
+    assert_eq!(names[1], same[0].name);
+    assert_eq!(names[2], same[1].name);
+    assert_eq!(names[3], "new");
+
+    let names = sorted_names(&repo, BookSort::Year, SortOrder::Desc).await;
+    assert_eq!(names[0], "new");
+    assert_eq!(names[3], "old");
+}
+
+#[allow(dead_code)]
+pub async fn sort_by_timestamps_should_follow_order(repo: Arc<dyn BookRepo>) {
+    let now = Utc::now();
+    for (name, inserted, updated) in [("first", 3, 1), ("second", 2, 3), ("third", 1, 2)] {
+        let mut book = book(name);
+        book.inserted_at = now - Duration::seconds(inserted);
+        book.updated_at = now + Duration::milliseconds(updated * 250);
+        repo.save_book(&book).await.unwrap();
+    }
+
+    assert_eq!(
+        sorted_names(&repo, BookSort::InsertedAt, SortOrder::Asc).await,
+        ["first", "second", "third"]
+    );
+    assert_eq!(
+        sorted_names(&repo, BookSort::UpdatedAt, SortOrder::Desc).await,
+        ["second", "third", "first"]
+    );
+}
+
+/// Saves one book for [`OWNER`] and one for somebody else, returning the
+/// latter.
+#[allow(dead_code)]
+async fn save_foreign_book(repo: &Arc<dyn BookRepo>) -> Book {
+    repo.save_book(&book("Rust Milik Sendiri")).await.unwrap();
+    let mut foreign = book("Rust Milik Orang Lain");
+    foreign.owner_id = Uuid::new_v4();
+    repo.save_book(&foreign).await.unwrap();
+    foreign
+}
+
+#[allow(dead_code)]
+pub async fn other_owners_books_should_not_be_listed(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+
+    let books = repo
+        .get_books(OWNER, &BookFilter::default(), &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(books.total, 1);
+    assert_eq!(books.items[0].name, "Rust Milik Sendiri");
+
+    let books = repo
+        .get_books(
+            foreign.owner_id,
+            &BookFilter::default(),
+            &PageRequest::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(books.total, 1);
+    assert_eq!(books.items[0].id, foreign.id);
+}
+
+#[allow(dead_code)]
+pub async fn other_owners_books_should_not_be_found(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+
+    assert!(
+        repo.get_book_by_id(OWNER, foreign.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.get_book_by_id(foreign.owner_id, foreign.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn other_owners_books_should_not_be_searchable(repo: Arc<dyn BookRepo>) {
+    save_foreign_book(&repo).await;
+
+    let hits = repo
+        .search_books(OWNER, "rust", &PageRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(hits.total, 1);
+    assert_eq!(hits.items.len(), 1);
+    assert_eq!(hits.items[0].book.name, "Rust Milik Sendiri");
+}
+
+#[allow(dead_code)]
+pub async fn other_owners_books_should_not_be_deletable(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+
+    let result = repo.delete_book(OWNER, foreign.id).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+    assert!(
+        repo.get_book_by_id(foreign.owner_id, foreign.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn stream_should_yield_full_books_oldest_first(repo: Arc<dyn BookRepo>) {
+    let mut older = book("Buku Lama");
+    older.inserted_at -= Duration::days(1);
+    let newer = book("Buku Baru");
+    repo.save_book(&newer).await.unwrap();
+    repo.save_book(&older).await.unwrap();
+
+    let books: Vec<Book> = repo
+        .stream_books(OWNER)
+        .await
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+
+    assert_eq!(books.len(), 2);
+    assert_same_book(&books[0], &older);
+    assert_same_book(&books[1], &newer);
+}
+
+#[allow(dead_code)]
+pub async fn stream_should_skip_other_owners_books(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+
+    let books: Vec<Book> = repo
+        .stream_books(OWNER)
+        .await
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+
+    assert_eq!(books.len(), 1);
+    assert!(books.iter().all(|book| book.id != foreign.id));
+}
+
+/// Saves `kept` and `removed`, then in a transaction adds `added`, renames
+/// `kept` and deletes `removed`.
+#[allow(dead_code)]
+async fn write_in_transaction(
+    repo: &Arc<dyn BookRepo>,
+) -> (Box<dyn BookTransaction>, Book, Book, Book) {
+    let kept = book("Buku Tetap");
+    let removed = book("Buku Dihapus");
+    let added = book("Buku Baru");
+    repo.save_book(&kept).await.unwrap();
+    repo.save_book(&removed).await.unwrap();
+
+    let mut transaction = repo.begin().await.unwrap();
+    let mut renamed = kept.clone();
+    renamed.name = "Buku Berganti".to_string();
+    transaction.save_book(&added).await.unwrap();
+    transaction.update_book(&renamed).await.unwrap();
+    transaction.delete_book(OWNER, removed.id).await.unwrap();
+    (transaction, kept, removed, added)
+}
+
+#[allow(dead_code)]
+pub async fn committed_transaction_should_keep_writes(repo: Arc<dyn BookRepo>) {
+    let (transaction, kept, removed, added) = write_in_transaction(&repo).await;
+
+    transaction.commit().await.unwrap();
+
+    let stored = repo.get_book_by_id(OWNER, kept.id).await.unwrap().unwrap();
+    assert_eq!(stored.name, "Buku Berganti");
+    let trash = repo
+        .get_trash(OWNER, &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(trash.items[0].book.id, removed.id);
+    assert!(
+        repo.get_book_by_id(OWNER, removed.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.get_book_by_id(OWNER, added.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn dropped_transaction_should_undo_writes(repo: Arc<dyn BookRepo>) {
+    let (transaction, kept, removed, added) = write_in_transaction(&repo).await;
+
+    drop(transaction);
+
+    let stored = repo.get_book_by_id(OWNER, kept.id).await.unwrap().unwrap();
+    assert_eq!(stored.name, "Buku Tetap");
+    assert!(
+        repo.get_book_by_id(OWNER, removed.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        repo.get_book_by_id(OWNER, added.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let page = PageRequest::default();
+    let hits = repo.search_books(OWNER, "Baru", &page).await.unwrap();
+    assert_eq!(hits.total, 0);
+    let hits = repo.search_books(OWNER, "Tetap", &page).await.unwrap();
+    assert_eq!(hits.total, 1);
+    assert_eq!(repo.get_trash(OWNER, &page).await.unwrap().total, 0);
+}
+
+#[allow(dead_code)]
+pub async fn transaction_should_read_its_own_writes(repo: Arc<dyn BookRepo>) {
+    let foreign = save_foreign_book(&repo).await;
+    let added = book("Buku Baru");
+    let mut transaction = repo.begin().await.unwrap();
+
+    transaction.save_book(&added).await.unwrap();
+
+    let stored = transaction.get_book_by_id(OWNER, added.id).await.unwrap();
+    assert_same_book(&stored.unwrap(), &added);
+    let result = transaction.delete_book(OWNER, foreign.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+    assert!(
+        transaction
+            .get_book_by_id(OWNER, foreign.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[allow(dead_code)]
+fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+}
+
+/// A book last updated at `updated_at`, added `days` before. A finished one
+/// was finished by that update.
+#[allow(dead_code)]
+fn book_updated(name: &str, updated_at: DateTime<Utc>, days: i64, finished: bool) -> Book {
+    let mut book = book(name);
+    book.updated_at = updated_at;
+    book.inserted_at = updated_at - Duration::days(days);
+    book.finished = finished;
+    if finished {
+        book.read_page = book.page_count;
+        book.finished_at = Some(updated_at);
+    }
+    book
+}
+
+#[allow(dead_code)]
+fn period(period: &str, books_finished: u64, pages_read: u64) -> PeriodStats {
+    PeriodStats {
+        period: period.to_string(),
+        books_finished,
+        pages_read,
+    }
+}
+
+#[allow(dead_code)]
+pub async fn stats_should_total_and_group_by_period(repo: Arc<dyn BookRepo>) {
+    let mut reading = book_updated("Buku A", at(2024, 1, 10), 1, false);
+    reading.reading = true;
+    for book in [
+        reading,
+        book_updated("Buku B", at(2024, 1, 20), 2, true),
+        book_updated("Buku C", at(2024, 3, 5), 4, true),
+        book_updated("Buku D", at(2025, 2, 1), 1, false),
+    ] {
+        repo.save_book(&book).await.unwrap();
+    }
+
+    let stats = repo
+        .get_stats(OWNER, &StatsRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(stats.total_books, 4);
+    assert_eq!(stats.finished_books, 2);
+    assert_eq!(stats.currently_reading, 1);
+    assert_eq!(stats.pages_reached, 250);
+    assert_eq!(
+        stats.periods,
+        vec![period("2024-01", 1, 0), period("2024-03", 1, 0)]
+    );
+    assert_eq!(
+        stats.average_completion_secs,
+        Some(Duration::days(3).num_seconds())
+    );
+
+    let by_year = StatsRequest {
+        period: StatsPeriod::Year,
+        ..StatsRequest::default()
+    };
+    let stats = repo.get_stats(OWNER, &by_year).await.unwrap();
+    assert_eq!(stats.periods, vec![period("2024", 2, 0)]);
+}
+
+#[allow(dead_code)]
+pub async fn stats_should_count_a_finish_when_it_happened(repo: Arc<dyn BookRepo>) {
+    let mut edited = book_updated("Buku A", at(2024, 6, 1), 0, true);
+    edited.inserted_at = at(2024, 1, 18);
+    edited.finished_at = Some(at(2024, 1, 20));
+    repo.save_book(&edited).await.unwrap();
+
+    let stats = repo
+        .get_stats(OWNER, &StatsRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(stats.periods, vec![period("2024-01", 1, 0)]);
+    assert_eq!(
+        stats.average_completion_secs,
+        Some(Duration::days(2).num_seconds())
+    );
+}
+
+#[allow(dead_code)]
+pub async fn stats_should_rank_authors_and_publishers(repo: Arc<dyn BookRepo>) {
+    for (author, publisher) in [
+        ("Tere Liye", "Gramedia"),
+        ("Andrea Hirata", "Bentang"),
+        ("Tere Liye", "Republika"),
+        ("Andrea Hirata", "Bentang"),
+        ("Dee Lestari", "Bentang"),
+        ("Tere Liye", ""),
+    ] {
+        let mut book = book("Buku");
+        book.author = author.to_string();
+        book.publisher = publisher.to_string();
+        repo.save_book(&book).await.unwrap();
+    }
+    let top = |name: &str, books| NameCount {
+        name: name.to_string(),
+        books,
+    };
+
+    let request = StatsRequest {
+        top: 2,
+        ..StatsRequest::default()
+    };
+    let stats = repo.get_stats(OWNER, &request).await.unwrap();
+
+    assert_eq!(
+        stats.top_authors,
+        vec![top("Tere Liye", 3), top("Andrea Hirata", 2)]
+    );
+    assert_eq!(
+        stats.top_publishers,
+        vec![top("Bentang", 3), top("Gramedia", 1)]
+    );
+}
+
+#[allow(dead_code)]
+pub async fn stats_should_skip_trash_and_other_owners(repo: Arc<dyn BookRepo>) {
+    save_foreign_book(&repo).await;
+    let trashed = book_updated("Buku Terhapus", at(2023, 6, 1), 1, true);
+    repo.save_book(&trashed).await.unwrap();
+    repo.delete_book(OWNER, trashed.id).await.unwrap();
+
+    let stats = repo
+        .get_stats(OWNER, &StatsRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(stats.total_books, 1);
+    assert_eq!(stats.finished_books, 0);
+    assert_eq!(stats.pages_reached, 25);
+    assert!(stats.periods.is_empty());
+    assert_eq!(stats.top_authors.len(), 1);
+    assert_eq!(stats.top_authors[0].books, 1);
+}
+
+#[allow(dead_code)]
+pub async fn stats_of_empty_shelf_should_be_zero(repo: Arc<dyn BookRepo>) {
+    let stats = repo
+        .get_stats(OWNER, &StatsRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(stats.total_books, 0);
+    assert_eq!(stats.finished_books, 0);
+    assert_eq!(stats.currently_reading, 0);
+    assert_eq!(stats.pages_reached, 0);
+    assert!(stats.periods.is_empty());
+    assert_eq!(stats.average_completion_secs, None);
+    assert!(stats.top_authors.is_empty());
+    assert!(stats.top_publishers.is_empty());
+}
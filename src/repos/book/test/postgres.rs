@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_book_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        book::{BookRepo, postgres::PgBookRepo, test::book_repo_conformance},
+        test::postgres_pool,
+    };
+
+    async fn repo() -> Arc<dyn BookRepo> {
+        Arc::new(PgBookRepo::new(postgres_pool().await))
+    }
+
+    book_repo_conformance!(repo());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_book_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        book::{BookRepo, sqlite::SqliteBookRepo, test::book_repo_conformance},
+        test::sqlite_pool,
+    };
+
+    async fn repo() -> Arc<dyn BookRepo> {
+        Arc::new(SqliteBookRepo::new(sqlite_pool().await))
+    }
+
+    book_repo_conformance!(repo());
+}
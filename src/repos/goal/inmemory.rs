@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Goal, GoalRepo, goal_not_found};
+
+#[derive(Default, Clone)]
+pub struct InMemoryGoalRepo(Arc<Mutex<Vec<Goal>>>);
+
+#[async_trait]
+impl GoalRepo for InMemoryGoalRepo {
+    async fn create_goal(&self, goal: &Goal) -> Result<Uuid, AppError> {
+        self.0.lock().await.push(goal.clone());
+        Ok(goal.id)
+    }
+    async fn get_goals(&self, owner: Uuid) -> Result<Vec<Goal>, AppError> {
+        let mut goals: Vec<Goal> = self
+            .0
+            .lock()
+            .await
+            .iter()
+            .filter(|goal| goal.owner_id == owner)
+            .cloned()
+            .collect();
+        goals.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(goals)
+    }
+    async fn get_goal(&self, owner: Uuid, id: Uuid) -> Result<Option<Goal>, AppError> {
+        Ok(self
+            .0
+            .lock()
+            .await
+            .iter()
+            .find(|goal| goal.id == id && goal.owner_id == owner)
+            .cloned())
+    }
+    async fn delete_goal(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut goals = self.0.lock().await;
+        let count = goals.len();
+        goals.retain(|goal| goal.id != id || goal.owner_id != owner);
+        if goals.len() == count {
+            return Err(goal_not_found("Target membaca gagal dihapus"));
+        }
+        Ok(id)
+    }
+}
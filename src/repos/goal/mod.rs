@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// What a goal counts.
+#[derive(Serialize, Deserialize, ToSchema, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum GoalMetric {
+    /// Books finished within the period.
+    Books,
+    /// Pages read in the sessions ending within the period.
+    Pages,
+}
+
+/// Reading `target` books or pages between `starts_at`, inclusive, and
+/// `ends_at`, exclusive.
+#[derive(Serialize, ToSchema, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Goal {
+    pub id: Uuid,
+    /// The user whose reading counts towards the goal.
+    pub owner_id: Uuid,
+    pub metric: GoalMetric,
+    pub target: u32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait GoalRepo: Send + Sync {
+    async fn create_goal(&self, _goal: &Goal) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Goals of `owner`, oldest first.
+    async fn get_goals(&self, _owner: Uuid) -> Result<Vec<Goal>, AppError> {
+        unimplemented!()
+    }
+    async fn get_goal(&self, _owner: Uuid, _id: Uuid) -> Result<Option<Goal>, AppError> {
+        unimplemented!()
+    }
+    /// Fails with 404 unless `owner` has a goal `id`.
+    async fn delete_goal(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+}
+
+pub(crate) fn goal_not_found(failure: &str) -> AppError {
+    let message = format!("{}. Id tidak ditemukan", failure);
+    AppError::ClientFail(axum::http::StatusCode::NOT_FOUND, message)
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use sqlx::{Row, postgres::PgPool, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Goal, GoalRepo, goal_not_found};
+
+#[derive(Clone)]
+pub struct PgGoalRepo(PgPool);
+
+impl PgGoalRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgGoalRepo(pool)
+    }
+}
+
+fn goal_from_row(row: &PgRow) -> Result<Goal, AppError> {
+    Ok(Goal {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        metric: row
+            .try_get("metric")
+            .map_err(|_e| AppError::DatabaseError)?,
+        target: u32::try_from(row.get::<i64, _>("target")).map_err(|_e| AppError::DatabaseError)?,
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        created_at: row.get("created_at"),
+    })
+}
+
+const COLUMNS: &str = "id, owner_id, metric, target, starts_at, ends_at, created_at";
+
+#[async_trait]
+impl GoalRepo for PgGoalRepo {
+    async fn create_goal(&self, goal: &Goal) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO goals ({}) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            COLUMNS
+        ))
+        .bind(goal.id)
+        .bind(goal.owner_id)
+        .bind(goal.metric)
+        .bind(i64::from(goal.target))
+        .bind(goal.starts_at)
+        .bind(goal.ends_at)
+        .bind(goal.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(goal.id)
+    }
+
+    async fn get_goals(&self, owner: Uuid) -> Result<Vec<Goal>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM goals WHERE owner_id = $1 ORDER BY created_at, id",
+            COLUMNS
+        ))
+        .bind(owner)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(goal_from_row)
+        .collect()
+    }
+
+    async fn get_goal(&self, owner: Uuid, id: Uuid) -> Result<Option<Goal>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM goals WHERE id = $1 AND owner_id = $2",
+            COLUMNS
+        ))
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(goal_from_row)
+        .transpose()
+    }
+
+    async fn delete_goal(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query("DELETE FROM goals WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner)
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(goal_not_found("Target membaca gagal dihapus"));
+        }
+        Ok(id)
+    }
+}
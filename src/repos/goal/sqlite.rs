@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Goal, GoalRepo, goal_not_found};
+
+#[derive(Clone)]
+pub struct SqliteGoalRepo(SqlitePool);
+
+impl SqliteGoalRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteGoalRepo(pool)
+    }
+}
+
+fn goal_from_row(row: &SqliteRow) -> Result<Goal, AppError> {
+    let uuid = |column: &str| {
+        Uuid::parse_str(row.get::<String, _>(column).as_str()).map_err(|_e| AppError::DatabaseError)
+    };
+    let timestamp = |column: &str| {
+        DateTime::parse_from_rfc3339(&row.get::<String, _>(column))
+            .map(|at| at.with_timezone(&Utc))
+            .map_err(|_e| AppError::DatabaseError)
+    };
+    Ok(Goal {
+        id: uuid("id")?,
+        owner_id: uuid("owner_id")?,
+        metric: row
+            .try_get("metric")
+            .map_err(|_e| AppError::DatabaseError)?,
+        target: u32::try_from(row.get::<i64, _>("target")).map_err(|_e| AppError::DatabaseError)?,
+        starts_at: timestamp("starts_at")?,
+        ends_at: timestamp("ends_at")?,
+        created_at: timestamp("created_at")?,
+    })
+}
+
+const COLUMNS: &str = "id, owner_id, metric, target, starts_at, ends_at, created_at";
+
+#[async_trait]
+impl GoalRepo for SqliteGoalRepo {
+    async fn create_goal(&self, goal: &Goal) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO goals ({}) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            COLUMNS
+        ))
+        .bind(goal.id.to_string())
+        .bind(goal.owner_id.to_string())
+        .bind(goal.metric)
+        .bind(i64::from(goal.target))
+        .bind(goal.starts_at.to_rfc3339())
+        .bind(goal.ends_at.to_rfc3339())
+        .bind(goal.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(goal.id)
+    }
+
+    async fn get_goals(&self, owner: Uuid) -> Result<Vec<Goal>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM goals WHERE owner_id = ? ORDER BY created_at, id",
+            COLUMNS
+        ))
+        .bind(owner.to_string())
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(goal_from_row)
+        .collect()
+    }
+
+    async fn get_goal(&self, owner: Uuid, id: Uuid) -> Result<Option<Goal>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM goals WHERE id = ? AND owner_id = ?",
+            COLUMNS
+        ))
+        .bind(id.to_string())
+        .bind(owner.to_string())
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(goal_from_row)
+        .transpose()
+    }
+
+    async fn delete_goal(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query("DELETE FROM goals WHERE id = ? AND owner_id = ?")
+            .bind(id.to_string())
+            .bind(owner.to_string())
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(goal_not_found("Target membaca gagal dihapus"));
+        }
+        Ok(id)
+    }
+}
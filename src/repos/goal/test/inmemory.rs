@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod inmemory_goal_repo {
+    use std::sync::Arc;
+
+    use crate::repos::goal::{GoalRepo, inmemory::InMemoryGoalRepo, test::goal_repo_conformance};
+
+    async fn repo() -> Arc<dyn GoalRepo> {
+        Arc::new(InMemoryGoalRepo::default())
+    }
+
+    goal_repo_conformance!(repo());
+}
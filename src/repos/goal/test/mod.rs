@@ -0,0 +1,132 @@
+//! Behaviour every [`GoalRepo`] backend must share. Each backend module
+//! runs the whole suite through [`goal_repo_conformance`].
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{Duration, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::goal::{Goal, GoalMetric, GoalRepo},
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! goal_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::goal::test::goal_repo_conformance!(@cases $repo;
+            created_goal_should_be_retrievable,
+            goals_should_be_listed_oldest_first,
+            other_owners_goals_should_not_be_found,
+            deleted_goal_should_be_gone,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::goal::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use goal_repo_conformance;
+
+/// A goal of `owner` over the next 30 days, created `minutes_ago`. Kept to
+/// milliseconds so every backend stores the times as they are.
+#[allow(dead_code)]
+fn goal(owner: Uuid, metric: GoalMetric, minutes_ago: i64) -> Goal {
+    let now = Utc::now().trunc_subsecs(3);
+    Goal {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        metric,
+        target: 12,
+        starts_at: now,
+        ends_at: now + Duration::days(30),
+        created_at: now - Duration::minutes(minutes_ago),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn created_goal_should_be_retrievable(repo: Arc<dyn GoalRepo>) {
+    let created = goal(Uuid::new_v4(), GoalMetric::Pages, 0);
+
+    let id = repo.create_goal(&created).await.unwrap();
+    assert_eq!(id, created.id);
+
+    let stored = repo
+        .get_goal(created.owner_id, created.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.owner_id, created.owner_id);
+    assert_eq!(stored.metric, GoalMetric::Pages);
+    assert_eq!(stored.target, 12);
+    assert_eq!(stored.starts_at, created.starts_at);
+    assert_eq!(stored.ends_at, created.ends_at);
+    assert_eq!(stored.created_at, created.created_at);
+}
+
+#[allow(dead_code)]
+pub async fn goals_should_be_listed_oldest_first(repo: Arc<dyn GoalRepo>) {
+    let owner = Uuid::new_v4();
+    let newest = goal(owner, GoalMetric::Books, 1);
+    let oldest = goal(owner, GoalMetric::Pages, 60);
+    for goal in [&newest, &oldest] {
+        repo.create_goal(goal).await.unwrap();
+    }
+
+    let goals = repo.get_goals(owner).await.unwrap();
+
+    let ids: Vec<Uuid> = goals.iter().map(|goal| goal.id).collect();
+    assert_eq!(ids, vec![oldest.id, newest.id]);
+}
+
+#[allow(dead_code)]
+pub async fn other_owners_goals_should_not_be_found(repo: Arc<dyn GoalRepo>) {
+    let foreign = goal(Uuid::new_v4(), GoalMetric::Books, 0);
+    repo.create_goal(&foreign).await.unwrap();
+    let owner = Uuid::new_v4();
+
+    assert!(repo.get_goal(owner, foreign.id).await.unwrap().is_none());
+    assert!(repo.get_goals(owner).await.unwrap().is_empty());
+    let result = repo.delete_goal(owner, foreign.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
+
+#[allow(dead_code)]
+pub async fn deleted_goal_should_be_gone(repo: Arc<dyn GoalRepo>) {
+    let deleted = goal(Uuid::new_v4(), GoalMetric::Books, 0);
+    repo.create_goal(&deleted).await.unwrap();
+
+    let id = repo
+        .delete_goal(deleted.owner_id, deleted.id)
+        .await
+        .unwrap();
+
+    assert_eq!(id, deleted.id);
+    assert!(
+        repo.get_goal(deleted.owner_id, deleted.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let result = repo.delete_goal(deleted.owner_id, deleted.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
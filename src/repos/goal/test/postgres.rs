@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_goal_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        goal::{GoalRepo, postgres::PgGoalRepo, test::goal_repo_conformance},
+        test::postgres_pool,
+    };
+
+    async fn repo() -> Arc<dyn GoalRepo> {
+        Arc::new(PgGoalRepo::new(postgres_pool().await))
+    }
+
+    goal_repo_conformance!(repo());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_goal_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        goal::{GoalRepo, sqlite::SqliteGoalRepo, test::goal_repo_conformance},
+        test::sqlite_pool,
+    };
+
+    async fn repo() -> Arc<dyn GoalRepo> {
+        Arc::new(SqliteGoalRepo::new(sqlite_pool().await))
+    }
+
+    goal_repo_conformance!(repo());
+}
@@ -0,0 +1,371 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::Utc;
+#[cfg(feature = "postgres")]
+use sqlx::postgres::PgPool;
+use sqlx::{
+    Executor, Row,
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
+};
+
+pub mod test;
+
+/// A schema change embedded in the binary, applied in `version` order.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_books",
+        sql: include_str!("../../../migrations/sqlite/0001_create_books.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "create_books_fts",
+        sql: include_str!("../../../migrations/sqlite/0002_create_books_fts.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_users",
+        sql: include_str!("../../../migrations/sqlite/0003_create_users.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "add_books_owner",
+        sql: include_str!("../../../migrations/sqlite/0004_add_books_owner.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "create_auth_tokens",
+        sql: include_str!("../../../migrations/sqlite/0005_create_auth_tokens.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "add_users_role",
+        sql: include_str!("../../../migrations/sqlite/0006_add_users_role.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "create_api_keys",
+        sql: include_str!("../../../migrations/sqlite/0007_create_api_keys.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "add_books_version",
+        sql: include_str!("../../../migrations/sqlite/0008_add_books_version.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "add_books_deleted_at",
+        sql: include_str!("../../../migrations/sqlite/0009_add_books_deleted_at.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "create_audit_log",
+        sql: include_str!("../../../migrations/sqlite/0010_create_audit_log.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "create_webhooks",
+        sql: include_str!("../../../migrations/sqlite/0011_create_webhooks.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "create_reading_sessions",
+        sql: include_str!("../../../migrations/sqlite/0012_create_reading_sessions.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "create_goals",
+        sql: include_str!("../../../migrations/sqlite/0013_create_goals.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "add_books_finished_at",
+        sql: include_str!("../../../migrations/sqlite/0014_add_books_finished_at.sql"),
+    },
+];
+
+#[cfg(feature = "postgres")]
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_books",
+        sql: include_str!("../../../migrations/postgres/0001_create_books.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "add_books_search",
+        sql: include_str!("../../../migrations/postgres/0002_add_books_search.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_users",
+        sql: include_str!("../../../migrations/postgres/0003_create_users.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "add_books_owner",
+        sql: include_str!("../../../migrations/postgres/0004_add_books_owner.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "create_auth_tokens",
+        sql: include_str!("../../../migrations/postgres/0005_create_auth_tokens.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "add_users_role",
+        sql: include_str!("../../../migrations/postgres/0006_add_users_role.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "create_api_keys",
+        sql: include_str!("../../../migrations/postgres/0007_create_api_keys.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "add_books_version",
+        sql: include_str!("../../../migrations/postgres/0008_add_books_version.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "add_books_deleted_at",
+        sql: include_str!("../../../migrations/postgres/0009_add_books_deleted_at.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "create_audit_log",
+        sql: include_str!("../../../migrations/postgres/0010_create_audit_log.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "create_webhooks",
+        sql: include_str!("../../../migrations/postgres/0011_create_webhooks.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "create_reading_sessions",
+        sql: include_str!("../../../migrations/postgres/0012_create_reading_sessions.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "create_goals",
+        sql: include_str!("../../../migrations/postgres/0013_create_goals.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "add_books_finished_at",
+        sql: include_str!("../../../migrations/postgres/0014_add_books_finished_at.sql"),
+    },
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "Database schema version {database} is newer than the latest version {supported} known to this binary"
+    )]
+    DatabaseTooNew { database: i64, supported: i64 },
+    #[error("Database has {0} pending migration(s), run `migrate` first")]
+    Pending(usize),
+    #[error("Migration failed: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: &'static str,
+    pub applied: bool,
+}
+
+/// Opens the SQLite database at `path`, creating the file when missing.
+/// `:memory:` is kept on a single connection so every query sees the same
+/// database.
+pub async fn connect_sqlite(path: &str) -> Result<SqlitePool, sqlx::Error> {
+    if path == ":memory:" {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::from_str("sqlite::memory:")?)
+            .await
+    } else {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        SqlitePool::connect_with(options).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub async fn connect_postgres(url: &str) -> Result<PgPool, sqlx::Error> {
+    PgPool::connect(url).await
+}
+
+fn latest(migrations: &[Migration]) -> i64 {
+    migrations.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// A database the migrations can be recorded in and applied to.
+#[async_trait]
+pub trait MigrationTarget: Send + Sync {
+    /// Versions recorded in `schema_migrations`, creating the table first.
+    async fn applied_versions(&self) -> Result<Vec<i64>, sqlx::Error>;
+    /// Runs `migration` and records it in a single transaction.
+    async fn apply(&self, migration: &Migration) -> Result<(), sqlx::Error>;
+}
+
+#[async_trait]
+impl MigrationTarget for SqlitePool {
+    async fn applied_versions(&self) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(self)
+        .await?;
+
+        let rows = sqlx::query("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(self)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("version")).collect())
+    }
+
+    async fn apply(&self, migration: &Migration) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        tx.execute(migration.sql).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, name, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl MigrationTarget for PgPool {
+    async fn applied_versions(&self) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(self)
+        .await?;
+
+        let rows = sqlx::query("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(self)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("version")).collect())
+    }
+
+    async fn apply(&self, migration: &Migration) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        tx.execute(migration.sql).await?;
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES ($1, $2, $3)",
+        )
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+}
+
+/// Lists every known migration and whether it has been applied, refusing
+/// databases written by a newer binary.
+pub async fn status(
+    target: &(impl MigrationTarget + ?Sized),
+    migrations: &[Migration],
+) -> Result<Vec<MigrationStatus>, MigrationError> {
+    let applied = target.applied_versions().await?;
+
+    let supported = latest(migrations);
+    if let Some(&database) = applied.iter().max()
+        && database > supported
+    {
+        return Err(MigrationError::DatabaseTooNew {
+            database,
+            supported,
+        });
+    }
+
+    Ok(migrations
+        .iter()
+        .map(|m| MigrationStatus {
+            version: m.version,
+            name: m.name,
+            applied: applied.contains(&m.version),
+        })
+        .collect())
+}
+
+/// Applies every pending migration, each in its own transaction, and
+/// returns the versions that ran.
+pub async fn migrate(
+    target: &(impl MigrationTarget + ?Sized),
+    migrations: &[Migration],
+) -> Result<Vec<i64>, MigrationError> {
+    let pending: Vec<&Migration> = status(target, migrations)
+        .await?
+        .iter()
+        .filter(|s| !s.applied)
+        .filter_map(|s| migrations.iter().find(|m| m.version == s.version))
+        .collect();
+
+    let mut ran = Vec::new();
+    for migration in pending {
+        target.apply(migration).await?;
+
+        tracing::info!(
+            "Applied migration {:04}_{}",
+            migration.version,
+            migration.name
+        );
+        ran.push(migration.version);
+    }
+
+    Ok(ran)
+}
+
+/// Startup check: applies pending migrations when `auto_apply` is set,
+/// otherwise refuses to run against an outdated schema.
+pub async fn prepare(
+    target: &(impl MigrationTarget + ?Sized),
+    migrations: &[Migration],
+    auto_apply: bool,
+) -> Result<(), MigrationError> {
+    if auto_apply {
+        migrate(target, migrations).await?;
+        return Ok(());
+    }
+
+    let pending = status(target, migrations)
+        .await?
+        .iter()
+        .filter(|s| !s.applied)
+        .count();
+    if pending > 0 {
+        return Err(MigrationError::Pending(pending));
+    }
+    Ok(())
+}
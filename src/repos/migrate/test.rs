@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod sqlite_migrations {
+    use sqlx::{Row, sqlite::SqlitePool};
+
+    use crate::repos::migrate::{
+        Migration, MigrationError, SQLITE_MIGRATIONS, connect_sqlite, migrate, prepare, status,
+    };
+
+    async fn memory_pool() -> SqlitePool {
+        connect_sqlite(":memory:").await.unwrap()
+    }
+
+    async fn table_exists(pool: &SqlitePool, name: &str) -> bool {
+        sqlx::query("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn migrate_should_create_books_and_record_version() {
+        let pool = memory_pool().await;
+
+        let ran = migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+
+        assert_eq!(ran, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert!(table_exists(&pool, "books").await);
+        let row = sqlx::query("SELECT version, name FROM schema_migrations ORDER BY version")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<i64, _>("version"), 1);
+        assert_eq!(row.get::<String, _>("name"), "create_books");
+    }
+
+    #[tokio::test]
+    async fn migrate_twice_should_be_a_noop() {
+        let pool = memory_pool().await;
+        migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+
+        let ran = migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+
+        assert!(ran.is_empty());
+    }
+
+    #[tokio::test]
+    async fn migrations_should_run_in_version_order() {
+        const MIGRATIONS: &[Migration] = &[
+            Migration {
+                version: 1,
+                name: "first",
+                sql: "CREATE TABLE a (id INTEGER);",
+            },
+            Migration {
+                version: 2,
+                name: "second",
+                sql: "ALTER TABLE a ADD COLUMN name TEXT;",
+            },
+        ];
+        let pool = memory_pool().await;
+        migrate(&pool, &MIGRATIONS[..1]).await.unwrap();
+
+        let ran = migrate(&pool, MIGRATIONS).await.unwrap();
+
+        assert_eq!(ran, vec![2]);
+        let statuses = status(&pool, MIGRATIONS).await.unwrap();
+        assert!(statuses.iter().all(|s| s.applied));
+    }
+
+    #[tokio::test]
+    async fn failed_migration_should_not_be_recorded() {
+        const MIGRATIONS: &[Migration] = &[Migration {
+            version: 1,
+            name: "broken",
+            sql: "CREATE TABLE a (id INTEGER); NOT SQL;",
+        }];
+        let pool = memory_pool().await;
+
+        assert!(migrate(&pool, MIGRATIONS).await.is_err());
+
+        assert!(!table_exists(&pool, "a").await);
+        assert!(!status(&pool, MIGRATIONS).await.unwrap()[0].applied);
+    }
+
+    #[tokio::test]
+    async fn legacy_database_should_keep_its_books() {
+        let pool = memory_pool().await;
+        sqlx::raw_sql(SQLITE_MIGRATIONS[0].sql)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO books VALUES ('id', 'Buku A', 2010, 'a', 's', 'p', 100, 25, 0, 0, 'now', 'now')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+
+        let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM books")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("count");
+        assert_eq!(count, 1);
+        let indexed: i64 =
+            sqlx::query("SELECT COUNT(*) AS count FROM books_fts WHERE books_fts MATCH 'buku'")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+                .get("count");
+        assert_eq!(indexed, 1);
+    }
+
+    #[tokio::test]
+    async fn search_index_should_follow_book_writes() {
+        let pool = memory_pool().await;
+        migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+        let insert = "INSERT OR REPLACE INTO books VALUES ('id', ?, 2010, 'a', 's', 'p', 100, 25, 0, 0, 'now', 'now', 'owner', 1, NULL, NULL)";
+        let indexed = |term: &'static str| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query("SELECT book_id FROM books_fts WHERE books_fts MATCH ?")
+                    .bind(term)
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap()
+                    .len()
+            }
+        };
+
+        sqlx::query(insert)
+            .bind("Buku Lama")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(insert)
+            .bind("Buku Baru")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(indexed("lama").await, 0);
+        assert_eq!(indexed("baru").await, 1);
+
+        sqlx::query("DELETE FROM books")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(indexed("baru").await, 0);
+    }
+
+    #[tokio::test]
+    async fn newer_database_should_be_refused() {
+        let pool = memory_pool().await;
+        migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (999, 'future', 'now')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = prepare(&pool, SQLITE_MIGRATIONS, true).await;
+
+        assert!(matches!(
+            result,
+            Err(MigrationError::DatabaseTooNew { database: 999, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn pending_migrations_without_auto_apply_should_be_refused() {
+        let pool = memory_pool().await;
+
+        let result = prepare(&pool, SQLITE_MIGRATIONS, false).await;
+
+        assert!(matches!(result, Err(MigrationError::Pending(14))));
+        assert!(!table_exists(&pool, "books").await);
+    }
+}
@@ -1 +1,10 @@
+pub mod api_key;
+pub mod audit;
 pub mod book;
+pub mod goal;
+pub mod migrate;
+pub mod session;
+pub mod test;
+pub mod token;
+pub mod user;
+pub mod webhook;
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::stats::{PeriodPages, StatsPeriod};
+
+use super::{ReadingSession, SessionFilter, SessionRepo};
+
+#[derive(Default, Clone)]
+pub struct InMemorySessionRepo(Arc<Mutex<Vec<ReadingSession>>>);
+
+#[async_trait]
+impl SessionRepo for InMemorySessionRepo {
+    async fn record_session(&self, session: &ReadingSession) -> Result<Uuid, AppError> {
+        self.0.lock().await.push(session.clone());
+        Ok(session.id)
+    }
+    async fn delete_session(&self, owner: Uuid, id: Uuid) -> Result<(), AppError> {
+        self.0
+            .lock()
+            .await
+            .retain(|session| !(session.owner_id == owner && session.id == id));
+        Ok(())
+    }
+    async fn get_sessions(
+        &self,
+        owner: Uuid,
+        filter: &SessionFilter,
+    ) -> Result<Vec<ReadingSession>, AppError> {
+        let mut sessions: Vec<ReadingSession> = self
+            .0
+            .lock()
+            .await
+            .iter()
+            .filter(|session| session.owner_id == owner && filter.matches(session))
+            .cloned()
+            .collect();
+        sessions.sort_by(|a, b| a.ended_at.cmp(&b.ended_at).then(a.id.cmp(&b.id)));
+        Ok(sessions)
+    }
+    async fn get_pages_read(
+        &self,
+        owner: Uuid,
+        period: StatsPeriod,
+    ) -> Result<Vec<PeriodPages>, AppError> {
+        let mut pages: BTreeMap<String, u64> = BTreeMap::new();
+        for session in self.0.lock().await.iter() {
+            if session.owner_id == owner && session.pages_read() > 0 {
+                *pages.entry(period.label(session.ended_at)).or_default() +=
+                    session.pages_read() as u64;
+            }
+        }
+        Ok(pages
+            .into_iter()
+            .map(|(period, pages)| PeriodPages { period, pages })
+            .collect())
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::stats::{PeriodPages, StatsPeriod};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// One sitting with a book, reading from `start_page` up to `end_page`.
+#[derive(Serialize, ToSchema, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingSession {
+    pub id: Uuid,
+    /// The user whose shelf the book is on.
+    pub owner_id: Uuid,
+    pub book_id: Uuid,
+    pub start_page: i32,
+    pub end_page: i32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ReadingSession {
+    pub fn pages_read(&self) -> i32 {
+        self.end_page - self.start_page
+    }
+}
+
+/// Narrows [`SessionRepo::get_sessions`] to sessions ending in a period,
+/// every field left `None` matches all of them. `since` is inclusive,
+/// `until` exclusive.
+#[derive(Default, Clone, Debug)]
+pub struct SessionFilter {
+    pub book_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &ReadingSession) -> bool {
+        self.book_id.is_none_or(|book| session.book_id == book)
+            && self.since.is_none_or(|since| session.ended_at >= since)
+            && self.until.is_none_or(|until| session.ended_at < until)
+    }
+}
+
+/// Reading sessions, kept when the books they were recorded on are purged.
+#[async_trait]
+pub trait SessionRepo: Send + Sync {
+    async fn record_session(&self, _session: &ReadingSession) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Removes a session of `owner`, doing nothing when there is none.
+    async fn delete_session(&self, _owner: Uuid, _id: Uuid) -> Result<(), AppError> {
+        unimplemented!()
+    }
+    /// Sessions of `owner` matching `filter`, in the order they ended.
+    async fn get_sessions(
+        &self,
+        _owner: Uuid,
+        _filter: &SessionFilter,
+    ) -> Result<Vec<ReadingSession>, AppError> {
+        unimplemented!()
+    }
+    /// Pages read by `owner` per period the sessions ended in, oldest first,
+    /// leaving out periods without any.
+    async fn get_pages_read(
+        &self,
+        _owner: Uuid,
+        _period: StatsPeriod,
+    ) -> Result<Vec<PeriodPages>, AppError> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, Row, postgres::PgPool, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::stats::{PeriodPages, StatsPeriod};
+
+use super::{ReadingSession, SessionFilter, SessionRepo};
+
+#[derive(Clone)]
+pub struct PgSessionRepo(PgPool);
+
+impl PgSessionRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgSessionRepo(pool)
+    }
+}
+
+fn session_from_row(row: &PgRow) -> ReadingSession {
+    ReadingSession {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        book_id: row.get("book_id"),
+        start_page: row.get("start_page"),
+        end_page: row.get("end_page"),
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+const COLUMNS: &str =
+    "id, owner_id, book_id, start_page, end_page, started_at, ended_at, created_at";
+
+#[async_trait]
+impl SessionRepo for PgSessionRepo {
+    async fn record_session(&self, session: &ReadingSession) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO reading_sessions ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            COLUMNS
+        ))
+        .bind(session.id)
+        .bind(session.owner_id)
+        .bind(session.book_id)
+        .bind(session.start_page)
+        .bind(session.end_page)
+        .bind(session.started_at)
+        .bind(session.ended_at)
+        .bind(session.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(session.id)
+    }
+
+    async fn delete_session(&self, owner: Uuid, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM reading_sessions WHERE owner_id = $1 AND id = $2")
+            .bind(owner)
+            .bind(id)
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        Ok(())
+    }
+
+    async fn get_sessions(
+        &self,
+        owner: Uuid,
+        filter: &SessionFilter,
+    ) -> Result<Vec<ReadingSession>, AppError> {
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {} FROM reading_sessions WHERE owner_id = ",
+            COLUMNS
+        ));
+        query.push_bind(owner);
+        if let Some(book) = filter.book_id {
+            query.push(" AND book_id = ").push_bind(book);
+        }
+        if let Some(at) = filter.since {
+            query.push(" AND ended_at >= ").push_bind(at);
+        }
+        if let Some(at) = filter.until {
+            query.push(" AND ended_at < ").push_bind(at);
+        }
+        query.push(" ORDER BY ended_at, id");
+
+        Ok(query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(session_from_row)
+            .collect())
+    }
+
+    async fn get_pages_read(
+        &self,
+        owner: Uuid,
+        period: StatsPeriod,
+    ) -> Result<Vec<PeriodPages>, AppError> {
+        let format = match period {
+            StatsPeriod::Month => "YYYY-MM",
+            StatsPeriod::Year => "YYYY",
+        };
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                to_char(ended_at AT TIME ZONE 'UTC', $1) AS period,
+                SUM(end_page - start_page)::BIGINT AS pages
+            FROM reading_sessions WHERE owner_id = $2 AND end_page > start_page
+            GROUP BY period
+            ORDER BY period ASC
+            "#,
+        )
+        .bind(format)
+        .bind(owner)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PeriodPages {
+                period: row.get("period"),
+                pages: row.get::<i64, _>("pages") as u64,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Row, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::stats::{PeriodPages, StatsPeriod};
+
+use super::{ReadingSession, SessionFilter, SessionRepo};
+
+#[derive(Clone)]
+pub struct SqliteSessionRepo(SqlitePool);
+
+impl SqliteSessionRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteSessionRepo(pool)
+    }
+}
+
+fn session_from_row(row: &SqliteRow) -> Result<ReadingSession, AppError> {
+    let uuid = |column: &str| {
+        Uuid::parse_str(row.get::<String, _>(column).as_str()).map_err(|_e| AppError::DatabaseError)
+    };
+    let timestamp = |column: &str| {
+        DateTime::parse_from_rfc3339(&row.get::<String, _>(column))
+            .map(|at| at.with_timezone(&Utc))
+            .map_err(|_e| AppError::DatabaseError)
+    };
+    Ok(ReadingSession {
+        id: uuid("id")?,
+        owner_id: uuid("owner_id")?,
+        book_id: uuid("book_id")?,
+        start_page: row.get("start_page"),
+        end_page: row.get("end_page"),
+        started_at: timestamp("started_at")?,
+        ended_at: timestamp("ended_at")?,
+        created_at: timestamp("created_at")?,
+    })
+}
+
+const COLUMNS: &str =
+    "id, owner_id, book_id, start_page, end_page, started_at, ended_at, created_at";
+
+#[async_trait]
+impl SessionRepo for SqliteSessionRepo {
+    async fn record_session(&self, session: &ReadingSession) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO reading_sessions ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            COLUMNS
+        ))
+        .bind(session.id.to_string())
+        .bind(session.owner_id.to_string())
+        .bind(session.book_id.to_string())
+        .bind(session.start_page)
+        .bind(session.end_page)
+        .bind(session.started_at.to_rfc3339())
+        .bind(session.ended_at.to_rfc3339())
+        .bind(session.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(session.id)
+    }
+
+    async fn delete_session(&self, owner: Uuid, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM reading_sessions WHERE owner_id = ? AND id = ?")
+            .bind(owner.to_string())
+            .bind(id.to_string())
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        Ok(())
+    }
+
+    async fn get_sessions(
+        &self,
+        owner: Uuid,
+        filter: &SessionFilter,
+    ) -> Result<Vec<ReadingSession>, AppError> {
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {} FROM reading_sessions WHERE owner_id = ",
+            COLUMNS
+        ));
+        query.push_bind(owner.to_string());
+        if let Some(book) = filter.book_id {
+            query.push(" AND book_id = ").push_bind(book.to_string());
+        }
+        // RFC 3339 text in UTC compares chronologically.
+        if let Some(at) = filter.since {
+            query.push(" AND ended_at >= ").push_bind(at.to_rfc3339());
+        }
+        if let Some(at) = filter.until {
+            query.push(" AND ended_at < ").push_bind(at.to_rfc3339());
+        }
+        query.push(" ORDER BY ended_at, id");
+
+        query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(session_from_row)
+            .collect()
+    }
+
+    async fn get_pages_read(
+        &self,
+        owner: Uuid,
+        period: StatsPeriod,
+    ) -> Result<Vec<PeriodPages>, AppError> {
+        // The period is the leading `YYYY-MM` or `YYYY` of the RFC 3339 text.
+        let label_len = match period {
+            StatsPeriod::Month => 7,
+            StatsPeriod::Year => 4,
+        };
+        let rows = sqlx::query(
+            r#"
+            SELECT substr(ended_at, 1, ?) AS period, SUM(end_page - start_page) AS pages
+            FROM reading_sessions WHERE owner_id = ? AND end_page > start_page
+            GROUP BY period
+            ORDER BY period ASC
+            "#,
+        )
+        .bind(label_len)
+        .bind(owner.to_string())
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PeriodPages {
+                period: row.get("period"),
+                pages: row.get::<i64, _>("pages") as u64,
+            })
+            .collect())
+    }
+}
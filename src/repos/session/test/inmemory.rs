@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod inmemory_session_repo {
+    use std::sync::Arc;
+
+    use crate::repos::session::{
+        SessionRepo, inmemory::InMemorySessionRepo, test::session_repo_conformance,
+    };
+
+    async fn repo() -> Arc<dyn SessionRepo> {
+        Arc::new(InMemorySessionRepo::default())
+    }
+
+    session_repo_conformance!(repo());
+}
@@ -0,0 +1,214 @@
+//! Behaviour every [`SessionRepo`] backend must share. Each backend module
+//! runs the whole suite through [`session_repo_conformance`].
+
+use std::sync::Arc;
+
+use chrono::{Duration, SubsecRound, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::repos::{
+    book::stats::{PeriodPages, StatsPeriod},
+    session::{ReadingSession, SessionFilter, SessionRepo},
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! session_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::session::test::session_repo_conformance!(@cases $repo;
+            recorded_session_should_be_listed_as_it_was,
+            sessions_should_be_listed_in_the_order_they_ended,
+            sessions_should_be_filtered,
+            deleted_session_should_be_gone,
+            pages_read_should_be_grouped_by_when_sessions_ended,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::session::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use session_repo_conformance;
+
+/// Half an hour on `book` of `owner` from `start_page` to `end_page`, ending
+/// `minutes_ago`. Kept to milliseconds so every backend stores the times as
+/// they are.
+#[allow(dead_code)]
+fn session(
+    owner: Uuid,
+    book: Uuid,
+    start_page: i32,
+    end_page: i32,
+    minutes_ago: i64,
+) -> ReadingSession {
+    let ended_at = (Utc::now() - Duration::minutes(minutes_ago)).trunc_subsecs(3);
+    ReadingSession {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        book_id: book,
+        start_page,
+        end_page,
+        started_at: ended_at - Duration::minutes(30),
+        ended_at,
+        created_at: Utc::now().trunc_subsecs(3),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn recorded_session_should_be_listed_as_it_was(repo: Arc<dyn SessionRepo>) {
+    let recorded = session(Uuid::new_v4(), Uuid::new_v4(), 10, 42, 5);
+
+    let id = repo.record_session(&recorded).await.unwrap();
+    assert_eq!(id, recorded.id);
+
+    let sessions = repo
+        .get_sessions(recorded.owner_id, &SessionFilter::default())
+        .await
+        .unwrap();
+
+    assert_eq!(sessions.len(), 1);
+    let stored = &sessions[0];
+    assert_eq!(stored.id, recorded.id);
+    assert_eq!(stored.book_id, recorded.book_id);
+    assert_eq!(stored.start_page, 10);
+    assert_eq!(stored.end_page, 42);
+    assert_eq!(stored.pages_read(), 32);
+    assert_eq!(stored.started_at, recorded.started_at);
+    assert_eq!(stored.ended_at, recorded.ended_at);
+    assert_eq!(stored.created_at, recorded.created_at);
+}
+
+#[allow(dead_code)]
+pub async fn sessions_should_be_listed_in_the_order_they_ended(repo: Arc<dyn SessionRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    let middle = session(owner, book, 20, 30, 60);
+    let last = session(owner, book, 30, 40, 10);
+    let first = session(owner, book, 0, 20, 120);
+    for session in [&middle, &last, &first] {
+        repo.record_session(session).await.unwrap();
+    }
+
+    let sessions = repo
+        .get_sessions(owner, &SessionFilter::default())
+        .await
+        .unwrap();
+
+    let ids: Vec<Uuid> = sessions.iter().map(|session| session.id).collect();
+    assert_eq!(ids, vec![first.id, middle.id, last.id]);
+}
+
+#[allow(dead_code)]
+pub async fn sessions_should_be_filtered(repo: Arc<dyn SessionRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    let old = session(owner, book, 0, 10, 300);
+    let recent = session(owner, book, 10, 20, 30);
+    let other_book = session(owner, Uuid::new_v4(), 0, 5, 60);
+    let foreign = session(Uuid::new_v4(), book, 0, 50, 30);
+    for session in [&old, &recent, &other_book, &foreign] {
+        repo.record_session(session).await.unwrap();
+    }
+
+    let ids = |filter: SessionFilter| {
+        let repo = repo.clone();
+        async move {
+            repo.get_sessions(owner, &filter)
+                .await
+                .unwrap()
+                .iter()
+                .map(|session| session.id)
+                .collect::<Vec<Uuid>>()
+        }
+    };
+
+    let by_book = SessionFilter {
+        book_id: Some(book),
+        ..SessionFilter::default()
+    };
+    assert_eq!(ids(by_book).await, vec![old.id, recent.id]);
+    let by_time = SessionFilter {
+        since: Some(other_book.ended_at),
+        until: Some(recent.ended_at + Duration::milliseconds(1)),
+        ..SessionFilter::default()
+    };
+    assert_eq!(ids(by_time).await, vec![other_book.id, recent.id]);
+    let until = SessionFilter {
+        until: Some(recent.ended_at),
+        ..SessionFilter::default()
+    };
+    assert_eq!(ids(until).await, vec![old.id, other_book.id]);
+}
+
+#[allow(dead_code)]
+pub async fn deleted_session_should_be_gone(repo: Arc<dyn SessionRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    let deleted = session(owner, book, 0, 10, 60);
+    let kept = session(owner, book, 10, 20, 30);
+    for session in [&deleted, &kept] {
+        repo.record_session(session).await.unwrap();
+    }
+
+    repo.delete_session(Uuid::new_v4(), kept.id).await.unwrap();
+    repo.delete_session(owner, deleted.id).await.unwrap();
+    repo.delete_session(owner, deleted.id).await.unwrap();
+
+    let sessions = repo
+        .get_sessions(owner, &SessionFilter::default())
+        .await
+        .unwrap();
+    let ids: Vec<Uuid> = sessions.iter().map(|session| session.id).collect();
+    assert_eq!(ids, vec![kept.id]);
+}
+
+#[allow(dead_code)]
+pub async fn pages_read_should_be_grouped_by_when_sessions_ended(repo: Arc<dyn SessionRepo>) {
+    let owner = Uuid::new_v4();
+    let book = Uuid::new_v4();
+    for (start_page, end_page, year, month) in [
+        (0, 30, 2024, 1),
+        (30, 50, 2024, 1),
+        (50, 50, 2024, 2),
+        (50, 80, 2024, 3),
+        (80, 100, 2025, 1),
+    ] {
+        let mut read = session(owner, book, start_page, end_page, 0);
+        read.ended_at = Utc.with_ymd_and_hms(year, month, 15, 12, 0, 0).unwrap();
+        read.started_at = read.ended_at - Duration::minutes(30);
+        repo.record_session(&read).await.unwrap();
+    }
+    repo.record_session(&session(Uuid::new_v4(), book, 0, 100, 5))
+        .await
+        .unwrap();
+    let pages = |period: &str, pages| PeriodPages {
+        period: period.to_string(),
+        pages,
+    };
+
+    let by_month = repo
+        .get_pages_read(owner, StatsPeriod::Month)
+        .await
+        .unwrap();
+    assert_eq!(
+        by_month,
+        vec![
+            pages("2024-01", 50),
+            pages("2024-03", 30),
+            pages("2025-01", 20)
+        ]
+    );
+    let by_year = repo.get_pages_read(owner, StatsPeriod::Year).await.unwrap();
+    assert_eq!(by_year, vec![pages("2024", 80), pages("2025", 20)]);
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_session_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        session::{SessionRepo, postgres::PgSessionRepo, test::session_repo_conformance},
+        test::postgres_pool,
+    };
+
+    async fn repo() -> Arc<dyn SessionRepo> {
+        Arc::new(PgSessionRepo::new(postgres_pool().await))
+    }
+
+    session_repo_conformance!(repo());
+}
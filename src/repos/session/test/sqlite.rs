@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_session_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        session::{SessionRepo, sqlite::SqliteSessionRepo, test::session_repo_conformance},
+        test::sqlite_pool,
+    };
+
+    async fn repo() -> Arc<dyn SessionRepo> {
+        Arc::new(SqliteSessionRepo::new(sqlite_pool().await))
+    }
+
+    session_repo_conformance!(repo());
+}
@@ -0,0 +1,47 @@
+use sqlx::sqlite::SqlitePool;
+
+use crate::repos::migrate::{SQLITE_MIGRATIONS, connect_sqlite, migrate};
+
+/// Fresh in-memory SQLite database with every migration applied.
+#[allow(dead_code)]
+pub async fn sqlite_pool() -> SqlitePool {
+    let pool = connect_sqlite(":memory:").await.unwrap();
+    migrate(&pool, SQLITE_MIGRATIONS).await.unwrap();
+    pool
+}
+
+/// Migrated PostgreSQL pool isolated in a new schema of the database at
+/// `BOOKSHELF_TEST_POSTGRES_URL`. Schemas are left behind, so point it at a
+/// scratch database.
+#[cfg(feature = "postgres")]
+#[allow(dead_code)]
+pub async fn postgres_pool() -> sqlx::postgres::PgPool {
+    use std::str::FromStr;
+
+    use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+    use uuid::Uuid;
+
+    use crate::repos::migrate::POSTGRES_MIGRATIONS;
+
+    let url = std::env::var("BOOKSHELF_TEST_POSTGRES_URL")
+        .expect("BOOKSHELF_TEST_POSTGRES_URL must point at a scratch database");
+    let schema = format!("test_{}", Uuid::new_v4().simple());
+
+    let admin = PgPool::connect(&url).await.unwrap();
+    sqlx::query(&format!("CREATE SCHEMA {}", schema))
+        .execute(&admin)
+        .await
+        .unwrap();
+    admin.close().await;
+
+    let options = PgConnectOptions::from_str(&url)
+        .unwrap()
+        .options([("search_path", schema.as_str())]);
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await
+        .unwrap();
+    migrate(&pool, POSTGRES_MIGRATIONS).await.unwrap();
+    pool
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{RefreshToken, TokenRepo};
+
+#[derive(Default)]
+struct Tokens {
+    refresh: HashMap<String, RefreshToken>,
+    revoked: HashMap<Uuid, DateTime<Utc>>,
+}
+
+#[derive(Default, Clone)]
+pub struct InMemoryTokenRepo(Arc<Mutex<Tokens>>);
+
+#[async_trait]
+impl TokenRepo for InMemoryTokenRepo {
+    async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), AppError> {
+        let mut tokens = self.0.lock().await;
+        let now = Utc::now();
+        tokens.refresh.retain(|_, t| t.expires_at > now);
+        tokens
+            .refresh
+            .insert(token.token_hash.clone(), token.clone());
+        Ok(())
+    }
+    async fn take_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshToken>, AppError> {
+        Ok(self.0.lock().await.refresh.remove(token_hash))
+    }
+    async fn delete_refresh_token(
+        &self,
+        token_hash: &str,
+        user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let mut tokens = self.0.lock().await;
+        match tokens.refresh.get(token_hash) {
+            Some(token) if token.user_id == user_id => {
+                tokens.refresh.remove(token_hash);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut tokens = self.0.lock().await;
+        let now = Utc::now();
+        tokens.revoked.retain(|_, at| *at > now);
+        tokens.revoked.insert(jti, expires_at);
+        Ok(())
+    }
+    async fn is_access_token_revoked(&self, jti: Uuid) -> Result<bool, AppError> {
+        Ok(self.0.lock().await.revoked.contains_key(&jti))
+    }
+}
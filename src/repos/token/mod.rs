@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::AppError;
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// A refresh token as stored server-side, `token_hash` is the SHA-256 of
+/// the opaque value handed to the client.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct RefreshToken {
+    pub token_hash: String,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Server-side state of issued tokens. Expired entries are pruned on writes,
+/// callers still check `expires_at` of what they read.
+#[async_trait]
+pub trait TokenRepo: Send + Sync {
+    async fn save_refresh_token(&self, _token: &RefreshToken) -> Result<(), AppError> {
+        unimplemented!()
+    }
+    /// Removes and returns the token, so each one can be used only once.
+    async fn take_refresh_token(
+        &self,
+        _token_hash: &str,
+    ) -> Result<Option<RefreshToken>, AppError> {
+        unimplemented!()
+    }
+    /// Removes the token only when it was issued to `user_id`, answering
+    /// whether it did.
+    async fn delete_refresh_token(
+        &self,
+        _token_hash: &str,
+        _user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        unimplemented!()
+    }
+    /// Rejects the access token `jti` until `expires_at`, after which it is
+    /// rejected for having expired anyway.
+    async fn revoke_access_token(
+        &self,
+        _jti: Uuid,
+        _expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        unimplemented!()
+    }
+    async fn is_access_token_revoked(&self, _jti: Uuid) -> Result<bool, AppError> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgPool;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{RefreshToken, TokenRepo};
+
+#[derive(Clone)]
+pub struct PgTokenRepo(PgPool);
+
+impl PgTokenRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgTokenRepo(pool)
+    }
+}
+
+#[async_trait]
+impl TokenRepo for PgTokenRepo {
+    async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= now()")
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query(
+            "INSERT INTO refresh_tokens (token_hash, user_id, expires_at, created_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&token.token_hash)
+        .bind(token.user_id)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn take_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshToken>, AppError> {
+        sqlx::query_as::<_, RefreshToken>(
+            "DELETE FROM refresh_tokens WHERE token_hash = $1 RETURNING token_hash, user_id, expires_at, created_at",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        token_hash: &str,
+        user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let result =
+            sqlx::query("DELETE FROM refresh_tokens WHERE token_hash = $1 AND user_id = $2")
+                .bind(token_hash)
+                .bind(user_id)
+                .execute(&self.0)
+                .await
+                .map_err(|_e| AppError::DatabaseError)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= now()")
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query(
+            "INSERT INTO revoked_tokens (jti, expires_at) VALUES ($1, $2) ON CONFLICT (jti) DO UPDATE SET expires_at = EXCLUDED.expires_at",
+        )
+        .bind(jti)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn is_access_token_revoked(&self, jti: Uuid) -> Result<bool, AppError> {
+        let revoked: Option<i32> =
+            sqlx::query_scalar("SELECT 1 FROM revoked_tokens WHERE jti = $1")
+                .bind(jti)
+                .fetch_optional(&self.0)
+                .await
+                .map_err(|_e| AppError::DatabaseError)?;
+        Ok(revoked.is_some())
+    }
+}
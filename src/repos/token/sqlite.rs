@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, sqlite::SqlitePool};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{RefreshToken, TokenRepo};
+
+#[derive(Clone)]
+pub struct SqliteTokenRepo(SqlitePool);
+
+impl SqliteTokenRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteTokenRepo(pool)
+    }
+}
+
+#[async_trait]
+impl TokenRepo for SqliteTokenRepo {
+    async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        // RFC 3339 text in UTC compares chronologically.
+        sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= ?")
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query(
+            "INSERT INTO refresh_tokens (token_hash, user_id, expires_at, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&token.token_hash)
+        .bind(token.user_id.to_string())
+        .bind(token.expires_at.to_rfc3339())
+        .bind(token.created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn take_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshToken>, AppError> {
+        let row = sqlx::query(
+            "DELETE FROM refresh_tokens WHERE token_hash = ? RETURNING token_hash, user_id, expires_at, created_at",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let timestamp = |column: &str| {
+            DateTime::parse_from_rfc3339(&row.get::<String, _>(column))
+                .map(|at| at.with_timezone(&Utc))
+                .map_err(|_e| AppError::DatabaseError)
+        };
+        Ok(Some(RefreshToken {
+            token_hash: row.get("token_hash"),
+            user_id: Uuid::parse_str(row.get::<String, _>("user_id").as_str())
+                .map_err(|_e| AppError::DatabaseError)?,
+            expires_at: timestamp("expires_at")?,
+            created_at: timestamp("created_at")?,
+        }))
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        token_hash: &str,
+        user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE token_hash = ? AND user_id = ?")
+            .bind(token_hash)
+            .bind(user_id.to_string())
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= ?")
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        sqlx::query("INSERT OR REPLACE INTO revoked_tokens (jti, expires_at) VALUES (?, ?)")
+            .bind(jti.to_string())
+            .bind(expires_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn is_access_token_revoked(&self, jti: Uuid) -> Result<bool, AppError> {
+        let revoked: Option<i64> = sqlx::query_scalar("SELECT 1 FROM revoked_tokens WHERE jti = ?")
+            .bind(jti.to_string())
+            .fetch_optional(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        Ok(revoked.is_some())
+    }
+}
@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod inmemory_token_repo {
+    use std::sync::Arc;
+
+    use crate::repos::token::{
+        TokenRepo, inmemory::InMemoryTokenRepo, test::token_repo_conformance,
+    };
+
+    async fn repo() -> Arc<dyn TokenRepo> {
+        Arc::new(InMemoryTokenRepo::default())
+    }
+
+    token_repo_conformance!(repo());
+}
@@ -0,0 +1,134 @@
+//! Behaviour every [`TokenRepo`] backend must share. Each backend module
+//! runs the whole suite through [`token_repo_conformance`].
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::repos::token::{RefreshToken, TokenRepo};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! token_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::token::test::token_repo_conformance!(@cases $repo;
+            saved_refresh_token_should_be_taken_once,
+            missing_refresh_token_should_be_none,
+            expired_refresh_tokens_should_be_pruned_on_save,
+            refresh_token_should_be_deleted_only_by_its_user,
+            revoked_access_token_should_be_reported,
+            expired_revocations_should_be_pruned_on_revoke,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::token::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use token_repo_conformance;
+
+#[allow(dead_code)]
+fn refresh_token(hash: &str, expires_in: Duration) -> RefreshToken {
+    let now = Utc::now();
+    RefreshToken {
+        token_hash: hash.to_string(),
+        user_id: Uuid::new_v4(),
+        expires_at: now + expires_in,
+        created_at: now,
+    }
+}
+
+#[allow(dead_code)]
+pub async fn saved_refresh_token_should_be_taken_once(repo: Arc<dyn TokenRepo>) {
+    let token = refresh_token("hash", Duration::days(1));
+    repo.save_refresh_token(&token).await.unwrap();
+
+    let taken = repo.take_refresh_token("hash").await.unwrap().unwrap();
+
+    assert_eq!(taken.token_hash, token.token_hash);
+    assert_eq!(taken.user_id, token.user_id);
+    assert_eq!(
+        taken.expires_at.timestamp_millis(),
+        token.expires_at.timestamp_millis()
+    );
+    assert!(repo.take_refresh_token("hash").await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn missing_refresh_token_should_be_none(repo: Arc<dyn TokenRepo>) {
+    repo.save_refresh_token(&refresh_token("hash", Duration::days(1)))
+        .await
+        .unwrap();
+
+    assert!(repo.take_refresh_token("other").await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn expired_refresh_tokens_should_be_pruned_on_save(repo: Arc<dyn TokenRepo>) {
+    repo.save_refresh_token(&refresh_token("expired", -Duration::days(1)))
+        .await
+        .unwrap();
+    repo.save_refresh_token(&refresh_token("fresh", Duration::days(1)))
+        .await
+        .unwrap();
+
+    assert!(repo.take_refresh_token("expired").await.unwrap().is_none());
+    assert!(repo.take_refresh_token("fresh").await.unwrap().is_some());
+}
+
+#[allow(dead_code)]
+pub async fn refresh_token_should_be_deleted_only_by_its_user(repo: Arc<dyn TokenRepo>) {
+    let token = refresh_token("hash", Duration::days(1));
+    repo.save_refresh_token(&token).await.unwrap();
+
+    assert!(
+        !repo
+            .delete_refresh_token("hash", Uuid::new_v4())
+            .await
+            .unwrap()
+    );
+    assert!(
+        repo.delete_refresh_token("hash", token.user_id)
+            .await
+            .unwrap()
+    );
+    assert!(repo.take_refresh_token("hash").await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn revoked_access_token_should_be_reported(repo: Arc<dyn TokenRepo>) {
+    let revoked = Uuid::new_v4();
+    repo.revoke_access_token(revoked, Utc::now() + Duration::minutes(15))
+        .await
+        .unwrap();
+
+    assert!(repo.is_access_token_revoked(revoked).await.unwrap());
+    assert!(!repo.is_access_token_revoked(Uuid::new_v4()).await.unwrap());
+}
+
+#[allow(dead_code)]
+pub async fn expired_revocations_should_be_pruned_on_revoke(repo: Arc<dyn TokenRepo>) {
+    let expired = Uuid::new_v4();
+    let fresh = Uuid::new_v4();
+    repo.revoke_access_token(expired, Utc::now() - Duration::minutes(1))
+        .await
+        .unwrap();
+    repo.revoke_access_token(fresh, Utc::now() + Duration::minutes(15))
+        .await
+        .unwrap();
+
+    assert!(!repo.is_access_token_revoked(expired).await.unwrap());
+    assert!(repo.is_access_token_revoked(fresh).await.unwrap());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_token_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::postgres_pool,
+        token::{TokenRepo, postgres::PgTokenRepo, test::token_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn TokenRepo> {
+        Arc::new(PgTokenRepo::new(postgres_pool().await))
+    }
+
+    token_repo_conformance!(repo());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_token_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::sqlite_pool,
+        token::{TokenRepo, sqlite::SqliteTokenRepo, test::token_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn TokenRepo> {
+        Arc::new(SqliteTokenRepo::new(sqlite_pool().await))
+    }
+
+    token_repo_conformance!(repo());
+}
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Role, User, UserRepo, user_not_found, username_taken};
+
+#[derive(Default, Clone)]
+pub struct InMemoryUserRepo(Arc<Mutex<HashMap<Uuid, User>>>);
+
+#[async_trait]
+impl UserRepo for InMemoryUserRepo {
+    async fn create_user(&self, user: &User) -> Result<Uuid, AppError> {
+        let mut users = self.0.lock().await;
+        if users.values().any(|u| u.username == user.username) {
+            return Err(username_taken());
+        }
+        users.insert(user.id, user.clone());
+        Ok(user.id)
+    }
+    async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
+        Ok(self.0.lock().await.get(&id).cloned())
+    }
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        Ok(self
+            .0
+            .lock()
+            .await
+            .values()
+            .find(|u| u.username == username)
+            .cloned())
+    }
+    async fn get_users(&self) -> Result<Vec<User>, AppError> {
+        let mut users: Vec<User> = self.0.lock().await.values().cloned().collect();
+        users.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(users)
+    }
+    async fn set_user_role(&self, id: Uuid, role: Role) -> Result<(), AppError> {
+        let mut users = self.0.lock().await;
+        let user = users.get_mut(&id).ok_or_else(user_not_found)?;
+        user.role = role;
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// What an account may do, see [`crate::services::auth::scope`].
+#[derive(
+    Serialize, Deserialize, ToSchema, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum Role {
+    Reader,
+    #[default]
+    Editor,
+    Admin,
+}
+
+/// An account that can sign in, `password_hash` is an Argon2 PHC string.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub password_hash: String,
+    pub role: Role,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    /// Stores a new user, failing with 409 when the username is taken.
+    async fn create_user(&self, _user: &User) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    async fn get_user_by_id(&self, _id: Uuid) -> Result<Option<User>, AppError> {
+        unimplemented!()
+    }
+    async fn get_user_by_username(&self, _username: &str) -> Result<Option<User>, AppError> {
+        unimplemented!()
+    }
+    /// Every account, oldest first.
+    async fn get_users(&self) -> Result<Vec<User>, AppError> {
+        unimplemented!()
+    }
+    /// Fails with 404 when there is no such user.
+    async fn set_user_role(&self, _id: Uuid, _role: Role) -> Result<(), AppError> {
+        unimplemented!()
+    }
+}
+
+pub(crate) fn username_taken() -> AppError {
+    AppError::ClientFail(
+        axum::http::StatusCode::CONFLICT,
+        "Username is already taken".to_string(),
+    )
+}
+
+pub(crate) fn user_not_found() -> AppError {
+    AppError::ClientFail(
+        axum::http::StatusCode::NOT_FOUND,
+        "User not found".to_string(),
+    )
+}
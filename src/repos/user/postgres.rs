@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgPool;
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Role, User, UserRepo, user_not_found, username_taken};
+
+#[derive(Clone)]
+pub struct PgUserRepo(PgPool);
+
+impl PgUserRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgUserRepo(pool)
+    }
+}
+
+#[async_trait]
+impl UserRepo for PgUserRepo {
+    async fn create_user(&self, user: &User) -> Result<Uuid, AppError> {
+        sqlx::query(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(user.id)
+        .bind(&user.username)
+        .bind(&user.password_hash)
+        .bind(user.role)
+        .bind(user.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(e) if e.is_unique_violation() => username_taken(),
+            _ => AppError::DatabaseError,
+        })?;
+
+        Ok(user.id)
+    }
+
+    async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
+        sqlx::query_as::<_, User>(
+            "SELECT id, username, password_hash, role, created_at FROM users WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        sqlx::query_as::<_, User>(
+            "SELECT id, username, password_hash, role, created_at FROM users WHERE username = $1",
+        )
+        .bind(username)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn get_users(&self) -> Result<Vec<User>, AppError> {
+        sqlx::query_as::<_, User>(
+            "SELECT id, username, password_hash, role, created_at FROM users ORDER BY created_at, id",
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)
+    }
+
+    async fn set_user_role(&self, id: Uuid, role: Role) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE users SET role = $1 WHERE id = $2")
+            .bind(role)
+            .bind(id)
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(user_not_found());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+
+use super::{Role, User, UserRepo, user_not_found, username_taken};
+
+#[derive(Clone)]
+pub struct SqliteUserRepo(SqlitePool);
+
+impl SqliteUserRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteUserRepo(pool)
+    }
+}
+
+fn user_from_row(row: &SqliteRow) -> Result<User, AppError> {
+    Ok(User {
+        id: Uuid::parse_str(row.get::<String, _>("id").as_str())
+            .map_err(|_e| AppError::DatabaseError)?,
+        username: row.get("username"),
+        password_hash: row.get("password_hash"),
+        role: row.try_get("role").map_err(|_e| AppError::DatabaseError)?,
+        created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+            .map_err(|_e| AppError::DatabaseError)?
+            .with_timezone(&Utc),
+    })
+}
+
+#[async_trait]
+impl UserRepo for SqliteUserRepo {
+    async fn create_user(&self, user: &User) -> Result<Uuid, AppError> {
+        sqlx::query(
+            "INSERT INTO users (id, username, password_hash, role, created_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(user.id.to_string())
+        .bind(&user.username)
+        .bind(&user.password_hash)
+        .bind(user.role)
+        .bind(user.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(e) if e.is_unique_violation() => username_taken(),
+            _ => AppError::DatabaseError,
+        })?;
+
+        Ok(user.id)
+    }
+
+    async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
+        sqlx::query("SELECT id, username, password_hash, role, created_at FROM users WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .as_ref()
+            .map(user_from_row)
+            .transpose()
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        sqlx::query(
+            "SELECT id, username, password_hash, role, created_at FROM users WHERE username = ?",
+        )
+        .bind(username)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(user_from_row)
+        .transpose()
+    }
+
+    async fn get_users(&self) -> Result<Vec<User>, AppError> {
+        sqlx::query(
+            "SELECT id, username, password_hash, role, created_at FROM users ORDER BY created_at, id",
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(user_from_row)
+        .collect()
+    }
+
+    async fn set_user_role(&self, id: Uuid, role: Role) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE users SET role = ? WHERE id = ?")
+            .bind(role)
+            .bind(id.to_string())
+            .execute(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(user_not_found());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod inmemory_user_repo {
+    use std::sync::Arc;
+
+    use crate::repos::user::{UserRepo, inmemory::InMemoryUserRepo, test::user_repo_conformance};
+
+    async fn repo() -> Arc<dyn UserRepo> {
+        Arc::new(InMemoryUserRepo::default())
+    }
+
+    user_repo_conformance!(repo());
+}
@@ -0,0 +1,149 @@
+//! Behaviour every [`UserRepo`] backend must share. Each backend module
+//! runs the whole suite through [`user_repo_conformance`].
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::user::{Role, User, UserRepo},
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! user_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::user::test::user_repo_conformance!(@cases $repo;
+            created_user_should_be_retrievable_by_id,
+            created_user_should_be_retrievable_by_username,
+            duplicate_username_should_conflict,
+            missing_user_should_be_none,
+            users_should_be_listed_oldest_first,
+            role_should_be_stored_and_updated,
+            setting_role_of_missing_user_should_be_404,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::user::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use user_repo_conformance;
+
+#[allow(dead_code)]
+pub fn user(username: &str) -> User {
+    User {
+        id: Uuid::new_v4(),
+        username: username.to_string(),
+        password_hash: "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA".to_string(),
+        role: Role::Editor,
+        created_at: Utc::now(),
+    }
+}
+
+#[allow(dead_code)]
+fn assert_same_user(actual: &User, expected: &User) {
+    assert_eq!(actual.id, expected.id);
+    assert_eq!(actual.username, expected.username);
+    assert_eq!(actual.password_hash, expected.password_hash);
+    assert_eq!(actual.role, expected.role);
+    assert_eq!(
+        actual.created_at.timestamp_millis(),
+        expected.created_at.timestamp_millis()
+    );
+}
+
+#[allow(dead_code)]
+pub async fn created_user_should_be_retrievable_by_id(repo: Arc<dyn UserRepo>) {
+    let user = user("alice");
+
+    let id = repo.create_user(&user).await.unwrap();
+
+    assert_eq!(id, user.id);
+    let stored = repo.get_user_by_id(user.id).await.unwrap().unwrap();
+    assert_same_user(&stored, &user);
+}
+
+#[allow(dead_code)]
+pub async fn created_user_should_be_retrievable_by_username(repo: Arc<dyn UserRepo>) {
+    let alice = user("alice");
+    repo.create_user(&alice).await.unwrap();
+    repo.create_user(&user("bob")).await.unwrap();
+
+    let stored = repo.get_user_by_username("alice").await.unwrap().unwrap();
+
+    assert_same_user(&stored, &alice);
+}
+
+#[allow(dead_code)]
+pub async fn duplicate_username_should_conflict(repo: Arc<dyn UserRepo>) {
+    let first = user("alice");
+    repo.create_user(&first).await.unwrap();
+
+    let result = repo.create_user(&user("alice")).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::CONFLICT, _))
+    ));
+    let stored = repo.get_user_by_username("alice").await.unwrap().unwrap();
+    assert_eq!(stored.id, first.id);
+}
+
+#[allow(dead_code)]
+pub async fn missing_user_should_be_none(repo: Arc<dyn UserRepo>) {
+    assert!(repo.get_user_by_id(Uuid::new_v4()).await.unwrap().is_none());
+    assert!(repo.get_user_by_username("nobody").await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn users_should_be_listed_oldest_first(repo: Arc<dyn UserRepo>) {
+    let mut bob = user("bob");
+    bob.created_at = Utc::now() - chrono::Duration::days(1);
+    let alice = user("alice");
+    repo.create_user(&alice).await.unwrap();
+    repo.create_user(&bob).await.unwrap();
+
+    let users = repo.get_users().await.unwrap();
+
+    let names: Vec<&str> = users.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(names, vec!["bob", "alice"]);
+}
+
+#[allow(dead_code)]
+pub async fn role_should_be_stored_and_updated(repo: Arc<dyn UserRepo>) {
+    let mut reader = user("alice");
+    reader.role = Role::Reader;
+    repo.create_user(&reader).await.unwrap();
+    let stored = repo.get_user_by_id(reader.id).await.unwrap().unwrap();
+    assert_eq!(stored.role, Role::Reader);
+
+    repo.set_user_role(reader.id, Role::Admin).await.unwrap();
+
+    let stored = repo.get_user_by_id(reader.id).await.unwrap().unwrap();
+    assert_eq!(stored.role, Role::Admin);
+}
+
+#[allow(dead_code)]
+pub async fn setting_role_of_missing_user_should_be_404(repo: Arc<dyn UserRepo>) {
+    let result = repo.set_user_role(Uuid::new_v4(), Role::Admin).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::ClientFail(StatusCode::NOT_FOUND, _))
+    ));
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_user_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::postgres_pool,
+        user::{UserRepo, postgres::PgUserRepo, test::user_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn UserRepo> {
+        Arc::new(PgUserRepo::new(postgres_pool().await))
+    }
+
+    user_repo_conformance!(repo());
+}
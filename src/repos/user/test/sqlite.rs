@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_user_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::sqlite_pool,
+        user::{UserRepo, sqlite::SqliteUserRepo, test::user_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn UserRepo> {
+        Arc::new(SqliteUserRepo::new(sqlite_pool().await))
+    }
+
+    user_repo_conformance!(repo());
+}
@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{
+    DeliveryStatus, Webhook, WebhookDelivery, WebhookRepo, delivery_not_found, webhook_not_found,
+};
+
+#[derive(Default)]
+struct Hooks {
+    webhooks: HashMap<Uuid, Webhook>,
+    deliveries: HashMap<Uuid, WebhookDelivery>,
+}
+
+#[derive(Default, Clone)]
+pub struct InMemoryWebhookRepo(Arc<Mutex<Hooks>>);
+
+#[async_trait]
+impl WebhookRepo for InMemoryWebhookRepo {
+    async fn create_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        self.0
+            .lock()
+            .await
+            .webhooks
+            .insert(webhook.id, webhook.clone());
+        Ok(webhook.id)
+    }
+    async fn get_webhooks(&self, owner: Uuid) -> Result<Vec<Webhook>, AppError> {
+        let mut webhooks: Vec<Webhook> = self
+            .0
+            .lock()
+            .await
+            .webhooks
+            .values()
+            .filter(|webhook| webhook.owner_id == owner)
+            .cloned()
+            .collect();
+        webhooks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(webhooks)
+    }
+    async fn get_webhook(&self, owner: Uuid, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        Ok(self
+            .0
+            .lock()
+            .await
+            .webhooks
+            .get(&id)
+            .filter(|webhook| webhook.owner_id == owner)
+            .cloned())
+    }
+    async fn update_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        let mut hooks = self.0.lock().await;
+        let stored = hooks
+            .webhooks
+            .get_mut(&webhook.id)
+            .filter(|stored| stored.owner_id == webhook.owner_id)
+            .ok_or_else(webhook_not_found)?;
+        stored.url = webhook.url.clone();
+        stored.events = webhook.events.clone();
+        Ok(webhook.id)
+    }
+    async fn delete_webhook(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut hooks = self.0.lock().await;
+        if hooks
+            .webhooks
+            .get(&id)
+            .is_none_or(|webhook| webhook.owner_id != owner)
+        {
+            return Err(webhook_not_found());
+        }
+        hooks.webhooks.remove(&id);
+        hooks
+            .deliveries
+            .retain(|_, delivery| delivery.webhook_id != id);
+        Ok(id)
+    }
+    async fn enqueue_delivery(&self, delivery: &WebhookDelivery) -> Result<Uuid, AppError> {
+        self.0
+            .lock()
+            .await
+            .deliveries
+            .insert(delivery.id, delivery.clone());
+        Ok(delivery.id)
+    }
+    async fn get_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        let mut due: Vec<WebhookDelivery> = self
+            .0
+            .lock()
+            .await
+            .deliveries
+            .values()
+            .filter(|delivery| {
+                delivery.status == DeliveryStatus::Pending && delivery.next_attempt_at <= now
+            })
+            .cloned()
+            .collect();
+        due.sort_by(|a, b| {
+            a.next_attempt_at
+                .cmp(&b.next_attempt_at)
+                .then(a.id.cmp(&b.id))
+        });
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        if let Some(stored) = self.0.lock().await.deliveries.get_mut(&delivery.id) {
+            stored.status = delivery.status;
+            stored.attempts = delivery.attempts;
+            stored.next_attempt_at = delivery.next_attempt_at;
+            stored.last_error = delivery.last_error.clone();
+            stored.delivered_at = delivery.delivered_at;
+        }
+        Ok(())
+    }
+    async fn get_deliveries(
+        &self,
+        owner: Uuid,
+        status: Option<DeliveryStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<WebhookDelivery>, AppError> {
+        let mut deliveries: Vec<WebhookDelivery> = self
+            .0
+            .lock()
+            .await
+            .deliveries
+            .values()
+            .filter(|delivery| {
+                delivery.owner_id == owner && status.is_none_or(|status| delivery.status == status)
+            })
+            .cloned()
+            .collect();
+        deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        let total = deliveries.len() as u64;
+        let items = deliveries
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(page.limit as usize)
+            .collect();
+        Ok(Page { items, total })
+    }
+    async fn retry_delivery(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut hooks = self.0.lock().await;
+        let delivery = hooks
+            .deliveries
+            .get_mut(&id)
+            .filter(|delivery| {
+                delivery.owner_id == owner && delivery.status == DeliveryStatus::Dead
+            })
+            .ok_or_else(delivery_not_found)?;
+        delivery.status = DeliveryStatus::Pending;
+        delivery.attempts = 0;
+        delivery.next_attempt_at = Utc::now();
+        Ok(id)
+    }
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+pub mod test;
+
+/// What a webhook can be notified of, named like the events sent.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub enum WebhookEvent {
+    #[serde(rename = "book.created")]
+    BookCreated,
+    #[serde(rename = "book.updated")]
+    BookUpdated,
+    #[serde(rename = "book.deleted")]
+    BookDeleted,
+    /// A book was finished, sent along with the change that finished it.
+    #[serde(rename = "book.finished")]
+    BookFinished,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::BookCreated,
+        WebhookEvent::BookUpdated,
+        WebhookEvent::BookDeleted,
+        WebhookEvent::BookFinished,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::BookCreated => "book.created",
+            WebhookEvent::BookUpdated => "book.updated",
+            WebhookEvent::BookDeleted => "book.deleted",
+            WebhookEvent::BookFinished => "book.finished",
+        }
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        WebhookEvent::ALL
+            .into_iter()
+            .find(|event| event.as_str() == name)
+            .ok_or(())
+    }
+}
+
+/// A URL notified of the `events` on the shelf of `owner_id`. `secret`
+/// signs every delivery.
+#[derive(Clone, Debug)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(
+    Serialize, Deserialize, ToSchema, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its first or next attempt.
+    #[default]
+    Pending,
+    Delivered,
+    /// Given up on after too many failed attempts.
+    Dead,
+}
+
+/// One event queued for one webhook.
+#[derive(Serialize, ToSchema, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub owner_id: Uuid,
+    pub event: WebhookEvent,
+    /// The JSON body sent, as built when the event happened.
+    #[schema(value_type = Object)]
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait WebhookRepo: Send + Sync {
+    async fn create_webhook(&self, _webhook: &Webhook) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Webhooks of `owner`, oldest first.
+    async fn get_webhooks(&self, _owner: Uuid) -> Result<Vec<Webhook>, AppError> {
+        unimplemented!()
+    }
+    async fn get_webhook(&self, _owner: Uuid, _id: Uuid) -> Result<Option<Webhook>, AppError> {
+        unimplemented!()
+    }
+    /// Replaces the URL and events, failing with 404 unless the owner has
+    /// the webhook.
+    async fn update_webhook(&self, _webhook: &Webhook) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Removes the webhook along with its deliveries, failing with 404
+    /// unless `owner` has a webhook `id`.
+    async fn delete_webhook(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    async fn enqueue_delivery(&self, _delivery: &WebhookDelivery) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+    /// Up to `limit` pending deliveries due at `now`, longest due first.
+    async fn get_due_deliveries(
+        &self,
+        _now: DateTime<Utc>,
+        _limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        unimplemented!()
+    }
+    /// Stores the outcome of an attempt: status, attempts, next attempt,
+    /// last error and delivery time.
+    async fn update_delivery(&self, _delivery: &WebhookDelivery) -> Result<(), AppError> {
+        unimplemented!()
+    }
+    /// Deliveries of `owner`, all or only those in `status`, newest first.
+    /// Only the paging of `page` is used.
+    async fn get_deliveries(
+        &self,
+        _owner: Uuid,
+        _status: Option<DeliveryStatus>,
+        _page: &PageRequest,
+    ) -> Result<Page<WebhookDelivery>, AppError> {
+        unimplemented!()
+    }
+    /// Queues a dead delivery again with a fresh count of attempts, failing
+    /// with 404 unless `owner` has a dead delivery `id`.
+    async fn retry_delivery(&self, _owner: Uuid, _id: Uuid) -> Result<Uuid, AppError> {
+        unimplemented!()
+    }
+}
+
+pub(crate) fn webhook_not_found() -> AppError {
+    AppError::ClientFail(
+        axum::http::StatusCode::NOT_FOUND,
+        "Webhook not found".to_string(),
+    )
+}
+
+pub(crate) fn delivery_not_found() -> AppError {
+    AppError::ClientFail(
+        axum::http::StatusCode::NOT_FOUND,
+        "Dead delivery not found".to_string(),
+    )
+}
+
+/// `events` as stored, space separated.
+fn join_events(events: &[WebhookEvent]) -> String {
+    events
+        .iter()
+        .map(|event| event.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split_events(events: &str) -> Vec<WebhookEvent> {
+    events
+        .split_whitespace()
+        .filter_map(|name| name.parse().ok())
+        .collect()
+}
@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, QueryBuilder, Row, postgres::PgPool, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{
+    DeliveryStatus, Webhook, WebhookDelivery, WebhookRepo, delivery_not_found, join_events,
+    split_events, webhook_not_found,
+};
+
+#[derive(Clone)]
+pub struct PgWebhookRepo(PgPool);
+
+impl PgWebhookRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: PgPool) -> Self {
+        PgWebhookRepo(pool)
+    }
+}
+
+fn webhook_from_row(row: &PgRow) -> Webhook {
+    Webhook {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+        events: split_events(&row.get::<String, _>("events")),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn delivery_from_row(row: &PgRow) -> Result<WebhookDelivery, AppError> {
+    Ok(WebhookDelivery {
+        id: row.get("id"),
+        webhook_id: row.get("webhook_id"),
+        owner_id: row.get("owner_id"),
+        event: row
+            .get::<String, _>("event")
+            .parse()
+            .map_err(|_e| AppError::DatabaseError)?,
+        payload: row.get("payload"),
+        status: row
+            .try_get("status")
+            .map_err(|_e| AppError::DatabaseError)?,
+        attempts: row.get::<i32, _>("attempts") as u32,
+        next_attempt_at: row.get("next_attempt_at"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        delivered_at: row.get("delivered_at"),
+    })
+}
+
+const WEBHOOK_COLUMNS: &str = "id, owner_id, url, secret, events, created_at";
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, owner_id, event, payload, status, attempts, next_attempt_at, last_error, created_at, delivered_at";
+
+#[async_trait]
+impl WebhookRepo for PgWebhookRepo {
+    async fn create_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO webhooks ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook.id)
+        .bind(webhook.owner_id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(join_events(&webhook.events))
+        .bind(webhook.created_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(webhook.id)
+    }
+
+    async fn get_webhooks(&self, owner: Uuid) -> Result<Vec<Webhook>, AppError> {
+        Ok(sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE owner_id = $1 ORDER BY created_at, id",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(owner)
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(webhook_from_row)
+        .collect())
+    }
+
+    async fn get_webhook(&self, owner: Uuid, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        Ok(sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE id = $1 AND owner_id = $2",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(webhook_from_row))
+    }
+
+    async fn update_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        let result = sqlx::query(
+            "UPDATE webhooks SET url = $1, events = $2 WHERE id = $3 AND owner_id = $4",
+        )
+        .bind(&webhook.url)
+        .bind(join_events(&webhook.events))
+        .bind(webhook.id)
+        .bind(webhook.owner_id)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(webhook_not_found());
+        }
+        Ok(webhook.id)
+    }
+
+    async fn delete_webhook(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(webhook_not_found());
+        }
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)?;
+        Ok(id)
+    }
+
+    async fn enqueue_delivery(&self, delivery: &WebhookDelivery) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO webhook_deliveries ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            DELIVERY_COLUMNS
+        ))
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.owner_id)
+        .bind(delivery.event.as_str())
+        .bind(&delivery.payload)
+        .bind(delivery.status)
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(delivery.id)
+    }
+
+    async fn get_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE status = $1 AND next_attempt_at <= $2 ORDER BY next_attempt_at, id LIMIT $3",
+            DELIVERY_COLUMNS
+        ))
+        .bind(DeliveryStatus::Pending)
+        .bind(now)
+        .bind(i64::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(delivery_from_row)
+        .collect()
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = $1, attempts = $2, next_attempt_at = $3, last_error = $4, delivered_at = $5 WHERE id = $6",
+        )
+        .bind(delivery.status)
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .bind(delivery.id)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        Ok(())
+    }
+
+    async fn get_deliveries(
+        &self,
+        owner: Uuid,
+        status: Option<DeliveryStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<WebhookDelivery>, AppError> {
+        let push_filters = |query: &mut QueryBuilder<Postgres>| {
+            query.push_bind(owner);
+            if let Some(status) = status {
+                query.push(" AND status = ").push_bind(status);
+            }
+        };
+
+        let mut count_query =
+            QueryBuilder::new("SELECT COUNT(*) FROM webhook_deliveries WHERE owner_id = ");
+        push_filters(&mut count_query);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {} FROM webhook_deliveries WHERE owner_id = ",
+            DELIVERY_COLUMNS
+        ));
+        push_filters(&mut query);
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
+
+        let items = query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(delivery_from_row)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+
+    async fn retry_delivery(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries SET status = $1, attempts = 0, next_attempt_at = $2 WHERE id = $3 AND owner_id = $4 AND status = $5",
+        )
+        .bind(DeliveryStatus::Pending)
+        .bind(Utc::now())
+        .bind(id)
+        .bind(owner)
+        .bind(DeliveryStatus::Dead)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(delivery_not_found());
+        }
+        Ok(id)
+    }
+}
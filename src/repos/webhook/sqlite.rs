@@ -0,0 +1,285 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Row, Sqlite, sqlite::SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::book::{Page, PageRequest};
+
+use super::{
+    DeliveryStatus, Webhook, WebhookDelivery, WebhookRepo, delivery_not_found, join_events,
+    split_events, webhook_not_found,
+};
+
+#[derive(Clone)]
+pub struct SqliteWebhookRepo(SqlitePool);
+
+impl SqliteWebhookRepo {
+    /// Wraps a pool whose schema is managed by [`crate::repos::migrate`].
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteWebhookRepo(pool)
+    }
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, AppError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|_e| AppError::DatabaseError)
+}
+
+fn parse_uuid(row: &SqliteRow, column: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(row.get::<String, _>(column).as_str()).map_err(|_e| AppError::DatabaseError)
+}
+
+fn webhook_from_row(row: &SqliteRow) -> Result<Webhook, AppError> {
+    Ok(Webhook {
+        id: parse_uuid(row, "id")?,
+        owner_id: parse_uuid(row, "owner_id")?,
+        url: row.get("url"),
+        secret: row.get("secret"),
+        events: split_events(&row.get::<String, _>("events")),
+        created_at: parse_timestamp(&row.get::<String, _>("created_at"))?,
+    })
+}
+
+fn delivery_from_row(row: &SqliteRow) -> Result<WebhookDelivery, AppError> {
+    Ok(WebhookDelivery {
+        id: parse_uuid(row, "id")?,
+        webhook_id: parse_uuid(row, "webhook_id")?,
+        owner_id: parse_uuid(row, "owner_id")?,
+        event: row
+            .get::<String, _>("event")
+            .parse()
+            .map_err(|_e| AppError::DatabaseError)?,
+        payload: serde_json::from_str(&row.get::<String, _>("payload"))
+            .map_err(|_e| AppError::DatabaseError)?,
+        status: row
+            .try_get("status")
+            .map_err(|_e| AppError::DatabaseError)?,
+        attempts: row.get::<i64, _>("attempts") as u32,
+        next_attempt_at: parse_timestamp(&row.get::<String, _>("next_attempt_at"))?,
+        last_error: row.get("last_error"),
+        created_at: parse_timestamp(&row.get::<String, _>("created_at"))?,
+        delivered_at: row
+            .get::<Option<String>, _>("delivered_at")
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+    })
+}
+
+const WEBHOOK_COLUMNS: &str = "id, owner_id, url, secret, events, created_at";
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, owner_id, event, payload, status, attempts, next_attempt_at, last_error, created_at, delivered_at";
+
+#[async_trait]
+impl WebhookRepo for SqliteWebhookRepo {
+    async fn create_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO webhooks ({}) VALUES (?, ?, ?, ?, ?, ?)",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook.id.to_string())
+        .bind(webhook.owner_id.to_string())
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(join_events(&webhook.events))
+        .bind(webhook.created_at.to_rfc3339())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(webhook.id)
+    }
+
+    async fn get_webhooks(&self, owner: Uuid) -> Result<Vec<Webhook>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE owner_id = ? ORDER BY created_at, id",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(owner.to_string())
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(webhook_from_row)
+        .collect()
+    }
+
+    async fn get_webhook(&self, owner: Uuid, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        sqlx::query(&format!(
+            "SELECT {} FROM webhooks WHERE id = ? AND owner_id = ?",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(id.to_string())
+        .bind(owner.to_string())
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .as_ref()
+        .map(webhook_from_row)
+        .transpose()
+    }
+
+    async fn update_webhook(&self, webhook: &Webhook) -> Result<Uuid, AppError> {
+        let result =
+            sqlx::query("UPDATE webhooks SET url = ?, events = ? WHERE id = ? AND owner_id = ?")
+                .bind(&webhook.url)
+                .bind(join_events(&webhook.events))
+                .bind(webhook.id.to_string())
+                .bind(webhook.owner_id.to_string())
+                .execute(&self.0)
+                .await
+                .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(webhook_not_found());
+        }
+        Ok(webhook.id)
+    }
+
+    async fn delete_webhook(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let mut tx = self.0.begin().await.map_err(|_e| AppError::DatabaseError)?;
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = ? AND owner_id = ?")
+            .bind(id.to_string())
+            .bind(owner.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(webhook_not_found());
+        }
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = ?")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+        tx.commit().await.map_err(|_e| AppError::DatabaseError)?;
+        Ok(id)
+    }
+
+    async fn enqueue_delivery(&self, delivery: &WebhookDelivery) -> Result<Uuid, AppError> {
+        sqlx::query(&format!(
+            "INSERT INTO webhook_deliveries ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            DELIVERY_COLUMNS
+        ))
+        .bind(delivery.id.to_string())
+        .bind(delivery.webhook_id.to_string())
+        .bind(delivery.owner_id.to_string())
+        .bind(delivery.event.as_str())
+        .bind(delivery.payload.to_string())
+        .bind(delivery.status)
+        .bind(i64::from(delivery.attempts))
+        .bind(delivery.next_attempt_at.to_rfc3339())
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at.to_rfc3339())
+        .bind(delivery.delivered_at.map(|at| at.to_rfc3339()))
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+
+        Ok(delivery.id)
+    }
+
+    async fn get_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        // RFC 3339 text in UTC compares chronologically.
+        sqlx::query(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE status = ? AND next_attempt_at <= ? ORDER BY next_attempt_at, id LIMIT ?",
+            DELIVERY_COLUMNS
+        ))
+        .bind(DeliveryStatus::Pending)
+        .bind(now.to_rfc3339())
+        .bind(i64::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?
+        .iter()
+        .map(delivery_from_row)
+        .collect()
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?, delivered_at = ? WHERE id = ?",
+        )
+        .bind(delivery.status)
+        .bind(i64::from(delivery.attempts))
+        .bind(delivery.next_attempt_at.to_rfc3339())
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at.map(|at| at.to_rfc3339()))
+        .bind(delivery.id.to_string())
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        Ok(())
+    }
+
+    async fn get_deliveries(
+        &self,
+        owner: Uuid,
+        status: Option<DeliveryStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<WebhookDelivery>, AppError> {
+        let push_filters = |query: &mut QueryBuilder<Sqlite>| {
+            query.push_bind(owner.to_string());
+            if let Some(status) = status {
+                query.push(" AND status = ").push_bind(status);
+            }
+        };
+
+        let mut count_query =
+            QueryBuilder::new("SELECT COUNT(*) FROM webhook_deliveries WHERE owner_id = ");
+        push_filters(&mut count_query);
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?;
+
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {} FROM webhook_deliveries WHERE owner_id = ",
+            DELIVERY_COLUMNS
+        ));
+        push_filters(&mut query);
+        query
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(page.limit))
+            .push(" OFFSET ")
+            .push_bind(page.offset() as i64);
+
+        let items = query
+            .build()
+            .fetch_all(&self.0)
+            .await
+            .map_err(|_e| AppError::DatabaseError)?
+            .iter()
+            .map(delivery_from_row)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Page {
+            items,
+            total: total as u64,
+        })
+    }
+
+    async fn retry_delivery(&self, owner: Uuid, id: Uuid) -> Result<Uuid, AppError> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries SET status = ?, attempts = 0, next_attempt_at = ? WHERE id = ? AND owner_id = ? AND status = ?",
+        )
+        .bind(DeliveryStatus::Pending)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id.to_string())
+        .bind(owner.to_string())
+        .bind(DeliveryStatus::Dead)
+        .execute(&self.0)
+        .await
+        .map_err(|_e| AppError::DatabaseError)?;
+        if result.rows_affected() == 0 {
+            return Err(delivery_not_found());
+        }
+        Ok(id)
+    }
+}
@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod inmemory_webhook_repo {
+    use std::sync::Arc;
+
+    use crate::repos::webhook::{
+        WebhookRepo, inmemory::InMemoryWebhookRepo, test::webhook_repo_conformance,
+    };
+
+    async fn repo() -> Arc<dyn WebhookRepo> {
+        Arc::new(InMemoryWebhookRepo::default())
+    }
+
+    webhook_repo_conformance!(repo());
+}
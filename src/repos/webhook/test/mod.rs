@@ -0,0 +1,309 @@
+//! Behaviour every [`WebhookRepo`] backend must share. Each backend module
+//! runs the whole suite through [`webhook_repo_conformance`].
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{Duration, SubsecRound, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::{
+    book::PageRequest,
+    webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent, WebhookRepo},
+};
+
+pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+/// Generates one `#[tokio::test]` per conformance case, each on a fresh repo
+/// built by `$repo`.
+#[allow(unused_macros)]
+macro_rules! webhook_repo_conformance {
+    ($repo:expr) => {
+        $crate::repos::webhook::test::webhook_repo_conformance!(@cases $repo;
+            created_webhook_should_be_found_by_its_owner,
+            updated_webhook_should_keep_its_secret,
+            deleted_webhook_should_take_its_deliveries,
+            due_deliveries_should_be_pending_and_due,
+            delivery_outcome_should_be_stored,
+            deliveries_should_be_filtered_and_paged,
+            only_dead_delivery_should_be_retried,
+        );
+    };
+    (@cases $repo:expr; $($case:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $case() {
+                $crate::repos::webhook::test::$case($repo.await).await;
+            }
+        )*
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use webhook_repo_conformance;
+
+/// A webhook of `owner` created `minutes_ago`. Times are kept to
+/// milliseconds so every backend stores them as they are.
+#[allow(dead_code)]
+fn webhook(owner: Uuid, minutes_ago: i64) -> Webhook {
+    Webhook {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        url: "http://127.0.0.1:9/hook".to_string(),
+        secret: "whsec_test".to_string(),
+        events: vec![WebhookEvent::BookCreated, WebhookEvent::BookFinished],
+        created_at: (Utc::now() - Duration::minutes(minutes_ago)).trunc_subsecs(3),
+    }
+}
+
+/// A pending delivery for `webhook`, queued and due `minutes_ago`.
+#[allow(dead_code)]
+fn delivery(webhook: &Webhook, minutes_ago: i64) -> WebhookDelivery {
+    let at = (Utc::now() - Duration::minutes(minutes_ago)).trunc_subsecs(3);
+    WebhookDelivery {
+        id: Uuid::new_v4(),
+        webhook_id: webhook.id,
+        owner_id: webhook.owner_id,
+        event: WebhookEvent::BookCreated,
+        payload: json!({ "event": "book.created", "data": { "book": { "name": "Buku A" } } }),
+        status: DeliveryStatus::Pending,
+        attempts: 0,
+        next_attempt_at: at,
+        last_error: None,
+        created_at: at,
+        delivered_at: None,
+    }
+}
+
+#[allow(dead_code)]
+fn assert_not_found<T: std::fmt::Debug>(result: Result<T, AppError>) {
+    match result {
+        Err(AppError::ClientFail(status, _)) => assert_eq!(status, StatusCode::NOT_FOUND),
+        other => panic!("Expected not found, got {:?}", other),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn created_webhook_should_be_found_by_its_owner(repo: Arc<dyn WebhookRepo>) {
+    let owner = Uuid::new_v4();
+    let newer = webhook(owner, 1);
+    let older = webhook(owner, 5);
+    let foreign = webhook(Uuid::new_v4(), 3);
+    for webhook in [&newer, &older, &foreign] {
+        assert_eq!(repo.create_webhook(webhook).await.unwrap(), webhook.id);
+    }
+
+    let listed = repo.get_webhooks(owner).await.unwrap();
+    let ids: Vec<Uuid> = listed.iter().map(|webhook| webhook.id).collect();
+    assert_eq!(ids, vec![older.id, newer.id]);
+
+    let found = repo.get_webhook(owner, newer.id).await.unwrap().unwrap();
+    assert_eq!(found.url, newer.url);
+    assert_eq!(found.secret, "whsec_test");
+    assert_eq!(found.events, newer.events);
+    assert_eq!(found.created_at, newer.created_at);
+    assert!(repo.get_webhook(owner, foreign.id).await.unwrap().is_none());
+}
+
+#[allow(dead_code)]
+pub async fn updated_webhook_should_keep_its_secret(repo: Arc<dyn WebhookRepo>) {
+    let created = webhook(Uuid::new_v4(), 0);
+    repo.create_webhook(&created).await.unwrap();
+
+    let updated = Webhook {
+        url: "https://example.com/hook".to_string(),
+        secret: "whsec_other".to_string(),
+        events: vec![WebhookEvent::BookDeleted],
+        ..created.clone()
+    };
+    repo.update_webhook(&updated).await.unwrap();
+
+    let found = repo
+        .get_webhook(created.owner_id, created.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.url, "https://example.com/hook");
+    assert_eq!(found.events, vec![WebhookEvent::BookDeleted]);
+    assert_eq!(found.secret, "whsec_test");
+
+    let foreign = Webhook {
+        owner_id: Uuid::new_v4(),
+        ..updated
+    };
+    assert_not_found(repo.update_webhook(&foreign).await);
+}
+
+#[allow(dead_code)]
+pub async fn deleted_webhook_should_take_its_deliveries(repo: Arc<dyn WebhookRepo>) {
+    let owner = Uuid::new_v4();
+    let deleted = webhook(owner, 0);
+    let kept = webhook(owner, 0);
+    for webhook in [&deleted, &kept] {
+        repo.create_webhook(webhook).await.unwrap();
+        repo.enqueue_delivery(&delivery(webhook, 0)).await.unwrap();
+    }
+
+    assert_not_found(repo.delete_webhook(Uuid::new_v4(), deleted.id).await);
+    assert_eq!(
+        repo.delete_webhook(owner, deleted.id).await.unwrap(),
+        deleted.id
+    );
+
+    assert!(repo.get_webhook(owner, deleted.id).await.unwrap().is_none());
+    let deliveries = repo
+        .get_deliveries(owner, None, &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(deliveries.total, 1);
+    assert_eq!(deliveries.items[0].webhook_id, kept.id);
+    assert_not_found(repo.delete_webhook(owner, deleted.id).await);
+}
+
+#[allow(dead_code)]
+pub async fn due_deliveries_should_be_pending_and_due(repo: Arc<dyn WebhookRepo>) {
+    let hook = webhook(Uuid::new_v4(), 0);
+    let later = delivery(&hook, 1);
+    let earlier = delivery(&hook, 5);
+    let earliest = delivery(&hook, 10);
+    let future = delivery(&hook, -10);
+    let delivered = WebhookDelivery {
+        status: DeliveryStatus::Delivered,
+        ..delivery(&hook, 20)
+    };
+    let dead = WebhookDelivery {
+        status: DeliveryStatus::Dead,
+        ..delivery(&hook, 20)
+    };
+    for delivery in [&later, &earlier, &earliest, &future, &delivered, &dead] {
+        repo.enqueue_delivery(delivery).await.unwrap();
+    }
+
+    let due = repo.get_due_deliveries(Utc::now(), 10).await.unwrap();
+    let ids: Vec<Uuid> = due.iter().map(|delivery| delivery.id).collect();
+    assert_eq!(ids, vec![earliest.id, earlier.id, later.id]);
+
+    let due = repo.get_due_deliveries(Utc::now(), 2).await.unwrap();
+    assert_eq!(due.len(), 2);
+    assert_eq!(due[0].payload, earliest.payload);
+    assert_eq!(due[0].event, WebhookEvent::BookCreated);
+}
+
+#[allow(dead_code)]
+pub async fn delivery_outcome_should_be_stored(repo: Arc<dyn WebhookRepo>) {
+    let hook = webhook(Uuid::new_v4(), 0);
+    let queued = delivery(&hook, 0);
+    repo.enqueue_delivery(&queued).await.unwrap();
+
+    let retried_at = (Utc::now() + Duration::minutes(1)).trunc_subsecs(3);
+    let failed = WebhookDelivery {
+        attempts: 1,
+        next_attempt_at: retried_at,
+        last_error: Some("500 Internal Server Error".to_string()),
+        ..queued.clone()
+    };
+    repo.update_delivery(&failed).await.unwrap();
+    assert!(
+        repo.get_due_deliveries(Utc::now(), 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let delivered_at = Utc::now().trunc_subsecs(3);
+    let delivered = WebhookDelivery {
+        status: DeliveryStatus::Delivered,
+        attempts: 2,
+        delivered_at: Some(delivered_at),
+        ..failed
+    };
+    repo.update_delivery(&delivered).await.unwrap();
+
+    let page = repo
+        .get_deliveries(hook.owner_id, None, &PageRequest::default())
+        .await
+        .unwrap();
+    let stored = &page.items[0];
+    assert_eq!(stored.status, DeliveryStatus::Delivered);
+    assert_eq!(stored.attempts, 2);
+    assert_eq!(stored.next_attempt_at, retried_at);
+    assert_eq!(
+        stored.last_error.as_deref(),
+        Some("500 Internal Server Error")
+    );
+    assert_eq!(stored.delivered_at, Some(delivered_at));
+    assert_eq!(stored.created_at, queued.created_at);
+}
+
+#[allow(dead_code)]
+pub async fn deliveries_should_be_filtered_and_paged(repo: Arc<dyn WebhookRepo>) {
+    let owner = Uuid::new_v4();
+    let hook = webhook(owner, 0);
+    let mut dead = Vec::new();
+    for minutes_ago in 0..5 {
+        let delivery = WebhookDelivery {
+            status: DeliveryStatus::Dead,
+            ..delivery(&hook, minutes_ago)
+        };
+        repo.enqueue_delivery(&delivery).await.unwrap();
+        dead.push(delivery.id);
+    }
+    repo.enqueue_delivery(&delivery(&hook, 0)).await.unwrap();
+    repo.enqueue_delivery(&delivery(&webhook(Uuid::new_v4(), 0), 0))
+        .await
+        .unwrap();
+
+    let all = repo
+        .get_deliveries(owner, None, &PageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(all.total, 6);
+
+    let page = PageRequest {
+        page: 2,
+        limit: 2,
+        ..PageRequest::default()
+    };
+    let second = repo
+        .get_deliveries(owner, Some(DeliveryStatus::Dead), &page)
+        .await
+        .unwrap();
+
+    assert_eq!(second.total, 5);
+    let ids: Vec<Uuid> = second.items.iter().map(|delivery| delivery.id).collect();
+    assert_eq!(ids, vec![dead[2], dead[3]]);
+}
+
+#[allow(dead_code)]
+pub async fn only_dead_delivery_should_be_retried(repo: Arc<dyn WebhookRepo>) {
+    let hook = webhook(Uuid::new_v4(), 0);
+    let dead = WebhookDelivery {
+        status: DeliveryStatus::Dead,
+        attempts: 8,
+        next_attempt_at: Utc::now() + Duration::days(1),
+        ..delivery(&hook, 0)
+    };
+    let pending = delivery(&hook, -10);
+    repo.enqueue_delivery(&dead).await.unwrap();
+    repo.enqueue_delivery(&pending).await.unwrap();
+
+    assert_not_found(repo.retry_delivery(Uuid::new_v4(), dead.id).await);
+    assert_not_found(repo.retry_delivery(hook.owner_id, pending.id).await);
+    assert_eq!(
+        repo.retry_delivery(hook.owner_id, dead.id).await.unwrap(),
+        dead.id
+    );
+
+    let due = repo
+        .get_due_deliveries(Utc::now() + Duration::seconds(1), 10)
+        .await
+        .unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, dead.id);
+    assert_eq!(due[0].attempts, 0);
+    assert_not_found(repo.retry_delivery(hook.owner_id, dead.id).await);
+}
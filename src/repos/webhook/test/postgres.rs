@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod postgres_webhook_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::postgres_pool,
+        webhook::{WebhookRepo, postgres::PgWebhookRepo, test::webhook_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn WebhookRepo> {
+        Arc::new(PgWebhookRepo::new(postgres_pool().await))
+    }
+
+    webhook_repo_conformance!(repo());
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+mod sqlite_webhook_repo {
+    use std::sync::Arc;
+
+    use crate::repos::{
+        test::sqlite_pool,
+        webhook::{WebhookRepo, sqlite::SqliteWebhookRepo, test::webhook_repo_conformance},
+    };
+
+    async fn repo() -> Arc<dyn WebhookRepo> {
+        Arc::new(SqliteWebhookRepo::new(sqlite_pool().await))
+    }
+
+    webhook_repo_conformance!(repo());
+}
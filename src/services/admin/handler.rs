@@ -0,0 +1,125 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::user::{Role, User},
+    services::auth::{
+        AuthState,
+        scope::{Authorized, UsersAdmin},
+    },
+};
+
+/// An account without its password hash.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    id: Uuid,
+    username: String,
+    role: Role,
+    created_at: DateTime<Utc>,
+}
+
+impl From<User> for UserView {
+    fn from(user: User) -> Self {
+        UserView {
+            id: user.id,
+            username: user.username,
+            role: user.role,
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RoleParams {
+    role: Role,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    responses(
+        (status = 200, description = "Every account, oldest first"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the users:admin scope"),
+    ),
+    security(
+        ("bearerAuth" = ["users:admin"])
+    )
+)]
+pub async fn get_users(
+    State(state): State<AuthState>,
+    _claims: Authorized<UsersAdmin>,
+) -> Result<impl IntoResponse, AppError> {
+    let users: Vec<UserView> = state
+        .users
+        .get_users()
+        .await?
+        .into_iter()
+        .map(UserView::from)
+        .collect();
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "users": users
+        }
+    }));
+
+    Ok((headers, body))
+}
+
+/// Takes effect when the user next logs in or refreshes their token.
+#[utoipa::path(
+    put,
+    path = "/admin/users/{id}/role",
+    request_body = RoleParams,
+    params(
+        ("id" = String, Path, description = "ID of the user"),
+    ),
+    responses(
+        (status = 200, description = "Role updated"),
+        (status = 400, description = "Invalid id or own account"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the users:admin scope"),
+        (status = 404, description = "User not found"),
+    ),
+    security(
+        ("bearerAuth" = ["users:admin"])
+    )
+)]
+pub async fn update_user_role(
+    State(state): State<AuthState>,
+    claims: Authorized<UsersAdmin>,
+    Path(id): Path<String>,
+    Json(params): Json<RoleParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = Uuid::parse_str(&id).map_err(|_| {
+        AppError::ClientFail(StatusCode::BAD_REQUEST, "Invalid user id".to_string())
+    })?;
+    // Keeps the last admin from locking everyone out.
+    if user_id == claims.user_id()? {
+        let message = "Admins can't change their own role".to_string();
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    state.users.set_user_role(user_id, params.role).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Role updated"
+    }));
+
+    Ok((headers, body))
+}
@@ -0,0 +1,32 @@
+//! Account management for admins, served from `/admin` with the
+//! [`AuthState`](crate::services::auth::AuthState).
+
+use crate::{
+    AppError,
+    repos::user::{Role, UserRepo},
+    services::auth::handler::normalize_username,
+};
+
+pub mod handler;
+pub mod test;
+
+/// Makes admins of the existing accounts named in `usernames`. Only
+/// accounts already registered are promoted, so nobody becomes an admin by
+/// being the first to register a listed name.
+pub async fn promote_admins(users: &dyn UserRepo, usernames: &[String]) -> Result<(), AppError> {
+    for username in usernames {
+        let username = normalize_username(username);
+        match users.get_user_by_username(&username).await? {
+            Some(user) if user.role != Role::Admin => {
+                users.set_user_role(user.id, Role::Admin).await?;
+                tracing::info!("Made {} an admin", username);
+            }
+            Some(_) => {}
+            None => tracing::warn!(
+                "No account named {} to make an admin, register it and restart",
+                username
+            ),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+use serde_json::Value;
+
+pub mod users;
+
+#[allow(dead_code)]
+fn build_get_users_request(token: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri("/admin/users")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_update_role_request(token: &str, id: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::PUT)
+        .uri(format!("/admin/users/{}/role", id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
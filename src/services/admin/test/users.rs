@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod manage_users {
+    use axum::{Router, http::StatusCode};
+    use serde_json::{Value, json};
+
+    use crate::{
+        app::{AppState, router},
+        config::Config,
+        services::{
+            admin::{
+                promote_admins,
+                test::{build_get_users_request, build_update_role_request},
+            },
+            auth::test::{build_post_request, call, get_ready_service},
+        },
+    };
+
+    /// Registers `username`, returning the id.
+    async fn register(app: &mut Router, username: &str) -> String {
+        let payload = json!({ "username": username, "password": "correct horse battery" });
+        let (status, body) = call(app, build_post_request("/auth/register", payload)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        body["data"]["userId"].as_str().unwrap().to_string()
+    }
+
+    /// App where `admin` is an admin and `bob` an editor, with their ids.
+    async fn app_with_users() -> (Router, String, String) {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let admin = register(&mut app, "admin").await;
+        let bob = register(&mut app, "bob").await;
+        promote_admins(state.auth.users.as_ref(), &["Admin".to_string()])
+            .await
+            .unwrap();
+        (app, admin, bob)
+    }
+
+    async fn token(app: &mut Router, username: &str) -> String {
+        let payload = json!({ "username": username, "password": "correct horse battery" });
+        let (status, body) = call(app, build_post_request("/auth/login", payload)).await;
+        assert_eq!(status, StatusCode::OK);
+        body["token"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn admin_should_list_users_without_password_hashes() {
+        let (mut app, admin, bob) = app_with_users().await;
+        let token = token(&mut app, "admin").await;
+
+        let (status, body) = call(&mut app, build_get_users_request(&token)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        let users = body["data"]["users"].as_array().unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0]["id"], admin.as_str());
+        assert_eq!(users[0]["role"], "admin");
+        assert_eq!(users[1]["id"], bob.as_str());
+        assert_eq!(users[1]["role"], "editor");
+        assert!(users.iter().all(|user| user.get("passwordHash").is_none()));
+    }
+
+    #[tokio::test]
+    async fn editor_should_be_forbidden() {
+        let (mut app, _, _) = app_with_users().await;
+        let token = token(&mut app, "bob").await;
+
+        let (status, body) = call(&mut app, build_get_users_request(&token)).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["message"], "Insufficient permissions");
+    }
+
+    #[tokio::test]
+    async fn missing_token_should_return_401() {
+        let (mut app, _, _) = app_with_users().await;
+
+        let (status, _) = call(&mut app, build_get_users_request("not-a-token")).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn new_role_should_apply_to_the_next_token() {
+        let (mut app, _, bob) = app_with_users().await;
+        let admin_token = token(&mut app, "admin").await;
+
+        let payload = json!({ "role": "admin" });
+        let request = build_update_role_request(&admin_token, &bob, payload);
+        let (status, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Role updated");
+
+        let bob_token = token(&mut app, "bob").await;
+        let (status, _) = call(&mut app, build_get_users_request(&bob_token)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn admin_should_not_change_own_role() {
+        let (mut app, admin, _) = app_with_users().await;
+        let token = token(&mut app, "admin").await;
+
+        let request = build_update_role_request(&token, &admin, json!({ "role": "reader" }));
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["message"], "Admins can't change their own role");
+    }
+
+    #[tokio::test]
+    async fn unknown_user_should_return_404() {
+        let (mut app, _, _) = app_with_users().await;
+        let token = token(&mut app, "admin").await;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        let request = build_update_role_request(&token, &id, json!({ "role": "reader" }));
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["message"], "User not found");
+    }
+
+    #[tokio::test]
+    async fn unknown_role_should_be_rejected() {
+        let (mut app, _, bob) = app_with_users().await;
+        let token = token(&mut app, "admin").await;
+
+        let request = build_update_role_request(&token, &bob, json!({ "role": "owner" }));
+        let ready_service = get_ready_service(&mut app).await;
+        let response = tower::Service::call(ready_service, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn users_should_start_as_editors() {
+        let (mut app, _, _) = app_with_users().await;
+        let token = token(&mut app, "admin").await;
+
+        let (_, body) = call(&mut app, build_get_users_request(&token)).await;
+
+        let roles: Vec<&Value> = body["data"]["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| &user["role"])
+            .collect();
+        assert_eq!(roles, vec!["admin", "editor"]);
+    }
+
+    #[tokio::test]
+    async fn registering_a_listed_name_should_not_make_an_admin() {
+        let mut config = Config::ephemeral();
+        config.auth.admin_usernames = vec!["Admin".to_string()];
+        let state = AppState::new(&config).await.unwrap();
+        let mut app = router(state);
+        register(&mut app, "admin").await;
+        let token = token(&mut app, "admin").await;
+
+        let (status, _) = call(&mut app, build_get_users_request(&token)).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
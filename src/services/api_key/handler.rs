@@ -0,0 +1,211 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::api_key::ApiKey,
+    services::auth::{
+        AuthState,
+        scope::{ApiKeysManage, Authorized, Scope},
+        token::{generate_api_key, hash_token},
+    },
+};
+
+/// Characters of the secret kept in the clear to tell keys apart.
+const PREFIX_LEN: usize = 12;
+const MAX_NAME_LEN: usize = 100;
+
+/// `scopes` defaults to every scope of the caller that a key may hold, and a
+/// key without `expiresAt` lives until revoked.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyParams {
+    name: String,
+    scopes: Option<Vec<Scope>>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// A stored key, without its secret.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyView {
+    id: Uuid,
+    name: String,
+    prefix: String,
+    scopes: Vec<Scope>,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ApiKey> for ApiKeyView {
+    fn from(key: ApiKey) -> Self {
+        ApiKeyView {
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            scopes: key
+                .scopes
+                .iter()
+                .filter_map(|name| name.parse().ok())
+                .collect(),
+            expires_at: key.expires_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+fn bad_request(message: &str) -> AppError {
+    AppError::ClientFail(StatusCode::BAD_REQUEST, message.to_string())
+}
+
+/// The secret is only ever returned by this call.
+#[utoipa::path(
+    post,
+    path = "/api-keys",
+    request_body = ApiKeyParams,
+    responses(
+        (status = 201, description = "API key created"),
+        (status = 400, description = "Invalid name, scopes or expiry"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the api_keys:manage scope"),
+    ),
+    security(
+        ("bearerAuth" = ["api_keys:manage"])
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AuthState>,
+    claims: Authorized<ApiKeysManage>,
+    Json(params): Json<ApiKeyParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let name = params.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(bad_request("Name must be 1 to 100 characters"));
+    }
+    let grantable: Vec<Scope> = claims
+        .scopes
+        .iter()
+        .copied()
+        .filter(|scope| *scope != Scope::ApiKeysManage)
+        .collect();
+    let scopes = match params.scopes {
+        Some(scopes) => {
+            if scopes.iter().any(|scope| !grantable.contains(scope)) {
+                return Err(bad_request("Scopes must be a subset of your own"));
+            }
+            scopes
+        }
+        None => grantable,
+    };
+    let now = Utc::now();
+    if params.expires_at.is_some_and(|at| at <= now) {
+        return Err(bad_request("Expiry must be in the future"));
+    }
+
+    let secret = generate_api_key();
+    let key = ApiKey {
+        id: Uuid::new_v4(),
+        owner_id: claims.user_id()?,
+        name: name.to_string(),
+        prefix: secret[..PREFIX_LEN].to_string(),
+        key_hash: hash_token(&secret),
+        scopes: scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect(),
+        expires_at: params.expires_at,
+        created_at: now,
+    };
+    state.api_keys.create_api_key(&key).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "API key created",
+        "data": {
+            "apiKey": ApiKeyView::from(key),
+            "key": secret
+        }
+    }));
+
+    Ok((StatusCode::CREATED, headers, body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    responses(
+        (status = 200, description = "Keys of the caller, oldest first"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the api_keys:manage scope"),
+    ),
+    security(
+        ("bearerAuth" = ["api_keys:manage"])
+    )
+)]
+pub async fn get_api_keys(
+    State(state): State<AuthState>,
+    claims: Authorized<ApiKeysManage>,
+) -> Result<impl IntoResponse, AppError> {
+    let keys: Vec<ApiKeyView> = state
+        .api_keys
+        .get_api_keys(claims.user_id()?)
+        .await?
+        .into_iter()
+        .map(ApiKeyView::from)
+        .collect();
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "apiKeys": keys
+        }
+    }));
+
+    Ok((headers, body))
+}
+
+/// Revoked keys stop working right away.
+#[utoipa::path(
+    delete,
+    path = "/api-keys/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the API key"),
+    ),
+    responses(
+        (status = 200, description = "API key revoked"),
+        (status = 400, description = "Invalid id"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the api_keys:manage scope"),
+        (status = 404, description = "API key not found"),
+    ),
+    security(
+        ("bearerAuth" = ["api_keys:manage"])
+    )
+)]
+pub async fn delete_api_key(
+    State(state): State<AuthState>,
+    claims: Authorized<ApiKeysManage>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let id = Uuid::parse_str(&id).map_err(|_| bad_request("Invalid API key id"))?;
+    state.api_keys.delete_api_key(claims.user_id()?, id).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "API key revoked"
+    }));
+
+    Ok((headers, body))
+}
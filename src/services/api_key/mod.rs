@@ -0,0 +1,6 @@
+//! Long-lived keys for scripts and integrations, served from `/api-keys`
+//! with the [`AuthState`](crate::services::auth::AuthState). Requests send a
+//! key in the `X-API-Key` header instead of a bearer token.
+
+pub mod handler;
+pub mod test;
@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod manage_api_keys {
+    use axum::{
+        Router,
+        http::{Method, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use tonic::Request;
+
+    use crate::{
+        app::{AppState, router},
+        config::Config,
+        repos::user::Role,
+        services::{
+            api_key::test::{
+                build_books_request, build_create_api_key_request, build_delete_api_key_request,
+                build_get_api_keys_request, new_book, with_api_key,
+            },
+            auth::{
+                API_KEY_HEADER,
+                test::{build_post_request, call, credentials_dummy, login, registered_app},
+            },
+            book::grpc::{
+                BookGrpcService,
+                proto::{ListBooksRequest, book_service_server::BookService},
+            },
+        },
+    };
+
+    async fn app_with_token() -> (Router, String) {
+        let (mut app, _) = registered_app().await;
+        let token = login(&mut app).await["token"].as_str().unwrap().to_string();
+        (app, token)
+    }
+
+    /// Like [`app_with_token`], also returning the state behind the app and
+    /// a key of the user.
+    async fn state_with_key() -> (AppState, Router, String) {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let request = build_post_request("/auth/register", credentials_dummy());
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let token = login(&mut app).await["token"].as_str().unwrap().to_string();
+        let key = create(&mut app, &token, json!({ "name": "importer" })).await["key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        (state, app, key)
+    }
+
+    async fn create(app: &mut Router, token: &str, payload: Value) -> Value {
+        let (status, body) = call(app, build_create_api_key_request(token, payload)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        body["data"].clone()
+    }
+
+    #[tokio::test]
+    async fn created_key_should_default_to_the_callers_scopes() {
+        let (mut app, token) = app_with_token().await;
+
+        let data = create(&mut app, &token, json!({ "name": "importer" })).await;
+
+        let key = data["key"].as_str().unwrap();
+        assert!(key.starts_with("bks_"));
+        assert_eq!(data["apiKey"]["name"], "importer");
+        assert_eq!(data["apiKey"]["prefix"], &key[..12]);
+        assert_eq!(
+            data["apiKey"]["scopes"],
+            json!([
+                "books:read",
+                "books:write",
+                "books:delete",
+                "webhooks:manage"
+            ])
+        );
+        assert!(data["apiKey"]["expiresAt"].is_null());
+    }
+
+    #[tokio::test]
+    async fn list_should_show_keys_without_secrets() {
+        let (mut app, token) = app_with_token().await;
+        create(&mut app, &token, json!({ "name": "first" })).await;
+        create(&mut app, &token, json!({ "name": "second" })).await;
+
+        let (status, body) = call(&mut app, build_get_api_keys_request(&token)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let keys = body["data"]["apiKeys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0]["name"], "first");
+        assert_eq!(keys[1]["name"], "second");
+        assert!(
+            keys.iter()
+                .all(|key| key.get("key").is_none() && key.get("keyHash").is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn key_should_authenticate_book_requests() {
+        let (mut app, token) = app_with_token().await;
+        let key = create(&mut app, &token, json!({ "name": "importer" })).await["key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let request = build_books_request(Method::POST, &key, Some(new_book()));
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = call(&mut app, build_books_request(Method::GET, &key, None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["books"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn key_should_be_limited_to_its_scopes() {
+        let (mut app, token) = app_with_token().await;
+        let payload = json!({ "name": "reader", "scopes": ["books:read"] });
+        let key = create(&mut app, &token, payload).await["key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let request = build_books_request(Method::POST, &key, Some(new_book()));
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["message"], "Insufficient permissions");
+        let (status, _) = call(&mut app, build_books_request(Method::GET, &key, None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn key_should_not_manage_keys() {
+        let (mut app, token) = app_with_token().await;
+        let key = create(&mut app, &token, json!({ "name": "importer" })).await["key"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let request = with_api_key(build_get_api_keys_request(&token), &key);
+        let (status, _) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn revoked_key_should_be_rejected() {
+        let (mut app, token) = app_with_token().await;
+        let data = create(&mut app, &token, json!({ "name": "importer" })).await;
+        let key = data["key"].as_str().unwrap();
+        let id = data["apiKey"]["id"].as_str().unwrap();
+
+        let (status, body) = call(&mut app, build_delete_api_key_request(&token, id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "API key revoked");
+
+        let (status, _) = call(&mut app, build_books_request(Method::GET, key, None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(&mut app, build_delete_api_key_request(&token, id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unknown_key_should_be_rejected() {
+        let (mut app, _) = app_with_token().await;
+
+        let request = build_books_request(Method::GET, "bks_not-a-key", None);
+        let (status, _) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn invalid_params_should_be_rejected() {
+        let (mut app, token) = app_with_token().await;
+        let past = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+        let cases = [
+            (json!({ "name": " " }), "Name must be 1 to 100 characters"),
+            (
+                json!({ "name": "x".repeat(101) }),
+                "Name must be 1 to 100 characters",
+            ),
+            (
+                json!({ "name": "admin", "scopes": ["users:admin"] }),
+                "Scopes must be a subset of your own",
+            ),
+            (
+                json!({ "name": "keys", "scopes": ["api_keys:manage"] }),
+                "Scopes must be a subset of your own",
+            ),
+            (
+                json!({ "name": "old", "expiresAt": past }),
+                "Expiry must be in the future",
+            ),
+        ];
+
+        for (payload, message) in cases {
+            let request = build_create_api_key_request(&token, payload);
+            let (status, body) = call(&mut app, request).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["message"], message);
+        }
+    }
+
+    #[tokio::test]
+    async fn key_should_follow_the_owners_current_role() {
+        let (state, mut app, key) = state_with_key().await;
+        let user = state.auth.users.get_users().await.unwrap().remove(0);
+
+        state
+            .auth
+            .users
+            .set_user_role(user.id, Role::Reader)
+            .await
+            .unwrap();
+
+        let request = build_books_request(Method::POST, &key, Some(new_book()));
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call(&mut app, build_books_request(Method::GET, &key, None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn logout_with_a_key_should_be_rejected_and_keep_the_key() {
+        let (_, mut app, key) = state_with_key().await;
+
+        let payload = json!({ "refreshToken": "" });
+        let request = with_api_key(build_post_request("/auth/logout", payload), &key);
+        let (status, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+
+        let (status, _) = call(&mut app, build_books_request(Method::GET, &key, None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn grpc_should_accept_api_key_metadata() {
+        let (state, _, key) = state_with_key().await;
+        let service = BookGrpcService::new(state.book);
+
+        let mut request = Request::new(ListBooksRequest::default());
+        request
+            .metadata_mut()
+            .insert(API_KEY_HEADER, key.parse().unwrap());
+        let response = service.list_books(request).await;
+
+        assert!(response.is_ok());
+    }
+}
@@ -0,0 +1,76 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+use serde_json::{Value, json};
+
+use crate::services::auth::API_KEY_HEADER;
+
+pub mod keys;
+
+#[allow(dead_code)]
+fn build_create_api_key_request(token: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api-keys")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_api_keys_request(token: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri("/api-keys")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_delete_api_key_request(token: &str, id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("/api-keys/{}", id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// `request` authenticated by `key` instead of a bearer token.
+#[allow(dead_code)]
+fn with_api_key(mut request: Request<Body>, key: &str) -> Request<Body> {
+    request.headers_mut().remove(header::AUTHORIZATION);
+    request
+        .headers_mut()
+        .insert(API_KEY_HEADER, key.parse().unwrap());
+    request
+}
+
+#[allow(dead_code)]
+fn new_book() -> Value {
+    json!({
+        "name": "Buku A",
+        "year": 2010,
+        "author": "John Doe",
+        "summary": "Lorem ipsum dolor sit amet",
+        "publisher": "Dicoding Indonesia",
+        "pageCount": 100,
+        "readPage": 25,
+        "reading": false
+    })
+}
+
+#[allow(dead_code)]
+fn build_books_request(method: Method, key: &str, payload: Option<Value>) -> Request<Body> {
+    let body = payload.map_or_else(Body::empty, |payload| Body::from(payload.to_string()));
+    Request::builder()
+        .method(method)
+        .uri("/books")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(API_KEY_HEADER, key)
+        .body(body)
+        .unwrap()
+}
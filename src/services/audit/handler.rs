@@ -0,0 +1,103 @@
+use axum::{
+    Json,
+    extract::{Query, State, rejection::QueryRejection},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::{AuditAction, AuditFilter};
+use crate::repos::book::MAX_PAGE_LIMIT;
+use crate::services::auth::scope::{AuditRead, Authorized};
+use crate::services::book::handler::{page_meta, page_request};
+
+use super::AuditState;
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// Only changes to books on this user's shelf
+    owner_id: Option<Uuid>,
+    /// Only changes made by this user
+    actor_id: Option<Uuid>,
+    /// Only changes to this book
+    book_id: Option<Uuid>,
+    /// Only changes of this kind
+    action: Option<AuditAction>,
+    /// Only changes made at or after this RFC 3339 timestamp
+    since: Option<DateTime<Utc>>,
+    /// Only changes made before this RFC 3339 timestamp
+    until: Option<DateTime<Utc>>,
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Entries per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+}
+
+fn invalid_query(message: String) -> AppError {
+    AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+}
+
+/// Changes to the books of every user, newest first.
+#[utoipa::path(
+    get,
+    path = "/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit entries, newest first"),
+        (status = 400, description = "Invalid filter or paging"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the audit:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["audit:read"])
+    )
+)]
+pub async fn get_audit(
+    State(state): State<AuditState>,
+    _claims: Authorized<AuditRead>,
+    query: Result<Query<AuditQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query
+        .map_err(|rejection| invalid_query(format!("Invalid query. {}", rejection.body_text())))?;
+    let page = page_request(query.page, query.limit, None, None).map_err(|_| {
+        invalid_query(format!(
+            "Invalid query. page must be at least 1 and limit between 1 and {}",
+            MAX_PAGE_LIMIT
+        ))
+    })?;
+    if let (Some(since), Some(until)) = (query.since, query.until)
+        && since >= until
+    {
+        return Err(invalid_query(
+            "Invalid query. since must be before until".to_string(),
+        ));
+    }
+
+    let filter = AuditFilter {
+        owner_id: query.owner_id,
+        actor_id: query.actor_id,
+        book_id: query.book_id,
+        action: query.action,
+        since: query.since,
+        until: query.until,
+    };
+    let entries = state.repo.get_entries(&filter, &page).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "entries": entries.items
+        },
+        "meta": page_meta(&page, entries.total)
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
@@ -0,0 +1,35 @@
+//! The audit log of book changes. Entries are recorded by the book handlers
+//! through [`BookState::changed`](crate::services::book::BookState::changed),
+//! admins read them all at `/audit`.
+
+use std::sync::Arc;
+
+use axum::{extract::FromRef, http::HeaderMap};
+
+use crate::{repos::audit::AuditRepo, services::auth::AuthState};
+
+pub mod handler;
+pub mod test;
+
+/// Set on every request by the router when the client sent none, and sent
+/// back with the response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+#[derive(Clone)]
+pub struct AuditState {
+    pub repo: Arc<dyn AuditRepo>,
+    pub auth: AuthState,
+}
+
+impl FromRef<AuditState> for AuthState {
+    fn from_ref(state: &AuditState) -> AuthState {
+        state.auth.clone()
+    }
+}
+
+pub fn request_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod audit_log {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        repos::user::Role,
+        services::{
+            audit::test::build_get_audit_request,
+            auth::test::call,
+            book::test::{
+                TEST_USER, bearer, bearer_as, build_create_book_request, build_delete_book_request,
+                build_patch_book_request, new_book_dummy,
+            },
+        },
+    };
+
+    const ADMIN: Uuid = Uuid::from_u128(99);
+
+    /// Entries as seen by an admin, the whole response body.
+    async fn audit(app: &mut Router, query: &str) -> Value {
+        let admin = bearer_as(ADMIN, Role::Admin);
+        let (status, body) = call(app, build_get_audit_request(&admin, query)).await;
+        assert_eq!(status, StatusCode::OK);
+        body
+    }
+
+    /// Creates a book of `user` and returns its id.
+    async fn create_book(app: &mut Router, user: Uuid) -> String {
+        let mut request = build_create_book_request(new_book_dummy());
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, bearer(user).parse().unwrap());
+        let (_, body) = call(app, request).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn admin_should_see_changes_of_every_user() {
+        let mut app = app(Config::ephemeral()).await;
+        let other = Uuid::new_v4();
+        let mine = create_book(&mut app, TEST_USER).await;
+        let theirs = create_book(&mut app, other).await;
+
+        let body = audit(&mut app, "").await;
+
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["meta"]["total"], 2);
+        let entries = body["data"]["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["bookId"], theirs);
+        assert_eq!(entries[0]["actorId"], other.to_string());
+        assert_eq!(entries[1]["bookId"], mine);
+        assert_eq!(entries[1]["action"], "create");
+    }
+
+    #[tokio::test]
+    async fn audit_should_be_filtered() {
+        let mut app = app(Config::ephemeral()).await;
+        let other = Uuid::new_v4();
+        let id = create_book(&mut app, TEST_USER).await;
+        create_book(&mut app, other).await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 10 })),
+        )
+        .await;
+        call(&mut app, build_delete_book_request(&id)).await;
+
+        let body = audit(&mut app, &format!("actorId={}", TEST_USER)).await;
+        assert_eq!(body["meta"]["total"], 3);
+        let body = audit(&mut app, &format!("ownerId={}", other)).await;
+        assert_eq!(body["meta"]["total"], 1);
+        let body = audit(&mut app, &format!("bookId={}&action=update", id)).await;
+        assert_eq!(body["meta"]["total"], 1);
+        assert_eq!(
+            body["data"]["entries"][0]["changes"]["readPage"]["after"],
+            10
+        );
+        let body = audit(&mut app, "action=delete").await;
+        assert_eq!(body["data"]["entries"][0]["bookId"], id);
+        let body = audit(
+            &mut app,
+            "since=2000-01-01T00:00:00Z&until=2001-01-01T00:00:00Z",
+        )
+        .await;
+        assert_eq!(body["meta"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn invalid_audit_query_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+        let admin = bearer_as(ADMIN, Role::Admin);
+
+        for query in [
+            "action=burn",
+            "bookId=xxxxx",
+            "limit=0",
+            "since=2001-01-01T00:00:00Z&until=2000-01-01T00:00:00Z",
+        ] {
+            let (status, body) = call(&mut app, build_get_audit_request(&admin, query)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+            assert_eq!(body["status"], "fail");
+        }
+    }
+
+    #[tokio::test]
+    async fn audit_should_need_the_audit_read_scope() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let editor = bearer_as(TEST_USER, Role::Editor);
+        let (status, _) = call(&mut app, build_get_audit_request(&editor, "")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let request = Request::builder()
+            .uri("/audit")
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
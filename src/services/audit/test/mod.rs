@@ -0,0 +1,16 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+
+pub mod log;
+
+#[allow(dead_code)]
+fn build_get_audit_request(authorization: &str, query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/audit?{}", query))
+        .header(header::AUTHORIZATION, authorization)
+        .body(Body::empty())
+        .unwrap()
+}
@@ -1,40 +1,244 @@
-use std::sync::LazyLock;
-
-use axum::{Json, response::IntoResponse};
-use jsonwebtoken::{Header, encode};
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::Utc;
 use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::user::{Role, User},
+    services::auth::{AuthParams, Credentials, RefreshParams, TokenPair},
+};
+
+use super::{
+    API_KEY_HEADER, AuthState, Claims,
+    password::{hash_password, verify_dummy_password, verify_password},
+    token::hash_token,
+};
+
+const USERNAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=32;
+const PASSWORD_LENGTH: std::ops::RangeInclusive<usize> = 8..=128;
+
+/// Usernames are matched case-insensitively and stored lowercased.
+pub fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+fn validate_credentials(username: &str, password: &str) -> Result<(), AppError> {
+    let valid_username = USERNAME_LENGTH.contains(&username.chars().count())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !valid_username {
+        let message = format!(
+            "Username must be {} to {} letters, digits, '.', '_' or '-'",
+            USERNAME_LENGTH.start(),
+            USERNAME_LENGTH.end()
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    if !PASSWORD_LENGTH.contains(&password.chars().count()) {
+        let message = format!(
+            "Password must be {} to {} characters",
+            PASSWORD_LENGTH.start(),
+            PASSWORD_LENGTH.end()
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    Ok(())
+}
+
+/// Checks the password of `username` and issues tokens for that user.
+async fn issue_tokens(
+    state: &AuthState,
+    username: &str,
+    password: &str,
+) -> Result<TokenPair, AppError> {
+    if username.is_empty() || password.is_empty() {
+        return Err(AppError::MissingCredentials);
+    }
+
+    let Some(user) = state
+        .users
+        .get_user_by_username(&normalize_username(username))
+        .await?
+    else {
+        verify_dummy_password(password.to_string()).await?;
+        return Err(AppError::WrongCredentials);
+    };
+    if !verify_password(password.to_string(), user.password_hash.clone()).await? {
+        return Err(AppError::WrongCredentials);
+    }
+
+    state.issue_tokens(&user).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/register",
+    request_body = Credentials,
+    responses(
+        (status = 201, description = "User registered"),
+        (status = 400, description = "Invalid username or password"),
+        (status = 409, description = "Username is already taken"),
+    )
+)]
+pub async fn register(
+    State(state): State<AuthState>,
+    Json(params): Json<Credentials>,
+) -> Result<impl IntoResponse, AppError> {
+    let username = normalize_username(&params.username);
+    validate_credentials(&username, &params.password)?;
+
+    let user = User {
+        id: Uuid::new_v4(),
+        username,
+        password_hash: hash_password(params.password).await?,
+        role: Role::default(),
+        created_at: Utc::now(),
+    };
+    let id = state.users.create_user(&user).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "User registered",
+        "data": {
+            "userId": id
+        }
+    }));
+
+    Ok((StatusCode::CREATED, headers, body))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    request_body = Credentials,
+    responses(
+        (status = 200, description = "Authentication successful", body = TokenPair),
+        (status = 401, description = "Authentication failed"),
+    )
+)]
+pub async fn login(
+    State(state): State<AuthState>,
+    Json(params): Json<Credentials>,
+) -> Result<impl IntoResponse, AppError> {
+    let tokens = issue_tokens(&state, &params.username, &params.password).await?;
 
-use crate::{AppError, services::auth::AuthParams};
+    Ok(Json(tokens))
+}
 
-use super::{Claims, Keys};
+/// Trades a refresh token for a new pair, the old refresh token stops
+/// working.
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    request_body = RefreshParams,
+    responses(
+        (status = 200, description = "Tokens refreshed", body = TokenPair),
+        (status = 401, description = "Unknown, used or expired refresh token"),
+    )
+)]
+pub async fn refresh(
+    State(state): State<AuthState>,
+    Json(params): Json<RefreshParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let stored = state
+        .tokens
+        .take_refresh_token(&hash_token(&params.refresh_token))
+        .await?
+        .filter(|token| token.expires_at > Utc::now())
+        .ok_or(AppError::InvalidToken)?;
+    // Accounts may be gone since the token was issued, and roles changed.
+    let user = state
+        .users
+        .get_user_by_id(stored.user_id)
+        .await?
+        .ok_or(AppError::InvalidToken)?;
 
-pub static KEYS: LazyLock<Keys> = LazyLock::new(|| Keys::new(b"test"));
+    Ok(Json(state.issue_tokens(&user).await?))
+}
 
+/// Revokes the bearer access token and the given refresh token. API keys
+/// are revoked by deleting them instead.
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    request_body = RefreshParams,
+    responses(
+        (status = 200, description = "Logged out"),
+        (status = 400, description = "Authenticated with an API key"),
+        (status = 401, description = "Invalid or missing token"),
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn logout(
+    State(state): State<AuthState>,
+    claims: Claims,
+    headers: HeaderMap,
+    Json(params): Json<RefreshParams>,
+) -> Result<impl IntoResponse, AppError> {
+    if headers.contains_key(API_KEY_HEADER) {
+        let message = "API keys can't log out, delete the key under /api-keys instead".to_string();
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    state
+        .tokens
+        .revoke_access_token(claims.jti, claims.expires_at())
+        .await?;
+    // A refresh token of someone else is left alone.
+    state
+        .tokens
+        .delete_refresh_token(&hash_token(&params.refresh_token), claims.user_id()?)
+        .await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Logged out"
+    }));
+
+    Ok((headers, body))
+}
+
+/// Client-credentials flavour of [`login`], `client_id` is the username.
 #[utoipa::path(
     post,
     path = "/auth",
     request_body = AuthParams,
     responses(
-        (status = 200, description = "Authentication successful"),
+        (status = 200, description = "Authentication successful", body = TokenPair),
         (status = 401, description = "Authentication failed"),
     )
 )]
-pub async fn authorize(Json(params): Json<AuthParams>) -> Result<impl IntoResponse, AppError> {
-    if params.client_id.is_empty() || params.client_secret.is_empty() {
-        return Err(AppError::MissingCredentials);
-    }
-    if params.client_id != "foo" || params.client_secret != "bar" {
-        return Err(AppError::WrongCredentials);
-    }
+pub async fn authorize(
+    State(state): State<AuthState>,
+    Json(params): Json<AuthParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let tokens = issue_tokens(&state, &params.client_id, &params.client_secret).await?;
 
-    let claims = Claims {
-        sub: params.client_id,
-        exp: 10000000000,
-    };
-    let token =
-        encode(&Header::default(), &claims, &KEYS.encoding).map_err(|_| AppError::TokenCreation)?;
+    Ok(Json(tokens))
+}
 
-    Ok(Json(json!({ "token": token })))
+/// Public keys that verify issued tokens, empty when signing with a shared
+/// secret.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "JSON Web Key Set"),
+    )
+)]
+pub async fn jwks(State(state): State<AuthState>) -> impl IntoResponse {
+    let headers = [(header::CACHE_CONTROL, "public, max-age=300")];
+    (headers, Json(state.keys.jwks().clone()))
 }
 
 #[utoipa::path(
@@ -0,0 +1,183 @@
+//! Token signing keys, either a shared HMAC secret or asymmetric key pairs
+//! whose public halves are published as a JWKS.
+
+use anyhow::{Context, anyhow, bail};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use rsa::{RsaPublicKey, pkcs8::DecodePublicKey, traits::PublicKeyParts};
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    config::{JwtAlgorithm, JwtKeyConfig},
+};
+
+use super::Claims;
+
+struct VerificationKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+pub struct Keys {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    verification: Vec<VerificationKey>,
+    jwks: JwkSet,
+}
+
+impl Keys {
+    /// HS256 with a shared secret. The JWKS stays empty since publishing the
+    /// secret would let anyone sign tokens.
+    pub fn from_secret(secret: &[u8]) -> Self {
+        Keys {
+            kid: None,
+            algorithm: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret),
+            verification: vec![VerificationKey {
+                kid: None,
+                algorithm: Algorithm::HS256,
+                key: DecodingKey::from_secret(secret),
+            }],
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    /// Loads the PEM files of `configs`, the first key signs and all of them
+    /// verify.
+    pub fn from_config(configs: &[JwtKeyConfig]) -> anyhow::Result<Self> {
+        let Some(signer) = configs.first() else {
+            bail!("No signing key configured");
+        };
+        let private_key = signer
+            .private_key
+            .as_deref()
+            .with_context(|| format!("Signing key `{}` has no private_key", signer.kid))?;
+        let pem = read(private_key)?;
+        let encoding = match signer.algorithm {
+            JwtAlgorithm::Rs256 => EncodingKey::from_rsa_pem(pem.as_bytes()),
+            JwtAlgorithm::EdDsa => EncodingKey::from_ed_pem(pem.as_bytes()),
+        }
+        .with_context(|| format!("Invalid private key {}", private_key))?;
+
+        let mut jwks = JwkSet { keys: Vec::new() };
+        let mut verification = Vec::new();
+        for config in configs {
+            if jwks.find(&config.kid).is_some() {
+                bail!("Duplicate key id `{}`", config.kid);
+            }
+            let jwk = public_jwk(config)?;
+            verification.push(VerificationKey {
+                kid: Some(config.kid.clone()),
+                algorithm: algorithm(config.algorithm),
+                key: DecodingKey::from_jwk(&jwk)
+                    .with_context(|| format!("Invalid public key {}", config.public_key))?,
+            });
+            jwks.keys.push(jwk);
+        }
+
+        let keys = Keys {
+            kid: Some(signer.kid.clone()),
+            algorithm: algorithm(signer.algorithm),
+            encoding,
+            verification,
+            jwks,
+        };
+        // Catches a private key paired with someone else's public key.
+        let probe = Claims {
+            sub: String::new(),
+            jti: Uuid::nil(),
+            exp: (Utc::now() + Duration::minutes(1)).timestamp() as usize,
+            scopes: Vec::new(),
+        };
+        keys.verify(&keys.sign(&probe)?).map_err(|_| {
+            anyhow!(
+                "Private key of `{}` doesn't match its public key",
+                signer.kid
+            )
+        })?;
+        Ok(keys)
+    }
+
+    pub fn sign(&self, claims: &Claims) -> Result<String, AppError> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.kid.clone();
+        encode(&header, claims, &self.encoding).map_err(|_| AppError::TokenCreation)
+    }
+
+    /// Claims of a token signed with one of the keys. The key is picked by
+    /// `kid` and its own algorithm is enforced, whatever `alg` the token
+    /// claims.
+    pub fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        let header = decode_header(token).map_err(|_| AppError::InvalidToken)?;
+        let key = self
+            .verification
+            .iter()
+            .find(|key| key.kid == header.kid)
+            .ok_or(AppError::InvalidToken)?;
+        decode::<Claims>(token, &key.key, &Validation::new(key.algorithm))
+            .map(|data| data.claims)
+            .map_err(|_| AppError::InvalidToken)
+    }
+
+    /// Public halves of the verification keys.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn algorithm(algorithm: JwtAlgorithm) -> Algorithm {
+    match algorithm {
+        JwtAlgorithm::Rs256 => Algorithm::RS256,
+        JwtAlgorithm::EdDsa => Algorithm::EdDSA,
+    }
+}
+
+fn read(path: &str) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read key file {}", path))
+}
+
+fn public_jwk(config: &JwtKeyConfig) -> anyhow::Result<Jwk> {
+    let pem = read(&config.public_key)?;
+    let invalid = || format!("Invalid public key {}", config.public_key);
+    let (key_algorithm, parameters) = match config.algorithm {
+        JwtAlgorithm::Rs256 => {
+            let key = RsaPublicKey::from_public_key_pem(&pem).with_context(invalid)?;
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+            });
+            (KeyAlgorithm::RS256, parameters)
+        }
+        JwtAlgorithm::EdDsa => {
+            let key =
+                ed25519_dalek::VerifyingKey::from_public_key_pem(&pem).with_context(invalid)?;
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(key.as_bytes()),
+            });
+            (KeyAlgorithm::EdDSA, parameters)
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(config.kid.clone()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
@@ -1,28 +1,119 @@
-use axum::{RequestPartsExt, extract::FromRequestParts, http::request::Parts};
+use std::sync::Arc;
+
+use axum::{
+    RequestPartsExt,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
 use axum_extra::{
     TypedHeader,
     headers::{Authorization, authorization::Bearer},
 };
-use handler::KEYS;
-use jsonwebtoken::{DecodingKey, EncodingKey, Validation, decode};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::{
+        api_key::ApiKeyRepo,
+        token::{RefreshToken, TokenRepo},
+        user::{User, UserRepo},
+    },
+};
 
-use crate::AppError;
+use self::{keys::Keys, scope::Scope};
 
 pub mod handler;
+pub mod keys;
+pub mod password;
+pub mod scope;
+pub mod test;
+pub mod token;
 
-pub struct Keys {
-    encoding: EncodingKey,
-    decoding: DecodingKey,
+#[derive(Clone)]
+pub struct AuthState {
+    pub keys: Arc<Keys>,
+    pub users: Arc<dyn UserRepo>,
+    pub tokens: Arc<dyn TokenRepo>,
+    pub api_keys: Arc<dyn ApiKeyRepo>,
+    pub access_token_ttl: Duration,
+    pub refresh_token_ttl: Duration,
 }
 
-impl Keys {
-    fn new(secret: &[u8]) -> Self {
-        Keys {
-            encoding: EncodingKey::from_secret(secret),
-            decoding: DecodingKey::from_secret(secret),
+impl AuthState {
+    /// Claims of an unexpired, unrevoked bearer token signed by this server.
+    pub async fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        let claims = self.keys.verify(token)?;
+        if self.tokens.is_access_token_revoked(claims.jti).await? {
+            return Err(AppError::InvalidToken);
         }
+        Ok(claims)
+    }
+
+    /// Claims on behalf of the owner of an unexpired API key. The key keeps
+    /// only the scopes the owner's current role still grants, and `jti` is
+    /// the key id.
+    pub async fn verify_api_key(&self, key: &str) -> Result<Claims, AppError> {
+        let now = Utc::now();
+        let api_key = self
+            .api_keys
+            .get_api_key_by_hash(&token::hash_token(key))
+            .await?
+            .filter(|api_key| api_key.expires_at.is_none_or(|at| at > now))
+            .ok_or(AppError::InvalidToken)?;
+        let user = self
+            .users
+            .get_user_by_id(api_key.owner_id)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        let granted = user.role.scopes();
+        let scopes = api_key
+            .scopes
+            .iter()
+            .filter_map(|name| name.parse::<Scope>().ok())
+            .filter(|scope| *scope != Scope::ApiKeysManage && granted.contains(scope))
+            .collect();
+        let expires_at = api_key.expires_at.unwrap_or(now + self.access_token_ttl);
+
+        Ok(Claims {
+            sub: user.id.to_string(),
+            jti: api_key.id,
+            exp: expires_at.timestamp() as usize,
+            scopes,
+        })
+    }
+
+    /// Signs a new access token with the scopes of the current role of
+    /// `user` and stores a refresh token that can be traded for the next one.
+    pub async fn issue_tokens(&self, user: &User) -> Result<TokenPair, AppError> {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: user.id.to_string(),
+            jti: Uuid::new_v4(),
+            exp: (now + self.access_token_ttl).timestamp() as usize,
+            scopes: user.role.scopes(),
+        };
+        let access_token = self.keys.sign(&claims)?;
+
+        let refresh_token = token::generate_refresh_token();
+        self.tokens
+            .save_refresh_token(&RefreshToken {
+                token_hash: token::hash_token(&refresh_token),
+                user_id: user.id,
+                expires_at: now + self.refresh_token_ttl,
+                created_at: now,
+            })
+            .await?;
+
+        Ok(TokenPair {
+            token: access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.access_token_ttl.num_seconds(),
+        })
     }
 }
 
@@ -32,26 +123,73 @@ pub struct AuthParams {
     client_secret: String,
 }
 
+/// Username and password of an account, for registering and logging in.
+#[derive(Deserialize, ToSchema)]
+pub struct Credentials {
+    username: String,
+    password: String,
+}
+
+/// A refresh token handed out with an access token.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshParams {
+    refresh_token: String,
+}
+
+/// Issued on login and refresh, `expires_in` is the access token lifetime
+/// in seconds.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPair {
+    pub token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+}
+
+/// `sub` is the id of the user the token was issued to, `jti` identifies the
+/// token itself so it can be revoked.
 #[derive(Serialize, Clone, Deserialize)]
 pub struct Claims {
-    sub: String,
-    exp: usize,
+    pub sub: String,
+    pub jti: Uuid,
+    pub exp: usize,
+    #[serde(default)]
+    pub scopes: Vec<Scope>,
+}
+
+impl Claims {
+    pub fn user_id(&self) -> Result<Uuid, AppError> {
+        Uuid::parse_str(&self.sub).map_err(|_| AppError::InvalidToken)
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp as i64, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
 }
 
+/// Header carrying an API key in place of a bearer token.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Taken from an `X-API-Key` header when present, a bearer token otherwise.
 impl<S> FromRequestParts<S> for Claims
 where
+    AuthState: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = AppError;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthState::from_ref(state);
+        if let Some(key) = parts.headers.get(API_KEY_HEADER) {
+            let key = key.to_str().map_err(|_| AppError::InvalidToken)?;
+            return auth.verify_api_key(key).await;
+        }
         let TypedHeader(Authorization(bearer)) = parts
             .extract::<TypedHeader<Authorization<Bearer>>>()
             .await
             .map_err(|_| AppError::InvalidToken)?;
-        let token_data = decode::<Claims>(bearer.token(), &KEYS.decoding, &Validation::default())
-            .map_err(|_| AppError::InvalidToken)?;
-
-        Ok(token_data.claims)
+        auth.verify(bearer.token()).await
     }
 }
@@ -0,0 +1,63 @@
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+
+use std::sync::LazyLock;
+
+use crate::AppError;
+
+/// Hash checked in place of a missing user's so that login takes as long for
+/// an unknown username as for a wrong password.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(b"bookshelf-dummy-password", &salt)
+        .map(|hash| hash.to_string())
+        .expect("hashing the dummy password")
+});
+
+/// Argon2id PHC string for `password` with a fresh random salt. Runs on the
+/// blocking pool since hashing is deliberately slow.
+pub async fn hash_password(password: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|_| AppError::PasswordHashing)
+    })
+    .await
+    .map_err(|_| AppError::PasswordHashing)?
+}
+
+#[cfg(test)]
+tokio::task_local! {
+    /// Verifications run within [`tokio::task::LocalKey::scope`], so tests
+    /// can tell a password was checked.
+    pub static VERIFICATIONS: std::cell::Cell<usize>;
+}
+
+/// Whether `password` matches a hash made by [`hash_password`].
+pub async fn verify_password(password: String, hash: String) -> Result<bool, AppError> {
+    #[cfg(test)]
+    let _ = VERIFICATIONS.try_with(|count| count.set(count.get() + 1));
+    tokio::task::spawn_blocking(move || {
+        let hash = PasswordHash::new(&hash).map_err(|_| AppError::PasswordHashing)?;
+        Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok())
+    })
+    .await
+    .map_err(|_| AppError::PasswordHashing)?
+}
+
+/// Runs the same verification as [`verify_password`] against a fixed hash
+/// and always answers `false`, for usernames that don't exist.
+pub async fn verify_dummy_password(password: String) -> Result<bool, AppError> {
+    let hash = tokio::task::spawn_blocking(|| DUMMY_HASH.clone())
+        .await
+        .map_err(|_| AppError::PasswordHashing)?;
+    verify_password(password, hash).await?;
+    Ok(false)
+}
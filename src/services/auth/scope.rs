@@ -0,0 +1,171 @@
+//! Permissions carried in access tokens. A token holds the scopes of the
+//! user's role at the time it was issued, routes declare the one they need
+//! through [`Authorized`].
+
+use std::{marker::PhantomData, ops::Deref, str::FromStr};
+
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{AppError, repos::user::Role};
+
+use super::{AuthState, Claims};
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    #[serde(rename = "books:read")]
+    BooksRead,
+    #[serde(rename = "books:write")]
+    BooksWrite,
+    #[serde(rename = "books:delete")]
+    BooksDelete,
+    #[serde(rename = "users:admin")]
+    UsersAdmin,
+    /// Reading the audit log of every shelf.
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    /// Creating and revoking API keys, never granted to a key itself.
+    #[serde(rename = "api_keys:manage")]
+    ApiKeysManage,
+    /// Subscribing webhooks to changes on the caller's own shelf.
+    #[serde(rename = "webhooks:manage")]
+    WebhooksManage,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 7] = [
+        Scope::BooksRead,
+        Scope::BooksWrite,
+        Scope::BooksDelete,
+        Scope::UsersAdmin,
+        Scope::AuditRead,
+        Scope::ApiKeysManage,
+        Scope::WebhooksManage,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::BooksRead => "books:read",
+            Scope::BooksWrite => "books:write",
+            Scope::BooksDelete => "books:delete",
+            Scope::UsersAdmin => "users:admin",
+            Scope::AuditRead => "audit:read",
+            Scope::ApiKeysManage => "api_keys:manage",
+            Scope::WebhooksManage => "webhooks:manage",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == name)
+            .ok_or(())
+    }
+}
+
+impl Role {
+    pub fn scopes(self) -> Vec<Scope> {
+        match self {
+            Role::Reader => vec![
+                Scope::BooksRead,
+                Scope::ApiKeysManage,
+                Scope::WebhooksManage,
+            ],
+            Role::Editor => vec![
+                Scope::BooksRead,
+                Scope::BooksWrite,
+                Scope::BooksDelete,
+                Scope::ApiKeysManage,
+                Scope::WebhooksManage,
+            ],
+            Role::Admin => Scope::ALL.to_vec(),
+        }
+    }
+}
+
+impl Claims {
+    /// Fails with 403 unless the token was granted `scope`.
+    pub fn require(&self, scope: Scope) -> Result<(), AppError> {
+        if self.scopes.contains(&scope) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+}
+
+/// Type-level [`Scope`] for [`Authorized`].
+pub trait RequiredScope {
+    const SCOPE: Scope;
+}
+
+pub struct BooksRead;
+pub struct BooksWrite;
+pub struct BooksDelete;
+pub struct UsersAdmin;
+pub struct AuditRead;
+pub struct ApiKeysManage;
+pub struct WebhooksManage;
+
+impl RequiredScope for BooksRead {
+    const SCOPE: Scope = Scope::BooksRead;
+}
+
+impl RequiredScope for BooksWrite {
+    const SCOPE: Scope = Scope::BooksWrite;
+}
+
+impl RequiredScope for BooksDelete {
+    const SCOPE: Scope = Scope::BooksDelete;
+}
+
+impl RequiredScope for UsersAdmin {
+    const SCOPE: Scope = Scope::UsersAdmin;
+}
+
+impl RequiredScope for AuditRead {
+    const SCOPE: Scope = Scope::AuditRead;
+}
+
+impl RequiredScope for ApiKeysManage {
+    const SCOPE: Scope = Scope::ApiKeysManage;
+}
+
+impl RequiredScope for WebhooksManage {
+    const SCOPE: Scope = Scope::WebhooksManage;
+}
+
+/// [`Claims`] of a token or API key granted `S`, rejected with 401 without
+/// valid credentials and 403 without the scope.
+pub struct Authorized<S>(Claims, PhantomData<S>);
+
+impl<S> Deref for Authorized<S> {
+    type Target = Claims;
+
+    fn deref(&self) -> &Claims {
+        &self.0
+    }
+}
+
+impl<S, T> FromRequestParts<T> for Authorized<S>
+where
+    S: RequiredScope,
+    AuthState: FromRef<T>,
+    T: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &T) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+        claims.require(S::SCOPE)?;
+        Ok(Authorized(claims, PhantomData))
+    }
+}
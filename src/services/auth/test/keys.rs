@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod signing_keys {
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode, header},
+    };
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, decode_header, encode};
+    use uuid::Uuid;
+
+    use crate::{
+        AppError,
+        config::{Config, JwtAlgorithm},
+        services::auth::{
+            Claims,
+            keys::Keys,
+            test::{
+                build_protected_request, call, get_ready_service, key_config, login,
+                registered_app, registered_app_with,
+            },
+        },
+    };
+
+    fn claims() -> Claims {
+        Claims {
+            sub: Uuid::new_v4().to_string(),
+            jti: Uuid::new_v4(),
+            exp: 10000000000,
+            scopes: Vec::new(),
+        }
+    }
+
+    fn build_jwks_request() -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri("/.well-known/jwks.json")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn eddsa_token_should_carry_kid_and_verify() {
+        let keys =
+            Keys::from_config(&[key_config("current", "ed25519", JwtAlgorithm::EdDsa, true)])
+                .unwrap();
+
+        let token = keys.sign(&claims()).unwrap();
+
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.alg, Algorithm::EdDSA);
+        assert_eq!(header.kid.as_deref(), Some("current"));
+        assert!(keys.verify(&token).is_ok());
+    }
+
+    #[test]
+    fn rs256_token_should_carry_kid_and_verify() {
+        let keys =
+            Keys::from_config(&[key_config("rsa", "rsa", JwtAlgorithm::Rs256, true)]).unwrap();
+
+        let token = keys.sign(&claims()).unwrap();
+
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+        assert_eq!(header.kid.as_deref(), Some("rsa"));
+        assert!(keys.verify(&token).is_ok());
+    }
+
+    #[test]
+    fn tokens_of_a_retired_key_should_still_verify() {
+        let old = Keys::from_config(&[key_config("old", "ed25519-old", JwtAlgorithm::EdDsa, true)])
+            .unwrap();
+        let token = old.sign(&claims()).unwrap();
+
+        let rotated = Keys::from_config(&[
+            key_config("new", "rsa", JwtAlgorithm::Rs256, true),
+            key_config("old", "ed25519-old", JwtAlgorithm::EdDsa, false),
+        ])
+        .unwrap();
+
+        assert!(rotated.verify(&token).is_ok());
+        let header = decode_header(rotated.sign(&claims()).unwrap()).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn tokens_of_a_dropped_key_should_be_rejected() {
+        let old = Keys::from_config(&[key_config("old", "ed25519-old", JwtAlgorithm::EdDsa, true)])
+            .unwrap();
+        let token = old.sign(&claims()).unwrap();
+
+        let keys =
+            Keys::from_config(&[key_config("current", "ed25519", JwtAlgorithm::EdDsa, true)])
+                .unwrap();
+
+        assert!(matches!(keys.verify(&token), Err(AppError::InvalidToken)));
+    }
+
+    #[test]
+    fn hmac_token_signed_with_the_public_key_should_be_rejected() {
+        let keys =
+            Keys::from_config(&[key_config("rsa", "rsa", JwtAlgorithm::Rs256, true)]).unwrap();
+        let public_key =
+            std::fs::read(key_config("rsa", "rsa", JwtAlgorithm::Rs256, false).public_key).unwrap();
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("rsa".to_string());
+
+        let forged = encode(&header, &claims(), &EncodingKey::from_secret(&public_key)).unwrap();
+
+        assert!(matches!(keys.verify(&forged), Err(AppError::InvalidToken)));
+    }
+
+    #[test]
+    fn mismatched_key_pair_should_fail_to_load() {
+        let mut config = key_config("current", "ed25519", JwtAlgorithm::EdDsa, true);
+        config.public_key = key_config("old", "ed25519-old", JwtAlgorithm::EdDsa, false).public_key;
+
+        assert!(Keys::from_config(&[config]).is_err());
+    }
+
+    #[test]
+    fn signing_key_without_private_key_should_fail_to_load() {
+        let result =
+            Keys::from_config(&[key_config("current", "ed25519", JwtAlgorithm::EdDsa, false)]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn duplicate_kid_should_fail_to_load() {
+        let result = Keys::from_config(&[
+            key_config("current", "ed25519", JwtAlgorithm::EdDsa, true),
+            key_config("current", "ed25519-old", JwtAlgorithm::EdDsa, false),
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn jwks_should_list_every_public_key() {
+        let mut config = Config::ephemeral();
+        config.auth.keys = vec![
+            key_config("current", "ed25519", JwtAlgorithm::EdDsa, true),
+            key_config("rsa", "rsa", JwtAlgorithm::Rs256, false),
+        ];
+        let (mut app, _) = registered_app_with(config).await;
+
+        let request = build_jwks_request();
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let keys = body["keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0]["kid"], "current");
+        assert_eq!(keys[0]["kty"], "OKP");
+        assert_eq!(keys[0]["crv"], "Ed25519");
+        assert_eq!(keys[0]["alg"], "EdDSA");
+        assert_eq!(keys[0]["use"], "sig");
+        assert_eq!(keys[1]["kid"], "rsa");
+        assert_eq!(keys[1]["kty"], "RSA");
+        assert_eq!(keys[1]["e"], "AQAB");
+        assert!(keys.iter().all(|key| key.get("d").is_none()));
+    }
+
+    #[tokio::test]
+    async fn jwks_should_be_empty_with_a_shared_secret() {
+        let (mut app, _) = registered_app().await;
+
+        let (status, body) = call(&mut app, build_jwks_request()).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["keys"].as_array().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn jwks_should_be_cacheable() {
+        let (mut app, _) = registered_app().await;
+        let ready_service = get_ready_service(&mut app).await;
+
+        let response = tower::Service::call(ready_service, build_jwks_request())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, max-age=300"
+        );
+    }
+
+    #[tokio::test]
+    async fn login_should_issue_tokens_signed_with_the_configured_key() {
+        let mut config = Config::ephemeral();
+        config.auth.keys = vec![key_config("current", "ed25519", JwtAlgorithm::EdDsa, true)];
+        let (mut app, id) = registered_app_with(config).await;
+
+        let tokens = login(&mut app).await;
+        let token = tokens["token"].as_str().unwrap();
+
+        assert_eq!(
+            decode_header(token).unwrap().kid.as_deref(),
+            Some("current")
+        );
+        let (status, body) = call(&mut app, build_protected_request(token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], format!("Hello, {}!", id));
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod login_user {
+    use std::cell::Cell;
+
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::auth::password::VERIFICATIONS,
+        services::auth::test::{
+            build_post_request, build_protected_request, call, credentials_dummy, registered_app,
+        },
+    };
+
+    #[tokio::test]
+    async fn login_should_issue_token_for_the_user() {
+        let (mut app, id) = registered_app().await;
+
+        let request = build_post_request("/auth/login", credentials_dummy());
+        let (status, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = body["token"].as_str().unwrap();
+        assert!(body["refreshToken"].is_string());
+        assert_eq!(body["tokenType"], "Bearer");
+        assert_eq!(body["expiresIn"], 900);
+
+        let (status, body) = call(&mut app, build_protected_request(token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], format!("Hello, {}!", id));
+    }
+
+    #[tokio::test]
+    async fn login_should_ignore_username_case() {
+        let (mut app, _) = registered_app().await;
+        let mut payload = credentials_dummy();
+        payload["username"] = json!(" Alice ");
+
+        let (status, _) = call(&mut app, build_post_request("/auth/login", payload)).await;
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn wrong_password_should_return_401() {
+        let (mut app, _) = registered_app().await;
+        let mut payload = credentials_dummy();
+        payload["password"] = json!("wrong password");
+
+        let (status, body) = call(&mut app, build_post_request("/auth/login", payload)).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["message"], "Wrong credentials");
+    }
+
+    #[tokio::test]
+    async fn unknown_user_should_return_401() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = build_post_request("/auth/login", credentials_dummy());
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["message"], "Wrong credentials");
+    }
+
+    #[tokio::test]
+    async fn unknown_user_should_have_a_password_checked_all_the_same() {
+        let (mut app, _) = registered_app().await;
+        let mut unknown = credentials_dummy();
+        unknown["username"] = json!("nobody");
+        let mut wrong = credentials_dummy();
+        wrong["password"] = json!("wrong password");
+
+        for payload in [unknown, wrong] {
+            let request = build_post_request("/auth/login", payload);
+            let (status, verifications) = VERIFICATIONS
+                .scope(Cell::new(0), async {
+                    let (status, _) = call(&mut app, request).await;
+                    (status, VERIFICATIONS.with(Cell::get))
+                })
+                .await;
+
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(verifications, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn hardcoded_client_should_be_rejected() {
+        let mut app = app(Config::ephemeral()).await;
+        let payload = json!({ "client_id": "foo", "client_secret": "bar" });
+
+        let (status, _) = call(&mut app, build_post_request("/auth", payload)).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn client_credentials_should_issue_token() {
+        let (mut app, _) = registered_app().await;
+        let payload = json!({ "client_id": "alice", "client_secret": "correct horse battery" });
+
+        let (status, body) = call(&mut app, build_post_request("/auth", payload)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["token"].is_string());
+        assert!(body["refreshToken"].is_string());
+    }
+
+    #[tokio::test]
+    async fn missing_token_should_return_401() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let (status, _) = call(&mut app, build_protected_request("not-a-token")).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
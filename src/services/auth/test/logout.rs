@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod logout_user {
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    use crate::services::auth::test::{
+        build_logout_request, build_post_request, build_protected_request, call, login,
+        registered_app,
+    };
+
+    #[tokio::test]
+    async fn logout_should_revoke_the_access_token() {
+        let (mut app, _) = registered_app().await;
+        let tokens = login(&mut app).await;
+        let token = tokens["token"].as_str().unwrap();
+        let refresh_token = tokens["refreshToken"].as_str().unwrap();
+
+        let (status, body) = call(&mut app, build_logout_request(token, refresh_token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Logged out");
+
+        let (status, _) = call(&mut app, build_protected_request(token)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn logout_should_revoke_the_refresh_token() {
+        let (mut app, _) = registered_app().await;
+        let tokens = login(&mut app).await;
+        let token = tokens["token"].as_str().unwrap();
+        let refresh_token = tokens["refreshToken"].as_str().unwrap();
+
+        call(&mut app, build_logout_request(token, refresh_token)).await;
+
+        let payload = json!({ "refreshToken": refresh_token });
+        let (status, _) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn logout_should_keep_other_sessions() {
+        let (mut app, _) = registered_app().await;
+        let first = login(&mut app).await;
+        let second = login(&mut app).await;
+
+        call(
+            &mut app,
+            build_logout_request(
+                first["token"].as_str().unwrap(),
+                first["refreshToken"].as_str().unwrap(),
+            ),
+        )
+        .await;
+
+        let token = second["token"].as_str().unwrap();
+        let (status, _) = call(&mut app, build_protected_request(token)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn logout_should_leave_another_users_refresh_token() {
+        let (mut app, _) = registered_app().await;
+        let alice = login(&mut app).await;
+        let bob_credentials = json!({ "username": "bob", "password": "correct horse battery" });
+        let request = build_post_request("/auth/register", bob_credentials.clone());
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, bob) = call(&mut app, build_post_request("/auth/login", bob_credentials)).await;
+
+        let alice_refresh_token = alice["refreshToken"].as_str().unwrap();
+        let request = build_logout_request(bob["token"].as_str().unwrap(), alice_refresh_token);
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let payload = json!({ "refreshToken": alice_refresh_token });
+        let (status, _) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn logout_without_token_should_return_401() {
+        let (mut app, _) = registered_app().await;
+
+        let (status, _) = call(&mut app, build_logout_request("not-a-token", "")).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
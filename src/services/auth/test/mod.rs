@@ -0,0 +1,121 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::{Service, ServiceExt};
+
+use crate::{
+    app::app,
+    config::{Config, JwtAlgorithm, JwtKeyConfig},
+};
+
+pub mod keys;
+pub mod login;
+pub mod logout;
+pub mod refresh;
+pub mod register;
+
+#[allow(dead_code)]
+pub fn credentials_dummy() -> Value {
+    json!({
+        "username": "alice",
+        "password": "correct horse battery"
+    })
+}
+
+/// Key pair `name` from `test/keys`, with its private key when `signer`.
+#[allow(dead_code)]
+fn key_config(kid: &str, name: &str, algorithm: JwtAlgorithm, signer: bool) -> JwtKeyConfig {
+    let path = |suffix: &str| {
+        format!(
+            "{}/src/services/auth/test/keys/{}{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name,
+            suffix
+        )
+    };
+    JwtKeyConfig {
+        kid: kid.to_string(),
+        algorithm,
+        public_key: path(".pub.pem"),
+        private_key: signer.then(|| path(".pem")),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn get_ready_service(app: &mut Router) -> &mut Router {
+    ServiceExt::<Request<Body>>::ready(app)
+        .await
+        .expect("Service should be ready")
+}
+
+#[allow(dead_code)]
+pub fn build_post_request(uri: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_protected_request(token: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri("/auth")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_logout_request(token: &str, refresh_token: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/auth/logout")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(
+            json!({ "refreshToken": refresh_token }).to_string(),
+        ))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+pub async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Value) {
+    let ready_service = get_ready_service(app).await;
+    let response = ready_service.call(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+/// App with the [`credentials_dummy`] user registered, and that user's id.
+#[allow(dead_code)]
+pub async fn registered_app_with(config: Config) -> (Router, String) {
+    let mut app = app(config).await;
+    let request = build_post_request("/auth/register", credentials_dummy());
+    let (status, body) = call(&mut app, request).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = body["data"]["userId"].as_str().unwrap().to_string();
+    (app, id)
+}
+
+#[allow(dead_code)]
+pub async fn registered_app() -> (Router, String) {
+    registered_app_with(Config::ephemeral()).await
+}
+
+/// Logs in as the [`credentials_dummy`] user, returning the token pair.
+#[allow(dead_code)]
+pub async fn login(app: &mut Router) -> Value {
+    let request = build_post_request("/auth/login", credentials_dummy());
+    let (status, body) = call(app, request).await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod refresh_token {
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    use crate::{
+        config::Config,
+        services::auth::test::{
+            build_post_request, build_protected_request, call, login, registered_app,
+            registered_app_with,
+        },
+    };
+
+    #[tokio::test]
+    async fn refresh_should_issue_new_working_tokens() {
+        let (mut app, id) = registered_app().await;
+        let tokens = login(&mut app).await;
+
+        let payload = json!({ "refreshToken": tokens["refreshToken"] });
+        let (status, body) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(body["token"], tokens["token"]);
+        assert_ne!(body["refreshToken"], tokens["refreshToken"]);
+
+        let token = body["token"].as_str().unwrap();
+        let (status, body) = call(&mut app, build_protected_request(token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], format!("Hello, {}!", id));
+    }
+
+    #[tokio::test]
+    async fn refresh_token_should_work_only_once() {
+        let (mut app, _) = registered_app().await;
+        let tokens = login(&mut app).await;
+        let payload = json!({ "refreshToken": tokens["refreshToken"] });
+
+        let (status, _) = call(
+            &mut app,
+            build_post_request("/auth/refresh", payload.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["message"], "Invalid token");
+    }
+
+    #[tokio::test]
+    async fn unknown_refresh_token_should_return_401() {
+        let (mut app, _) = registered_app().await;
+
+        let payload = json!({ "refreshToken": "not-a-refresh-token" });
+        let (status, _) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn expired_refresh_token_should_return_401() {
+        let mut config = Config::ephemeral();
+        config.auth.refresh_token_ttl_secs = 0;
+        let (mut app, _) = registered_app_with(config).await;
+        let tokens = login(&mut app).await;
+
+        let payload = json!({ "refreshToken": tokens["refreshToken"] });
+        let (status, _) = call(&mut app, build_post_request("/auth/refresh", payload)).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn access_token_should_expire_after_configured_ttl() {
+        let mut config = Config::ephemeral();
+        config.auth.access_token_ttl_secs = 60;
+        let (mut app, _) = registered_app_with(config).await;
+
+        let tokens = login(&mut app).await;
+
+        assert_eq!(tokens["expiresIn"], 60);
+    }
+}
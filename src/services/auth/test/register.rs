@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod register_user {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::auth::test::{build_post_request, credentials_dummy, get_ready_service},
+    };
+
+    async fn register(app: &mut Router, payload: Value) -> (StatusCode, Value) {
+        let request = build_post_request("/auth/register", payload);
+        let ready_service = get_ready_service(app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn register_should_return_201_with_user_id() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = build_post_request("/auth/register", credentials_dummy());
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "User registered");
+        assert!(body["data"]["userId"].is_string());
+    }
+
+    #[tokio::test]
+    async fn taken_username_should_return_409() {
+        let mut app = app(Config::ephemeral()).await;
+        register(&mut app, credentials_dummy()).await;
+
+        let mut payload = credentials_dummy();
+        payload["username"] = json!("ALICE");
+        let (status, body) = register(&mut app, payload).await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["message"], "Username is already taken");
+    }
+
+    #[tokio::test]
+    async fn invalid_username_should_return_400() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for username in ["al", "alice smith", "al!ce", ""] {
+            let mut payload = credentials_dummy();
+            payload["username"] = json!(username);
+            let (status, body) = register(&mut app, payload).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "username `{}`", username);
+            assert_eq!(body["status"], "fail");
+        }
+    }
+
+    #[tokio::test]
+    async fn short_password_should_return_400() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut payload = credentials_dummy();
+        payload["password"] = json!("short");
+
+        let (status, body) = register(&mut app, payload).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["message"], "Password must be 8 to 128 characters");
+    }
+}
@@ -0,0 +1,33 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/// Opaque refresh token, 256 random bits in URL-safe base64.
+pub fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Marks API keys so leaked ones are easy to spot.
+pub const API_KEY_PREFIX: &str = "bks_";
+
+/// API key secret, [`API_KEY_PREFIX`] followed by 256 random bits.
+pub fn generate_api_key() -> String {
+    format!("{}{}", API_KEY_PREFIX, generate_refresh_token())
+}
+
+/// Marks webhook signing secrets, which unlike API keys are kept as is.
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Webhook signing secret, [`WEBHOOK_SECRET_PREFIX`] followed by 256 random
+/// bits.
+pub fn generate_webhook_secret() -> String {
+    format!("{}{}", WEBHOOK_SECRET_PREFIX, generate_refresh_token())
+}
+
+/// What is stored for a refresh token or API key. A plain digest is enough
+/// since the secret itself is random rather than chosen by a user.
+pub fn hash_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
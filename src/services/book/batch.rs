@@ -0,0 +1,236 @@
+//! Many book writes in one request, applied in a single [`BookTransaction`].
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::{Book, BookTransaction};
+use crate::services::audit::request_id;
+use crate::services::auth::scope::{Authorized, BooksWrite, Scope};
+
+use super::BookState;
+use super::handler::BookParams;
+
+pub const MAX_BATCH_OPERATIONS: usize = 100;
+
+/// One write, tagged by `op`. Each follows the rules of its single-book
+/// endpoint.
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOperation {
+    Create { book: BookParams },
+    Update { id: String, book: BookParams },
+    Delete { id: String },
+}
+
+/// Without `continueOnError` the first failing operation cancels the whole
+/// batch, with it the failures are reported and the rest is kept.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchParams {
+    operations: Vec<BatchOperation>,
+    #[serde(default)]
+    continue_on_error: bool,
+}
+
+/// Outcome of the operation at `index`.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    index: usize,
+    status: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    book_id: Option<Uuid>,
+}
+
+fn not_found(message: &str) -> AppError {
+    AppError::ClientFail(StatusCode::NOT_FOUND, message.to_string())
+}
+
+/// A write made by [`apply`], audited once the batch is committed.
+struct Applied {
+    message: &'static str,
+    action: AuditAction,
+    before: Option<Book>,
+    after: Option<Book>,
+}
+
+impl Applied {
+    fn book_id(&self) -> Option<Uuid> {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map(|book| book.id)
+    }
+}
+
+/// The book written and the message of the matching single-book endpoint.
+/// Client errors are raised before anything is written.
+async fn apply(
+    transaction: &mut dyn BookTransaction,
+    owner: Uuid,
+    operation: BatchOperation,
+) -> Result<Applied, AppError> {
+    match operation {
+        BatchOperation::Create { book } => {
+            book.validate("menambahkan")?;
+            let book = book.into_book(owner);
+            transaction.save_book(&book).await?;
+            Ok(Applied {
+                message: "Buku berhasil ditambahkan",
+                action: AuditAction::Create,
+                before: None,
+                after: Some(book),
+            })
+        }
+        BatchOperation::Update { id, book: params } => {
+            let missing = "Gagal memperbarui buku. Id tidak ditemukan";
+            let id = Uuid::parse_str(&id).map_err(|_| not_found(missing))?;
+            params.validate("memperbarui")?;
+            let before = transaction
+                .get_book_by_id(owner, id)
+                .await?
+                .ok_or_else(|| not_found(missing))?;
+            let mut book = before.clone();
+            params.apply_to(&mut book);
+            book.version = transaction.update_book(&book).await?;
+            Ok(Applied {
+                message: "Buku berhasil diperbarui",
+                action: AuditAction::Update,
+                before: Some(before),
+                after: Some(book),
+            })
+        }
+        BatchOperation::Delete { id } => {
+            let missing = "Buku gagal dihapus. Id tidak ditemukan";
+            let id = Uuid::parse_str(&id).map_err(|_| not_found(missing))?;
+            let book = transaction
+                .get_book_by_id(owner, id)
+                .await?
+                .ok_or_else(|| not_found(missing))?;
+            transaction.delete_book(owner, id).await?;
+            Ok(Applied {
+                message: "Buku berhasil dihapus",
+                action: AuditAction::Delete,
+                before: Some(book),
+                after: None,
+            })
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/books/batch",
+    request_body = BatchParams,
+    responses(
+        (status = 200, description = "Batch berhasil diproses, with a result per operation"),
+        (status = 400, description = "Too many or no operations, or an operation failed"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks books:write, or books:delete for deletes"),
+        (status = 404, description = "A book to update or delete was not found"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write", "books:delete"]),
+        ("apiKeyAuth" = ["books:write", "books:delete"])
+    )
+)]
+pub async fn batch_books(
+    State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
+    request_headers: HeaderMap,
+    Json(params): Json<BatchParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    if params.operations.is_empty() || params.operations.len() > MAX_BATCH_OPERATIONS {
+        let message = format!(
+            "Gagal memproses batch. Jumlah operasi harus antara 1 dan {}",
+            MAX_BATCH_OPERATIONS
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    if params
+        .operations
+        .iter()
+        .any(|operation| matches!(operation, BatchOperation::Delete { .. }))
+    {
+        claims.require(Scope::BooksDelete)?;
+    }
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let mut transaction = state.repo.begin().await?;
+    let mut results = Vec::new();
+    let mut writes = Vec::new();
+    for (index, operation) in params.operations.into_iter().enumerate() {
+        let result = match apply(transaction.as_mut(), owner, operation).await {
+            Ok(applied) => {
+                let result = BatchResult {
+                    index,
+                    status: "success",
+                    message: applied.message.to_string(),
+                    book_id: applied.book_id(),
+                };
+                writes.push(applied);
+                result
+            }
+            Err(AppError::ClientFail(status, message)) if !params.continue_on_error => {
+                // Dropping the transaction rolls back the earlier operations.
+                let body = Json(json!({
+                    "status": "fail",
+                    "message": format!("Batch dibatalkan. {}", message),
+                    "data": {
+                        "index": index
+                    }
+                }));
+                return Ok((status, headers, body));
+            }
+            Err(AppError::ClientFail(_, message)) => BatchResult {
+                index,
+                status: "fail",
+                message,
+                book_id: None,
+            },
+            Err(error) => return Err(error),
+        };
+        results.push(result);
+    }
+    transaction.commit().await?;
+    let request_id = request_id(&request_headers);
+    for applied in writes {
+        state
+            .changed(
+                owner,
+                applied.action,
+                applied.before.as_ref(),
+                applied.after.as_ref(),
+                request_id.clone(),
+            )
+            .await;
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.status == "fail")
+        .count();
+    let body = Json(json!({
+        "status": "success",
+        "message": "Batch berhasil diproses",
+        "data": {
+            "succeeded": results.len() - failed,
+            "failed": failed,
+            "results": results
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
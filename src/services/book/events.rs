@@ -0,0 +1,342 @@
+//! Changes to books pushed to clients as they happen, so dashboards don't
+//! have to poll. Served as Server-Sent Events at `/books/events` and over a
+//! WebSocket at `/books/events/ws`, each client only sees its own shelf.
+
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{
+        State, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    http::HeaderMap,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::Book;
+use crate::services::auth::scope::{Authorized, BooksRead};
+
+use super::BookState;
+
+/// Events kept for clients resuming with `Last-Event-ID`, and queued for a
+/// slow client before it misses some.
+pub const REPLAY_CAPACITY: usize = 256;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BookEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl BookEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookEventKind::Created => "created",
+            BookEventKind::Updated => "updated",
+            BookEventKind::Deleted => "deleted",
+        }
+    }
+}
+
+impl From<AuditAction> for BookEventKind {
+    /// A restored book is back on the shelf, which clients see as created.
+    fn from(action: AuditAction) -> Self {
+        match action {
+            AuditAction::Create | AuditAction::Restore => BookEventKind::Created,
+            AuditAction::Update => BookEventKind::Updated,
+            AuditAction::Delete => BookEventKind::Deleted,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookEvent {
+    /// Counts up from 1 since the server started.
+    pub id: u64,
+    pub kind: BookEventKind,
+    pub book_id: Uuid,
+    /// The book after the change, or as it was when deleted.
+    pub book: Book,
+    pub at: DateTime<Utc>,
+}
+
+struct Replay {
+    next_id: u64,
+    events: VecDeque<Arc<BookEvent>>,
+}
+
+/// Hands every [`BookEvent`] to the connected clients and keeps the latest
+/// [`REPLAY_CAPACITY`] for those reconnecting.
+#[derive(Clone)]
+pub struct BookEvents {
+    sender: broadcast::Sender<Arc<BookEvent>>,
+    replay: Arc<Mutex<Replay>>,
+}
+
+impl Default for BookEvents {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(REPLAY_CAPACITY);
+        BookEvents {
+            sender,
+            replay: Arc::new(Mutex::new(Replay {
+                next_id: 1,
+                events: VecDeque::with_capacity(REPLAY_CAPACITY),
+            })),
+        }
+    }
+}
+
+impl BookEvents {
+    pub fn publish(&self, kind: BookEventKind, book: &Book) {
+        // Numbering and sending under the lock keeps ids in order for
+        // every receiver.
+        let mut replay = self.replay.lock().unwrap();
+        let event = Arc::new(BookEvent {
+            id: replay.next_id,
+            kind,
+            book_id: book.id,
+            book: book.clone(),
+            at: Utc::now(),
+        });
+        replay.next_id += 1;
+        if replay.events.len() == REPLAY_CAPACITY {
+            replay.events.pop_front();
+        }
+        replay.events.push_back(event.clone());
+        // Sending only fails without receivers.
+        let _ = self.sender.send(event);
+    }
+
+    /// The kept events after `last_id`, along with a receiver of every later
+    /// one, so nothing is missed or repeated in between.
+    pub fn subscribe(
+        &self,
+        last_id: Option<u64>,
+    ) -> (Vec<Arc<BookEvent>>, broadcast::Receiver<Arc<BookEvent>>) {
+        let replay = self.replay.lock().unwrap();
+        let replayed = match last_id {
+            Some(last_id) => replay
+                .events
+                .iter()
+                .filter(|event| event.id > last_id)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        (replayed, self.sender.subscribe())
+    }
+}
+
+fn sse_event(event: &BookEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.kind.as_str())
+        .json_data(event)
+        .expect("A book event always serializes")
+}
+
+/// A `text/event-stream` of changes to the caller's books. Reconnecting
+/// with `Last-Event-ID` first replays the events missed since, as far as
+/// they are still kept. A client falling too far behind is disconnected and
+/// resumes the same way.
+#[utoipa::path(
+    get,
+    path = "/books/events",
+    params(
+        ("Last-Event-ID" = Option<u64>, Header, description = "Id of the last event received"),
+    ),
+    responses(
+        (status = 200, description = "Stream of created, updated and deleted events", content_type = "text/event-stream"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn book_events(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    request_headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let owner = claims.user_id()?;
+    let last_id = request_headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    let (replayed, receiver) = state.events.subscribe(last_id);
+    let live = BroadcastStream::new(receiver).map_while(Result::ok);
+    let events = tokio_stream::iter(replayed)
+        .chain(live)
+        .filter(move |event| event.book.owner_id == owner)
+        .map(|event| Ok(sse_event(&event)));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Which events a WebSocket client wants, every field left out matches all.
+#[derive(Serialize, Deserialize, ToSchema, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kinds: Option<Vec<BookEventKind>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    book_ids: Option<Vec<Uuid>>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &BookEvent) -> bool {
+        self.kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&event.kind))
+            && self
+                .book_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&event.book_id))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ClientMessage {
+    /// Replaces the filter, replaying the kept events after `lastEventId`.
+    Subscribe {
+        #[serde(flatten)]
+        filter: EventFilter,
+        #[serde(rename = "lastEventId")]
+        last_event_id: Option<u64>,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ServerMessage<'a> {
+    Subscribed(&'a EventFilter),
+    Event(&'a BookEvent),
+    Error { message: String },
+}
+
+/// False once the client is gone.
+async fn send(socket: &mut WebSocket, message: &ServerMessage<'_>) -> bool {
+    let text = serde_json::to_string(message).expect("A server message always serializes");
+    socket.send(Message::Text(text.into())).await.is_ok()
+}
+
+/// Upgrades to a WebSocket sending `{"type": "event", ...}` for each change
+/// to the caller's books. Every event is sent until the client narrows them
+/// with `{"type": "subscribe", "kinds": [...], "bookIds": [...]}`, which is
+/// acknowledged with `{"type": "subscribed", ...}` and can also carry a
+/// `lastEventId` to catch up from.
+#[utoipa::path(
+    get,
+    path = "/books/events/ws",
+    responses(
+        (status = 101, description = "Switched to the WebSocket protocol"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn book_events_ws(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let owner = claims.user_id()?;
+    // Subscribed before answering, so changes made once the client is
+    // connected are never missed.
+    let (_, receiver) = state.events.subscribe(None);
+    Ok(upgrade
+        .on_upgrade(move |socket| serve_socket(socket, state.events, receiver, owner))
+        .into_response())
+}
+
+async fn serve_socket(
+    mut socket: WebSocket,
+    events: BookEvents,
+    mut receiver: broadcast::Receiver<Arc<BookEvent>>,
+    owner: Uuid,
+) {
+    let mut filter = EventFilter::default();
+    // Replayed events still queued in `receiver` aren't sent twice.
+    let mut replayed_up_to = 0;
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                let sent = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Subscribe { filter: subscribed, last_event_id }) => {
+                        filter = subscribed;
+                        let mut sent = send(&mut socket, &ServerMessage::Subscribed(&filter)).await;
+                        let (replayed, _) = events.subscribe(last_event_id);
+                        for event in replayed {
+                            if sent && event.book.owner_id == owner && filter.matches(&event) {
+                                sent = send(&mut socket, &ServerMessage::Event(&event)).await;
+                            }
+                            replayed_up_to = replayed_up_to.max(event.id);
+                        }
+                        sent
+                    }
+                    Err(error) => {
+                        let message = format!("Invalid message. {}", error);
+                        send(&mut socket, &ServerMessage::Error { message }).await
+                    }
+                };
+                if !sent {
+                    break;
+                }
+            }
+            event = receiver.recv() => {
+                let sent = match event {
+                    Ok(event) => {
+                        if event.id <= replayed_up_to
+                            || event.book.owner_id != owner
+                            || !filter.matches(&event)
+                        {
+                            continue;
+                        }
+                        send(&mut socket, &ServerMessage::Event(&event)).await
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        let message = format!(
+                            "Missed {} events, subscribe again with lastEventId to catch up",
+                            missed
+                        );
+                        send(&mut socket, &ServerMessage::Error { message }).await
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if !sent {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::{Book, BookFilter, BookSort, BookSummary, SortOrder};
+use crate::services::audit::REQUEST_ID_HEADER;
+use crate::services::auth::{API_KEY_HEADER, scope::Scope};
+
+use super::BookState;
+use super::handler::{BookParams, next_page, page_request, validate_filter};
+use proto::book_service_server::BookService;
+use proto::{
+    CreateBookRequest, CreateBookResponse, DeleteBookRequest, DeleteBookResponse, GetBookRequest,
+    GetBookResponse, ListBooksRequest, ListBooksResponse, UpdateBookRequest, UpdateBookResponse,
+};
+
+pub mod proto {
+    tonic::include_proto!("bookshelf.v1");
+}
+
+/// `bookshelf.v1.BookService`, sharing the repository of the REST handlers.
+pub struct BookGrpcService {
+    state: BookState,
+}
+
+impl BookGrpcService {
+    pub fn new(state: BookState) -> Self {
+        BookGrpcService { state }
+    }
+}
+
+impl From<proto::BookInput> for BookParams {
+    fn from(input: proto::BookInput) -> Self {
+        BookParams {
+            name: input.name,
+            year: input.year,
+            author: input.author,
+            summary: input.summary,
+            publisher: input.publisher,
+            page_count: input.page_count,
+            read_page: input.read_page,
+            reading: input.reading,
+            finished: false,
+            inserted_at: DateTime::default(),
+            updated_at: DateTime::default(),
+        }
+    }
+}
+
+impl From<Book> for proto::Book {
+    fn from(book: Book) -> Self {
+        proto::Book {
+            id: book.id.to_string(),
+            name: book.name,
+            year: book.year,
+            author: book.author,
+            summary: book.summary,
+            publisher: book.publisher,
+            page_count: book.page_count,
+            read_page: book.read_page,
+            reading: book.reading,
+            finished: book.finished,
+            inserted_at: book.inserted_at.to_rfc3339(),
+            updated_at: book.updated_at.to_rfc3339(),
+            owner_id: book.owner_id.to_string(),
+            version: book.version,
+        }
+    }
+}
+
+impl From<BookSummary> for proto::BookSummary {
+    fn from(book: BookSummary) -> Self {
+        proto::BookSummary {
+            id: book.id.to_string(),
+            name: book.name,
+            publisher: book.publisher,
+        }
+    }
+}
+
+fn parse_id(id: &str, message: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(id)
+        .map_err(|_| AppError::ClientFail(StatusCode::NOT_FOUND, message.to_string()))
+}
+
+fn request_id<T>(request: &Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+fn required_input(input: Option<proto::BookInput>) -> Result<BookParams, Status> {
+    input
+        .map(BookParams::from)
+        .ok_or_else(|| Status::invalid_argument("book is required"))
+}
+
+fn parse_timestamp(value: Option<String>, field: &str) -> Result<Option<DateTime<Utc>>, Status> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(&value)
+                .map(|at| at.with_timezone(&Utc))
+                .map_err(|_| Status::invalid_argument(format!("{} must be RFC 3339", field)))
+        })
+        .transpose()
+}
+
+impl BookGrpcService {
+    /// The user named by the `x-api-key` metadata or else the bearer token in
+    /// `authorization`, which must have been granted `scope`.
+    async fn owner<T>(&self, request: &Request<T>, scope: Scope) -> Result<Uuid, AppError> {
+        let metadata = request.metadata();
+        let claims = if let Some(key) = metadata.get(API_KEY_HEADER) {
+            let key = key.to_str().map_err(|_| AppError::InvalidToken)?;
+            self.state.auth.verify_api_key(key).await?
+        } else {
+            let token = metadata
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .ok_or(AppError::InvalidToken)?;
+            self.state.auth.verify(token).await?
+        };
+        claims.require(scope)?;
+        claims.user_id()
+    }
+}
+
+#[tonic::async_trait]
+impl BookService for BookGrpcService {
+    async fn create_book(
+        &self,
+        request: Request<CreateBookRequest>,
+    ) -> Result<Response<CreateBookResponse>, Status> {
+        let owner = self.owner(&request, Scope::BooksWrite).await?;
+        let request_id = request_id(&request);
+        let params = required_input(request.into_inner().book)?;
+        params.validate("menambahkan")?;
+
+        let book = params.into_book(owner);
+        let id = self.state.repo.save_book(&book).await?;
+        self.state
+            .changed(owner, AuditAction::Create, None, Some(&book), request_id)
+            .await;
+
+        Ok(Response::new(CreateBookResponse {
+            book_id: id.to_string(),
+        }))
+    }
+
+    async fn list_books(
+        &self,
+        request: Request<ListBooksRequest>,
+    ) -> Result<Response<ListBooksResponse>, Status> {
+        let owner = self.owner(&request, Scope::BooksRead).await?;
+        let query = request.into_inner();
+        let sort = match query.sort() {
+            proto::BookSort::Unspecified => None,
+            proto::BookSort::Name => Some(BookSort::Name),
+            proto::BookSort::Year => Some(BookSort::Year),
+            proto::BookSort::InsertedAt => Some(BookSort::InsertedAt),
+            proto::BookSort::UpdatedAt => Some(BookSort::UpdatedAt),
+        };
+        let order = match query.order() {
+            proto::SortOrder::Unspecified => None,
+            proto::SortOrder::Asc => Some(SortOrder::Asc),
+            proto::SortOrder::Desc => Some(SortOrder::Desc),
+        };
+        let page = page_request(
+            (query.page != 0).then_some(query.page),
+            (query.limit != 0).then_some(query.limit),
+            sort,
+            order,
+        )?;
+
+        let filter = BookFilter {
+            inserted_after: parse_timestamp(query.inserted_after, "inserted_after")?,
+            updated_since: parse_timestamp(query.updated_since, "updated_since")?,
+            finished_since: None,
+            finished_before: None,
+            name: query.name,
+            author: query.author,
+            publisher: query.publisher,
+            reading: query.reading,
+            finished: query.finished,
+            year_from: query.year_from,
+            year_to: query.year_to,
+            page_count_min: query.page_count_min,
+            page_count_max: query.page_count_max,
+            read_percent_min: query.read_percent_min,
+            read_percent_max: query.read_percent_max,
+        };
+        validate_filter(&filter)?;
+
+        let books = self.state.repo.get_books(owner, &filter, &page).await?;
+
+        Ok(Response::new(ListBooksResponse {
+            next_page: next_page(&page, books.total),
+            books: books.items.into_iter().map(Into::into).collect(),
+            total: books.total,
+        }))
+    }
+
+    async fn get_book(
+        &self,
+        request: Request<GetBookRequest>,
+    ) -> Result<Response<GetBookResponse>, Status> {
+        let owner = self.owner(&request, Scope::BooksRead).await?;
+        let message = "Buku tidak ditemukan";
+        let book_id = parse_id(&request.into_inner().id, message)?;
+
+        let book = self
+            .state
+            .repo
+            .get_book_by_id(owner, book_id)
+            .await?
+            .ok_or_else(|| AppError::ClientFail(StatusCode::NOT_FOUND, message.to_string()))?;
+
+        Ok(Response::new(GetBookResponse {
+            book: Some(book.into()),
+        }))
+    }
+
+    async fn update_book(
+        &self,
+        request: Request<UpdateBookRequest>,
+    ) -> Result<Response<UpdateBookResponse>, Status> {
+        let owner = self.owner(&request, Scope::BooksWrite).await?;
+        let message = "Gagal memperbarui buku. Id tidak ditemukan";
+        let request_id = request_id(&request);
+        let request = request.into_inner();
+        let book_id = parse_id(&request.id, message)?;
+
+        let params = required_input(request.book)?;
+        params.validate("memperbarui")?;
+
+        let before = self
+            .state
+            .repo
+            .get_book_by_id(owner, book_id)
+            .await?
+            .ok_or_else(|| AppError::ClientFail(StatusCode::NOT_FOUND, message.to_string()))?;
+        let mut book = before.clone();
+        params.apply_to(&mut book);
+        book.version = self.state.repo.update_book(&book).await?;
+        self.state
+            .changed(
+                owner,
+                AuditAction::Update,
+                Some(&before),
+                Some(&book),
+                request_id,
+            )
+            .await;
+
+        Ok(Response::new(UpdateBookResponse {
+            book: Some(book.into()),
+        }))
+    }
+
+    async fn delete_book(
+        &self,
+        request: Request<DeleteBookRequest>,
+    ) -> Result<Response<DeleteBookResponse>, Status> {
+        let owner = self.owner(&request, Scope::BooksDelete).await?;
+        let request_id = request_id(&request);
+        let message = "Buku gagal dihapus. Id tidak ditemukan";
+        let book_id = parse_id(&request.into_inner().id, message)?;
+
+        let book = self
+            .state
+            .repo
+            .get_book_by_id(owner, book_id)
+            .await?
+            .ok_or_else(|| AppError::ClientFail(StatusCode::NOT_FOUND, message.to_string()))?;
+        let deleted_id = self.state.repo.delete_book(owner, book_id).await?;
+        self.state
+            .changed(owner, AuditAction::Delete, Some(&book), None, request_id)
+            .await;
+
+        Ok(Response::new(DeleteBookResponse {
+            book_id: deleted_id.to_string(),
+        }))
+    }
+}
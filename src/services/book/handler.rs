@@ -1,17 +1,25 @@
+use axum::extract::rejection::{JsonRejection, QueryRejection};
 use axum::extract::{Path, Query};
-use axum::http::{StatusCode, header};
-use axum::response::IntoResponse;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
 use axum::{Json, extract::State};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
-use serde_json::json;
-use utoipa::ToSchema;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Value, json};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::AppError;
-use crate::repos::book::Book;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::search::search_terms;
+use crate::repos::book::{
+    Book, BookFilter, BookSort, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT, PageRequest, SortOrder,
+};
 
 use super::BookState;
+use super::precondition::{check_if_match, etag, not_modified};
+use crate::services::audit::request_id;
+use crate::services::auth::scope::{Authorized, BooksDelete, BooksRead, BooksWrite};
 
 #[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -35,11 +43,206 @@ pub struct BookParams {
     #[allow(dead_code)]
     pub updated_at: DateTime<Utc>,
 }
-#[derive(Deserialize, ToSchema)]
+
+impl BookParams {
+    /// Checks the rules shared by every write, `action` completes the
+    /// "Gagal ... buku" message.
+    pub fn validate(&self, action: &str) -> Result<(), AppError> {
+        check_book(&self.name, self.read_page, self.page_count, action)
+    }
+
+    pub fn into_book(self, owner: Uuid) -> Book {
+        let mut book = Book {
+            id: Uuid::new_v4(),
+            owner_id: owner,
+            name: self.name,
+            year: self.year,
+            publisher: self.publisher,
+            author: self.author,
+            summary: self.summary,
+            page_count: self.page_count,
+            read_page: self.read_page,
+            reading: self.reading,
+            updated_at: Utc::now(),
+            inserted_at: Utc::now(),
+            finished: false,
+            finished_at: None,
+            version: 1,
+        };
+        update_finished(&mut book);
+        book
+    }
+
+    /// Replaces every field of `book` but its id, owner and insertion time.
+    pub fn apply_to(self, book: &mut Book) {
+        book.name = self.name;
+        book.year = self.year;
+        book.author = self.author;
+        book.summary = self.summary;
+        book.publisher = self.publisher;
+        book.page_count = self.page_count;
+        book.read_page = self.read_page;
+        book.reading = self.reading;
+        book.updated_at = Utc::now();
+        update_finished(book);
+    }
+}
+
+/// A book counts as finished while it is being read on its last page.
+pub fn is_finished(reading: bool, read_page: i32, page_count: i32) -> bool {
+    reading && read_page == page_count
+}
+
+/// Recomputes `finished` of `book`, stamping `finished_at` when it becomes
+/// finished and clearing it once it no longer is. Edits that keep it finished
+/// keep the time it was finished.
+pub fn update_finished(book: &mut Book) {
+    book.finished = is_finished(book.reading, book.read_page, book.page_count);
+    book.finished_at = if book.finished {
+        book.finished_at.or(Some(book.updated_at))
+    } else {
+        None
+    };
+}
+
+/// The rules every stored book follows, `action` completes the
+/// "Gagal ... buku" message.
+fn check_book(name: &str, read_page: i32, page_count: i32, action: &str) -> Result<(), AppError> {
+    if name.is_empty() {
+        let message = format!("Gagal {} buku. Mohon isi nama buku", action);
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+
+    if read_page > page_count {
+        let message = format!(
+            "Gagal {} buku. readPage tidak boleh lebih besar dari pageCount",
+            action
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+
+    Ok(())
+}
+
+/// RFC 7396 merge patch of a book. Absent members keep their value, and
+/// `null` is rejected since no member can be removed.
+#[derive(Deserialize, ToSchema, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BookPatch {
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<i32>)]
+    pub year: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub author: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub summary: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
+    pub publisher: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<i32>)]
+    pub page_count: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<i32>)]
+    pub read_page: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<bool>)]
+    pub reading: Option<Option<bool>>,
+}
+
+/// Tells a member set to `null`, `Some(None)`, from an absent one, `None`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn patch_field<T>(field: &str, value: Option<Option<T>>, target: &mut T) -> Result<(), AppError> {
+    match value {
+        None => Ok(()),
+        Some(None) => {
+            let message = format!("Gagal memperbarui buku. {} tidak boleh null", field);
+            Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message))
+        }
+        Some(Some(value)) => {
+            *target = value;
+            Ok(())
+        }
+    }
+}
+
+impl BookPatch {
+    /// Patches `book` and recomputes `finished`, leaving it untouched unless
+    /// the result is a valid book.
+    pub fn apply_to(self, book: &mut Book) -> Result<(), AppError> {
+        let mut patched = book.clone();
+        patch_field("name", self.name, &mut patched.name)?;
+        patch_field("year", self.year, &mut patched.year)?;
+        patch_field("author", self.author, &mut patched.author)?;
+        patch_field("summary", self.summary, &mut patched.summary)?;
+        patch_field("publisher", self.publisher, &mut patched.publisher)?;
+        patch_field("pageCount", self.page_count, &mut patched.page_count)?;
+        patch_field("readPage", self.read_page, &mut patched.read_page)?;
+        patch_field("reading", self.reading, &mut patched.reading)?;
+        check_book(
+            &patched.name,
+            patched.read_page,
+            patched.page_count,
+            "memperbarui",
+        )?;
+
+        patched.updated_at = Utc::now();
+        update_finished(&mut patched);
+        *book = patched;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
 pub struct BooksQuery {
+    /// Filter books by name containing the given string
     name: Option<String>,
+    /// Filter books by author containing the given string
+    author: Option<String>,
+    /// Filter books by publisher containing the given string
+    publisher: Option<String>,
+    /// Filter books by reading status (1 for reading, 0 for not reading)
     reading: Option<String>,
+    /// Filter books by finished status (1 for finished, 0 for not finished)
     finished: Option<String>,
+    /// Only books published in or after this year
+    year_from: Option<i32>,
+    /// Only books published in or before this year
+    year_to: Option<i32>,
+    /// Only books with at least this many pages
+    page_count_min: Option<i32>,
+    /// Only books with at most this many pages
+    page_count_max: Option<i32>,
+    /// Only books added after this RFC 3339 timestamp
+    inserted_after: Option<DateTime<Utc>>,
+    /// Only books updated at or after this RFC 3339 timestamp
+    updated_since: Option<DateTime<Utc>>,
+    /// Only books with at least this percentage read, between 0 and 100
+    read_percent_min: Option<f64>,
+    /// Only books with at most this percentage read, between 0 and 100
+    read_percent_max: Option<f64>,
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Books per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+    /// Column to sort by, defaults to insertedAt
+    sort: Option<BookSort>,
+    /// Sort direction, defaults to asc
+    order: Option<SortOrder>,
 }
 
 #[utoipa::path(
@@ -49,42 +252,34 @@ pub struct BooksQuery {
     responses(
         (status = 201, description = "Buku berhasil ditambahkan"),
         (status = 400, description = "Gagal menambahkan buku"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
     )
 )]
 pub async fn create_book(
     State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
+    request_headers: HeaderMap,
     Json(params): Json<BookParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    if params.name.is_empty() {
-        let message = "Gagal menambahkan buku. Mohon isi nama buku".to_string();
-        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
-    }
+    let owner = claims.user_id()?;
+    params.validate("menambahkan")?;
 
-    if params.read_page > params.page_count {
-        let message =
-            "Gagal menambahkan buku. readPage tidak boleh lebih besar dari pageCount".to_string();
-        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
-    }
-
-    let book = Book {
-        id: Uuid::new_v4(),
-        name: params.name,
-        year: params.year,
-        publisher: params.publisher,
-        author: params.author,
-        summary: params.summary,
-        page_count: params.page_count,
-        read_page: params.read_page,
-        reading: params.reading,
-        updated_at: Utc::now(),
-        inserted_at: Utc::now(),
-        finished: if params.reading {
-            params.read_page == params.page_count
-        } else {
-            false
-        },
-    };
+    let book = params.into_book(owner);
     let id = state.repo.save_book(&book).await?;
+    state
+        .changed(
+            owner,
+            AuditAction::Create,
+            None,
+            Some(&book),
+            request_id(&request_headers),
+        )
+        .await;
 
     let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
 
@@ -99,22 +294,94 @@ pub async fn create_book(
     Ok((StatusCode::CREATED, headers, body))
 }
 
+/// Fills in pagination defaults and rejects out of range values.
+pub fn page_request(
+    page: Option<u32>,
+    limit: Option<u32>,
+    sort: Option<BookSort>,
+    order: Option<SortOrder>,
+) -> Result<PageRequest, AppError> {
+    let page = PageRequest {
+        page: page.unwrap_or(1),
+        limit: limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+        sort: sort.unwrap_or_default(),
+        order: order.unwrap_or_default(),
+    };
+    if page.page == 0 {
+        let message = "Gagal menampilkan buku. page minimal 1".to_string();
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    if page.limit == 0 || page.limit > MAX_PAGE_LIMIT {
+        let message = format!(
+            "Gagal menampilkan buku. limit harus antara 1 dan {}",
+            MAX_PAGE_LIMIT
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    Ok(page)
+}
+
+/// Rejects empty ranges and percentages outside 0..=100.
+pub fn validate_filter(filter: &BookFilter) -> Result<(), AppError> {
+    let fail = |message: &str| {
+        let message = format!("Gagal menampilkan buku. {}", message);
+        Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message))
+    };
+
+    if let (Some(from), Some(to)) = (filter.year_from, filter.year_to)
+        && from > to
+    {
+        return fail("yearFrom tidak boleh lebih besar dari yearTo");
+    }
+    if let (Some(min), Some(max)) = (filter.page_count_min, filter.page_count_max)
+        && min > max
+    {
+        return fail("pageCountMin tidak boleh lebih besar dari pageCountMax");
+    }
+    for percent in [filter.read_percent_min, filter.read_percent_max]
+        .into_iter()
+        .flatten()
+    {
+        if !(0.0..=100.0).contains(&percent) {
+            return fail("readPercent harus antara 0 dan 100");
+        }
+    }
+    if let (Some(min), Some(max)) = (filter.read_percent_min, filter.read_percent_max)
+        && min > max
+    {
+        return fail("readPercentMin tidak boleh lebih besar dari readPercentMax");
+    }
+    Ok(())
+}
+
 #[utoipa::path(
     get,
     path = "/books",
-    params(
-        ("name" = Option<String>, Query, description = "Filter books by name containing the given string"),
-        ("reading" = Option<String>, Query, description = "Filter books by reading status (1 for reading, 0 for not reading)"),
-        ("finished" = Option<String>, Query, description = "Filter books by finished status (1 for finished, 0 for not finished)"),
-    ),
+    params(BooksQuery),
     responses(
         (status = 200, description = "List of books retrieved successfully"),
+        (status = 400, description = "Invalid query parameters"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
     )
 )]
 pub async fn get_books(
     State(state): State<BookState>,
-    Query(query): Query<BooksQuery>,
+    claims: Authorized<BooksRead>,
+    query: Result<Query<BooksQuery>, QueryRejection>,
 ) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal menampilkan buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+
+    let page = page_request(query.page, query.limit, query.sort, query.order)?;
+
     let reading = query
         .reading
         .and_then(|s| s.parse::<u8>().ok())
@@ -123,43 +390,162 @@ pub async fn get_books(
         .finished
         .and_then(|s| s.parse::<u8>().ok())
         .map(|n| n == 1);
-    let name = query.name;
+    let filter = BookFilter {
+        name: query.name,
+        author: query.author,
+        publisher: query.publisher,
+        reading,
+        finished,
+        year_from: query.year_from,
+        year_to: query.year_to,
+        page_count_min: query.page_count_min,
+        page_count_max: query.page_count_max,
+        inserted_after: query.inserted_after,
+        updated_since: query.updated_since,
+        finished_since: None,
+        finished_before: None,
+        read_percent_min: query.read_percent_min,
+        read_percent_max: query.read_percent_max,
+    };
+    validate_filter(&filter)?;
 
-    let books = state.repo.get_books(name, reading, finished).await?;
+    let books = state.repo.get_books(owner, &filter, &page).await?;
 
     let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
     let body = Json(json!({
         "status": "success",
         "data": {
-            "books": books
-        }
+            "books": books.items
+        },
+        "meta": page_meta(&page, books.total)
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+pub fn page_meta(page: &PageRequest, total: u64) -> Value {
+    json!({
+        "page": page.page,
+        "limit": page.limit,
+        "total": total,
+        "totalPages": total_pages(page, total),
+        "nextPage": next_page(page, total)
+    })
+}
+
+fn total_pages(page: &PageRequest, total: u64) -> u64 {
+    total.div_ceil(u64::from(page.limit))
+}
+
+/// The page after `page` out of `total` matches, unless it is the last.
+pub fn next_page(page: &PageRequest, total: u64) -> Option<u32> {
+    (u64::from(page.page) < total_pages(page, total)).then_some(page.page + 1)
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Words to look for in the name, author and summary, all must match
+    q: Option<String>,
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Results per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/books/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matching books, best match first"),
+        (status = 400, description = "Missing search words or invalid paging"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn search_books(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    query: Result<Query<SearchQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal mencari buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+
+    let q = query.q.unwrap_or_default();
+    if search_terms(&q).is_empty() {
+        let message = "Gagal mencari buku. Mohon isi kata kunci pencarian".to_string();
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+    let page = page_request(query.page, query.limit, None, None)?;
+
+    let books = state.repo.search_books(owner, &q, &page).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "books": books.items
+        },
+        "meta": page_meta(&page, books.total)
     }));
 
     Ok((StatusCode::OK, headers, body))
 }
 
+/// The ETag of the book is sent along, a matching `If-None-Match` gets an
+/// empty 304 instead.
 #[utoipa::path(
     get,
     path = "/books/{id}",
     responses(
         (status = 200, description = "Buku ditemukan"),
+        (status = 304, description = "The book still matches If-None-Match"),
         (status = 404, description = "Buku tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
     ),
     params(
         ("id" = String, Path, description = "ID of the book to retrieve"),
+        ("If-None-Match" = Option<String>, Header, description = "ETags the client already has"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
     )
 )]
 pub async fn get_book_by_id(
     State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
     Path(id): Path<String>,
-) -> Result<impl IntoResponse, AppError> {
+    request_headers: HeaderMap,
+) -> Result<Response, AppError> {
     let book_id = Uuid::parse_str(&id).map_err(|_| {
         let message = "Buku tidak ditemukan".to_string();
         AppError::ClientFail(StatusCode::NOT_FOUND, message)
     })?;
 
-    if let Ok(Some(book)) = state.repo.get_book_by_id(book_id).await {
-        let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let owner = claims.user_id()?;
+    if let Ok(Some(book)) = state.repo.get_book_by_id(owner, book_id).await {
+        if not_modified(&request_headers, &book) {
+            let headers = [(header::ETAG, etag(&book))];
+            return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+        }
+
+        let headers = [
+            (
+                header::CONTENT_TYPE,
+                "application/json; charset=utf-8".to_string(),
+            ),
+            (header::ETAG, etag(&book)),
+        ];
         let body = Json(json!({
             "status": "success",
             "data": {
@@ -167,13 +553,16 @@ pub async fn get_book_by_id(
             }
         }));
 
-        Ok((StatusCode::OK, headers, body))
+        Ok((StatusCode::OK, headers, body).into_response())
     } else {
         let message = "Buku tidak ditemukan".to_string();
         Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
     }
 }
 
+/// Full replacement, every field is required. See [`patch_book`] for partial
+/// updates. With `If-Match` the book must still be at one of the listed
+/// ETags.
 #[utoipa::path(
     put,
     path = "/books/{id}",
@@ -182,112 +571,204 @@ pub async fn get_book_by_id(
         (status = 200, description = "Buku berhasil diperbarui"),
         (status = 400, description = "Gagal memperbarui buku"),
         (status = 404, description = "Buku tidak ditemukan"),
+        (status = 412, description = "The book changed since the ETag in If-Match"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
     ),
     params(
         ("id" = String, Path, description = "ID of the book to update"),
+        ("If-Match" = Option<String>, Header, description = "ETag the update is based on"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
     )
 )]
 pub async fn update_book(
     State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
     Path(id): Path<String>,
+    request_headers: HeaderMap,
     Json(params): Json<BookParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let book_id = Uuid::parse_str(&id).map_err(|_| {
+    let not_found = || {
         let message = "Gagal memperbarui buku. Id tidak ditemukan".to_string();
         AppError::ClientFail(StatusCode::NOT_FOUND, message)
-    })?;
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
 
-    if params.name.is_empty() {
-        let message = "Gagal memperbarui buku. Mohon isi nama buku".to_string();
-        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
-    }
+    let owner = claims.user_id()?;
+    params.validate("memperbarui")?;
 
-    if params.read_page > params.page_count {
-        let message =
-            "Gagal memperbarui buku. readPage tidak boleh lebih besar dari pageCount".to_string();
-        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
-    }
-
-    if let Ok(Some(mut book)) = state.repo.get_book_by_id(book_id).await {
-        book.name = params.name;
-        book.year = if params.year == 0 {
-            book.year
-        } else {
-            params.year
-        };
-        book.author = if params.author.is_empty() {
-            book.author
-        } else {
-            params.author
-        };
-        book.summary = if params.summary.is_empty() {
-            book.summary
-        } else {
-            params.summary
-        };
-        book.publisher = if params.publisher.is_empty() {
-            book.publisher
-        } else {
-            params.publisher
-        };
-        book.page_count = if params.page_count == 0 {
-            book.page_count
-        } else {
-            params.page_count
-        };
-        book.read_page = if params.read_page == 0 {
-            book.read_page
-        } else {
-            params.read_page
-        };
-        book.reading = if params.reading {
-            params.reading
-        } else {
-            book.reading
-        };
-        book.finished = if params.reading {
-            params.read_page == params.page_count
-        } else {
-            false
-        };
-        book.updated_at = Utc::now();
+    let before = state
+        .repo
+        .get_book_by_id(owner, book_id)
+        .await?
+        .ok_or_else(not_found)?;
+    check_if_match(&request_headers, &before, "Gagal memperbarui buku")?;
+    let mut book = before.clone();
+    params.apply_to(&mut book);
+    book.version = state.repo.update_book(&book).await?;
+    state
+        .changed(
+            owner,
+            AuditAction::Update,
+            Some(&before),
+            Some(&book),
+            request_id(&request_headers),
+        )
+        .await;
 
-        let _ = state.repo.save_book(&book).await;
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            "application/json; charset=utf-8".to_string(),
+        ),
+        (header::ETAG, etag(&book)),
+    ];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Buku berhasil diperbarui"
+    }));
 
-        let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
-        let body = Json(json!({
-            "status": "success",
-            "message": "Buku berhasil diperbarui"
-        }));
+    Ok((StatusCode::OK, headers, body))
+}
 
-        Ok((StatusCode::OK, headers, body))
-    } else {
+/// Changes only the members present in an RFC 7396 merge patch, so values
+/// like `readPage: 0` or `reading: false` can be set. `If-Match` works as
+/// for [`update_book`].
+#[utoipa::path(
+    patch,
+    path = "/books/{id}",
+    request_body(content = BookPatch, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Buku berhasil diperbarui"),
+        (status = 400, description = "Gagal memperbarui buku"),
+        (status = 404, description = "Gagal memperbarui buku. Id tidak ditemukan"),
+        (status = 412, description = "The book changed since the ETag in If-Match"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+    ),
+    params(
+        ("id" = String, Path, description = "ID of the book to patch"),
+        ("If-Match" = Option<String>, Header, description = "ETag the patch is based on"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
+    )
+)]
+pub async fn patch_book(
+    State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
+    Path(id): Path<String>,
+    request_headers: HeaderMap,
+    patch: Result<Json<BookPatch>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || {
         let message = "Gagal memperbarui buku. Id tidak ditemukan".to_string();
-        Err(AppError::ClientFail(StatusCode::NOT_FOUND, message))
-    }
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let owner = claims.user_id()?;
+    let Json(patch) = patch.map_err(|rejection| {
+        let message = format!("Gagal memperbarui buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+
+    let before = state
+        .repo
+        .get_book_by_id(owner, book_id)
+        .await?
+        .ok_or_else(not_found)?;
+    check_if_match(&request_headers, &before, "Gagal memperbarui buku")?;
+    let mut book = before.clone();
+    patch.apply_to(&mut book)?;
+    book.version = state.repo.update_book(&book).await?;
+    state
+        .changed(
+            owner,
+            AuditAction::Update,
+            Some(&before),
+            Some(&book),
+            request_id(&request_headers),
+        )
+        .await;
+
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            "application/json; charset=utf-8".to_string(),
+        ),
+        (header::ETAG, etag(&book)),
+    ];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Buku berhasil diperbarui",
+        "data": {
+            "book": book
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
 }
 
+/// With `If-Match` the book must still be at one of the listed ETags.
 #[utoipa::path(
     delete,
     path = "/books/{id}",
     responses(
         (status = 200, description = "Buku berhasil dihapus"),
         (status = 404, description = "Buku gagal dihapus. Id tidak ditemukan"),
+        (status = 412, description = "The book changed since the ETag in If-Match"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:delete scope"),
     ),
     params(
         ("id" = String, Path, description = "ID of the book to delete"),
+        ("If-Match" = Option<String>, Header, description = "ETag the deletion is based on"),
+    ),
+    security(
+        ("bearerAuth" = ["books:delete"]),
+        ("apiKeyAuth" = ["books:delete"])
     )
 )]
 pub async fn delete_book(
     State(state): State<BookState>,
+    claims: Authorized<BooksDelete>,
     Path(id): Path<String>,
+    request_headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
-    let book_id = Uuid::parse_str(&id).map_err(|_| {
+    let not_found = || {
         let message = "Buku gagal dihapus. Id tidak ditemukan".to_string();
         AppError::ClientFail(StatusCode::NOT_FOUND, message)
-    })?;
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
 
-    let deleted_id = state.repo.delete_book(book_id).await?;
+    let owner = claims.user_id()?;
+    let book = state
+        .repo
+        .get_book_by_id(owner, book_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let deleted_id = if request_headers.contains_key(header::IF_MATCH) {
+        check_if_match(&request_headers, &book, "Buku gagal dihapus")?;
+        state
+            .repo
+            .delete_book_version(owner, book_id, book.version)
+            .await?
+    } else {
+        state.repo.delete_book(owner, book_id).await?
+    };
+    state
+        .changed(
+            owner,
+            AuditAction::Delete,
+            Some(&book),
+            None,
+            request_id(&request_headers),
+        )
+        .await;
 
     let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
     let body = Json(json!({
@@ -0,0 +1,89 @@
+//! What happened to a single book, read from the audit log.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State, rejection::QueryRejection},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditFilter;
+use crate::services::auth::scope::{Authorized, BooksRead};
+
+use super::BookState;
+use super::handler::{page_meta, page_request};
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Entries per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+}
+
+/// Every recorded change to a book of the caller, newest first. Kept after
+/// the book is deleted and purged.
+#[utoipa::path(
+    get,
+    path = "/books/{id}/history",
+    params(
+        ("id" = String, Path, description = "ID of the book"),
+        HistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Audit entries of the book, newest first"),
+        (status = 400, description = "Invalid paging"),
+        (status = 404, description = "Riwayat buku tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_book_history(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    Path(id): Path<String>,
+    query: Result<Query<HistoryQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || {
+        let message = "Riwayat buku tidak ditemukan".to_string();
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal menampilkan riwayat buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+    let page = page_request(query.page, query.limit, None, None)?;
+
+    let filter = AuditFilter {
+        owner_id: Some(owner),
+        book_id: Some(book_id),
+        ..AuditFilter::default()
+    };
+    let entries = state.audit.get_entries(&filter, &page).await?;
+    if entries.total == 0 {
+        return Err(not_found());
+    }
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "entries": entries.items
+        },
+        "meta": page_meta(&page, entries.total)
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
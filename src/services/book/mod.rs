@@ -1,11 +1,74 @@
 use std::sync::Arc;
 
-use crate::repos::book::BookRepo;
+use axum::extract::FromRef;
 
+use uuid::Uuid;
+
+use crate::{
+    repos::{
+        audit::{AuditAction, AuditEntry, AuditRepo},
+        book::{Book, BookRepo},
+        session::SessionRepo,
+        webhook::WebhookRepo,
+    },
+    services::{auth::AuthState, webhook},
+};
+
+use events::BookEvents;
+
+pub mod batch;
+pub mod events;
+pub mod grpc;
 pub mod handler;
+pub mod history;
+pub mod precondition;
+pub mod sessions;
+pub mod stats;
 pub mod test;
+pub mod transfer;
+pub mod trash;
 
 #[derive(Clone)]
 pub struct BookState {
     pub repo: Arc<dyn BookRepo>,
+    pub audit: Arc<dyn AuditRepo>,
+    pub events: BookEvents,
+    pub sessions: Arc<dyn SessionRepo>,
+    pub webhooks: Arc<dyn WebhookRepo>,
+    pub auth: AuthState,
+}
+
+impl BookState {
+    /// Records that `actor` took `book` from `before` to `after`, tells the
+    /// clients following the shelf and queues the webhook deliveries. The
+    /// change is already stored by then, so a failed audit write is logged
+    /// and the change still announced, rather than failing the request.
+    pub async fn changed(
+        &self,
+        actor: Uuid,
+        action: AuditAction,
+        before: Option<&Book>,
+        after: Option<&Book>,
+        request_id: Option<String>,
+    ) {
+        let entry = AuditEntry::new(actor, action, before, after, request_id);
+        if let Err(error) = self.audit.record(&entry).await {
+            tracing::error!(
+                "Failed to record {:?} of book {} in the audit log: {}",
+                action,
+                entry.book_id,
+                error
+            );
+        }
+        if let Some(book) = after.or(before) {
+            self.events.publish(action.into(), book);
+        }
+        webhook::enqueue(self.webhooks.as_ref(), action, before, after).await;
+    }
+}
+
+impl FromRef<BookState> for AuthState {
+    fn from_ref(state: &BookState) -> AuthState {
+        state.auth.clone()
+    }
 }
@@ -0,0 +1,53 @@
+//! Conditional requests on a single book. Its ETag is its version, so a
+//! client can skip fetching a book it already has and make sure it doesn't
+//! overwrite changes it hasn't seen.
+
+use axum::http::{HeaderMap, HeaderName, header};
+
+use crate::AppError;
+use crate::repos::book::{Book, version_conflict};
+
+pub fn etag(book: &Book) -> String {
+    format!("\"{}\"", book.version)
+}
+
+/// Entity tags listed in header `name`, `None` when it isn't sent. An
+/// unreadable value lists nothing and so matches nothing.
+fn listed(headers: &HeaderMap, name: HeaderName) -> Option<Vec<String>> {
+    let values = headers.get_all(name);
+    let mut values = values.iter().peekable();
+    values.peek()?;
+    Some(
+        values
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+    )
+}
+
+/// Fails with 412 unless `If-Match` is absent, `*` or lists the current
+/// ETag of `book`. Weak tags never match, as RFC 9110 asks of `If-Match`.
+/// `failure` opens the message.
+pub fn check_if_match(headers: &HeaderMap, book: &Book, failure: &str) -> Result<(), AppError> {
+    let Some(tags) = listed(headers, header::IF_MATCH) else {
+        return Ok(());
+    };
+    let current = etag(book);
+    if tags.iter().any(|tag| tag == "*" || *tag == current) {
+        Ok(())
+    } else {
+        Err(version_conflict(failure))
+    }
+}
+
+/// Whether `If-None-Match` is `*` or lists the current ETag of `book`,
+/// weak tags included.
+pub fn not_modified(headers: &HeaderMap, book: &Book) -> bool {
+    let current = etag(book);
+    listed(headers, header::IF_NONE_MATCH).is_some_and(|tags| {
+        tags.iter()
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == current)
+    })
+}
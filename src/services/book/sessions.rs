@@ -0,0 +1,300 @@
+//! Reading sessions, each sitting with a book from one page to another. The
+//! latest session sets how far the book is read, and together they chart
+//! the progress over time.
+
+use axum::{
+    Json,
+    extract::{Path, State, rejection::JsonRejection},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::Book;
+use crate::repos::session::{ReadingSession, SessionFilter};
+use crate::services::audit::request_id;
+use crate::services::auth::scope::{Authorized, BooksRead, BooksWrite};
+
+use super::BookState;
+use super::handler::update_finished;
+use super::precondition::{check_if_match, etag};
+
+/// How far ahead of the server a client's clock may run.
+const MAX_CLOCK_SKEW: Duration = Duration::minutes(1);
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionParams {
+    /// Page the session started on, 0 before the first page
+    start_page: i32,
+    /// Page the session ended on, at most the page count
+    end_page: i32,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+}
+
+impl SessionParams {
+    fn validate(&self, page_count: i32) -> Result<(), AppError> {
+        let fail = |message: &str| {
+            let message = format!("Gagal menambahkan sesi membaca. {}", message);
+            Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message))
+        };
+
+        if self.start_page < 0 {
+            return fail("startPage tidak boleh negatif");
+        }
+        if self.start_page > self.end_page {
+            return fail("startPage tidak boleh lebih besar dari endPage");
+        }
+        if self.end_page > page_count {
+            return fail("endPage tidak boleh lebih besar dari pageCount");
+        }
+        if self.started_at >= self.ended_at {
+            return fail("startedAt harus sebelum endedAt");
+        }
+        if self.ended_at > Utc::now() + MAX_CLOCK_SKEW {
+            return fail("endedAt tidak boleh di masa depan");
+        }
+        Ok(())
+    }
+}
+
+/// Records a session and moves the book to the page the latest session
+/// ended on, marking it as being read. A session ending before the latest
+/// one leaves the book as it is. `If-Match` works as for
+/// [`update_book`](super::handler::update_book).
+#[utoipa::path(
+    post,
+    path = "/books/{id}/sessions",
+    request_body = SessionParams,
+    responses(
+        (status = 201, description = "Sesi membaca berhasil ditambahkan"),
+        (status = 400, description = "Gagal menambahkan sesi membaca"),
+        (status = 404, description = "Gagal menambahkan sesi membaca. Id tidak ditemukan"),
+        (status = 412, description = "The book changed since the ETag in If-Match"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+    ),
+    params(
+        ("id" = String, Path, description = "ID of the book read"),
+        ("If-Match" = Option<String>, Header, description = "ETag the session is based on"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
+    )
+)]
+pub async fn create_session(
+    State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
+    Path(id): Path<String>,
+    request_headers: HeaderMap,
+    params: Result<Json<SessionParams>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || {
+        let message = "Gagal menambahkan sesi membaca. Id tidak ditemukan".to_string();
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let owner = claims.user_id()?;
+    let Json(params) = params.map_err(|rejection| {
+        let message = format!("Gagal menambahkan sesi membaca. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+
+    let before = state
+        .repo
+        .get_book_by_id(owner, book_id)
+        .await?
+        .ok_or_else(not_found)?;
+    check_if_match(&request_headers, &before, "Gagal menambahkan sesi membaca")?;
+    params.validate(before.page_count)?;
+
+    let session = ReadingSession {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        book_id,
+        start_page: params.start_page,
+        end_page: params.end_page,
+        started_at: params.started_at,
+        ended_at: params.ended_at,
+        created_at: Utc::now(),
+    };
+    state.sessions.record_session(&session).await?;
+
+    let filter = SessionFilter {
+        book_id: Some(book_id),
+        ..SessionFilter::default()
+    };
+    let sessions = state.sessions.get_sessions(owner, &filter).await?;
+    let mut book = before.clone();
+    if let Some(latest) = sessions.last() {
+        book.read_page = latest.end_page;
+        book.reading = true;
+        book.updated_at = Utc::now();
+        update_finished(&mut book);
+        if book.finished && !before.finished {
+            // Finished when the session ended, not when it was sent.
+            book.finished_at = Some(latest.ended_at);
+        }
+    }
+    if book.read_page != before.read_page
+        || book.reading != before.reading
+        || book.finished != before.finished
+    {
+        // The session only counts along with the move it makes, a retry
+        // after a 412 would otherwise record it twice.
+        book.version = match state.repo.update_book(&book).await {
+            Ok(version) => version,
+            Err(error) => {
+                state.sessions.delete_session(owner, session.id).await?;
+                return Err(error);
+            }
+        };
+        state
+            .changed(
+                owner,
+                AuditAction::Update,
+                Some(&before),
+                Some(&book),
+                request_id(&request_headers),
+            )
+            .await;
+    }
+
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            "application/json; charset=utf-8".to_string(),
+        ),
+        (header::ETAG, etag(&book)),
+    ];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Sesi membaca berhasil ditambahkan",
+        "data": {
+            "sessionId": session.id,
+            "readPage": book.read_page,
+            "reading": book.reading,
+            "finished": book.finished
+        }
+    }));
+
+    Ok((StatusCode::CREATED, headers, body))
+}
+
+/// Where a session left the book.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressPoint {
+    /// When the session ended
+    at: DateTime<Utc>,
+    /// The page it ended on
+    page: i32,
+    pages_read: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BookProgress {
+    book_id: Uuid,
+    page_count: i32,
+    read_page: i32,
+    finished: bool,
+    /// `readPage` as a percentage of `pageCount`
+    percent: f64,
+    sessions: usize,
+    /// Pages read over every session, counting pages read again
+    pages_read: i64,
+    seconds_read: i64,
+    /// One point per session, in the order they ended
+    series: Vec<ProgressPoint>,
+}
+
+impl BookProgress {
+    fn new(book: &Book, sessions: &[ReadingSession]) -> Self {
+        BookProgress {
+            book_id: book.id,
+            page_count: book.page_count,
+            read_page: book.read_page,
+            finished: book.finished,
+            percent: (book.read_percent() * 10.0).round() / 10.0,
+            sessions: sessions.len(),
+            pages_read: sessions
+                .iter()
+                .map(|session| i64::from(session.pages_read()))
+                .sum(),
+            seconds_read: sessions
+                .iter()
+                .map(|session| (session.ended_at - session.started_at).num_seconds())
+                .sum(),
+            series: sessions
+                .iter()
+                .map(|session| ProgressPoint {
+                    at: session.ended_at,
+                    page: session.end_page,
+                    pages_read: session.pages_read(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// How far a book of the caller is read, with the page reached after each
+/// recorded session.
+#[utoipa::path(
+    get,
+    path = "/books/{id}/progress",
+    params(
+        ("id" = String, Path, description = "ID of the book"),
+    ),
+    responses(
+        (status = 200, description = "Progress of the book", body = BookProgress),
+        (status = 404, description = "Buku tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_book_progress(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || {
+        let message = "Buku tidak ditemukan".to_string();
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    };
+    let book_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let owner = claims.user_id()?;
+
+    let book = state
+        .repo
+        .get_book_by_id(owner, book_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let filter = SessionFilter {
+        book_id: Some(book_id),
+        ..SessionFilter::default()
+    };
+    let sessions = state.sessions.get_sessions(owner, &filter).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "progress": BookProgress::new(&book, &sessions)
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
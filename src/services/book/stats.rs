@@ -0,0 +1,78 @@
+//! Aggregates over the caller's whole shelf.
+
+use axum::{
+    Json,
+    extract::{Query, State, rejection::QueryRejection},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::AppError;
+use crate::repos::book::stats::{DEFAULT_TOP_LIMIT, MAX_TOP_LIMIT, StatsPeriod, StatsRequest};
+use crate::services::auth::scope::{Authorized, BooksRead};
+
+use super::BookState;
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// Grouping of `periods`, `month` or `year`, defaults to `month`
+    period: Option<StatsPeriod>,
+    /// Authors and publishers listed, between 1 and 50, defaults to 5
+    top: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Totals, activity per period, average completion time and top authors and publishers"),
+        (status = 400, description = "Invalid period or top"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_shelf_stats(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    query: Result<Query<StatsQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal menampilkan statistik. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+    let request = StatsRequest {
+        period: query.period.unwrap_or_default(),
+        top: query.top.unwrap_or(DEFAULT_TOP_LIMIT),
+    };
+    if request.top == 0 || request.top > MAX_TOP_LIMIT {
+        let message = format!(
+            "Gagal menampilkan statistik. top harus antara 1 dan {}",
+            MAX_TOP_LIMIT
+        );
+        return Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message));
+    }
+
+    let mut stats = state.repo.get_stats(owner, &request).await?;
+    let pages = state.sessions.get_pages_read(owner, request.period).await?;
+    stats.add_pages_read(pages);
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "stats": stats
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod book_batch {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::{
+            auth::scope::Scope,
+            book::test::{
+                TEST_USER, bearer_with, build_batch_request, build_create_book_request,
+                build_get_book_by_id_request, build_get_books_request, get_ready_service,
+                new_book_dummy, update_book_dummy,
+            },
+        },
+    };
+
+    async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn create_book(app: &mut Router) -> String {
+        let (_, body) = call(app, build_create_book_request(new_book_dummy())).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    async fn book_count(app: &mut Router) -> u64 {
+        let (_, body) = call(app, build_get_books_request()).await;
+        body["meta"]["total"].as_u64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn batch_should_apply_every_operation() {
+        let mut app = app(Config::ephemeral()).await;
+        let updated = create_book(&mut app).await;
+        let deleted = create_book(&mut app).await;
+        let payload = json!({
+            "operations": [
+                { "op": "create", "book": new_book_dummy() },
+                { "op": "update", "id": updated, "book": update_book_dummy() },
+                { "op": "delete", "id": deleted },
+            ]
+        });
+
+        let (status, body) = call(&mut app, build_batch_request(payload)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Batch berhasil diproses");
+        assert_eq!(body["data"]["succeeded"], 3);
+        assert_eq!(body["data"]["failed"], 0);
+        let results = body["data"]["results"].as_array().unwrap();
+        assert_eq!(results[0]["message"], "Buku berhasil ditambahkan");
+        assert_eq!(results[1]["bookId"], updated.as_str());
+        assert_eq!(results[2]["message"], "Buku berhasil dihapus");
+        let (_, body) = call(&mut app, build_get_book_by_id_request(&updated)).await;
+        assert_eq!(body["data"]["book"]["name"], "Buku Revisi");
+        assert_eq!(book_count(&mut app).await, 2);
+    }
+
+    #[tokio::test]
+    async fn failing_operation_should_roll_back_the_batch() {
+        let mut app = app(Config::ephemeral()).await;
+        let deleted = create_book(&mut app).await;
+        let payload = json!({
+            "operations": [
+                { "op": "create", "book": new_book_dummy() },
+                { "op": "delete", "id": deleted },
+                { "op": "delete", "id": Uuid::new_v4() },
+            ]
+        });
+
+        let (status, body) = call(&mut app, build_batch_request(payload)).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(
+            body["message"],
+            "Batch dibatalkan. Buku gagal dihapus. Id tidak ditemukan"
+        );
+        assert_eq!(body["data"]["index"], 2);
+        let (status, _) = call(&mut app, build_get_book_by_id_request(&deleted)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(book_count(&mut app).await, 1);
+    }
+
+    #[tokio::test]
+    async fn continue_on_error_should_keep_the_successful_operations() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut invalid = new_book_dummy();
+        invalid["readPage"] = json!(500);
+        let payload = json!({
+            "continueOnError": true,
+            "operations": [
+                { "op": "create", "book": invalid },
+                { "op": "create", "book": new_book_dummy() },
+                { "op": "update", "id": "not-an-id", "book": update_book_dummy() },
+            ]
+        });
+
+        let (status, body) = call(&mut app, build_batch_request(payload)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["succeeded"], 1);
+        assert_eq!(body["data"]["failed"], 2);
+        let results = body["data"]["results"].as_array().unwrap();
+        assert_eq!(results[0]["status"], "fail");
+        assert_eq!(
+            results[0]["message"],
+            "Gagal menambahkan buku. readPage tidak boleh lebih besar dari pageCount"
+        );
+        assert!(results[0].get("bookId").is_none());
+        assert_eq!(results[1]["status"], "success");
+        assert_eq!(
+            results[2]["message"],
+            "Gagal memperbarui buku. Id tidak ditemukan"
+        );
+        assert_eq!(book_count(&mut app).await, 1);
+    }
+
+    #[tokio::test]
+    async fn empty_batch_should_be_400() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = build_batch_request(json!({ "operations": [] }));
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["message"],
+            "Gagal memproses batch. Jumlah operasi harus antara 1 dan 100"
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_batch_should_be_400() {
+        let mut app = app(Config::ephemeral()).await;
+        let operations: Vec<Value> = (0..101)
+            .map(|_| json!({ "op": "create", "book": new_book_dummy() }))
+            .collect();
+
+        let request = build_batch_request(json!({ "operations": operations }));
+        let (status, _) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(book_count(&mut app).await, 0);
+    }
+
+    #[tokio::test]
+    async fn deletes_should_require_the_delete_scope() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        let mut request = build_batch_request(json!({
+            "operations": [{ "op": "delete", "id": id }]
+        }));
+        let writer = bearer_with(TEST_USER, vec![Scope::BooksRead, Scope::BooksWrite]);
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, writer.parse().unwrap());
+
+        let (status, _) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -7,6 +7,7 @@ mod delete_book_with_correct_id {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_delete_book_request, get_ready_service, new_book_dummy,
         },
@@ -14,7 +15,7 @@ mod delete_book_with_correct_id {
 
     #[tokio::test]
     async fn status_should_be_200() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -30,7 +31,7 @@ mod delete_book_with_correct_id {
 
     #[tokio::test]
     async fn response_header_should_be_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -47,7 +48,7 @@ mod delete_book_with_correct_id {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -65,7 +66,7 @@ mod delete_book_with_correct_id {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -85,7 +86,7 @@ mod delete_book_with_correct_id {
 
     #[tokio::test]
     async fn when_get_detail_books_the_book_should_not_found() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -112,12 +113,13 @@ mod delete_book_with_incorrect_id {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{build_delete_book_request, get_ready_service},
     };
 
     #[tokio::test]
     async fn status_should_be_404() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_delete_book_request("incorrect-id");
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -126,7 +128,7 @@ mod delete_book_with_incorrect_id {
 
     #[tokio::test]
     async fn response_header_should_be_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_delete_book_request("xxxxxx");
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -136,7 +138,7 @@ mod delete_book_with_incorrect_id {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_delete_book_request("xxxxxx");
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -147,7 +149,7 @@ mod delete_book_with_incorrect_id {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_delete_book_request("xxxxxx");
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
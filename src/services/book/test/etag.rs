@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod conditional_requests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{HeaderName, Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            build_create_book_request, build_delete_book_request, build_get_book_by_id_request,
+            build_patch_book_request, build_update_book_request, get_ready_service, new_book_dummy,
+            update_book_dummy,
+        },
+    };
+
+    /// Status, ETag and JSON body, `Null` for an empty one.
+    async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Option<String>, Value) {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let status = response.status();
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap()
+        };
+        (status, etag, body)
+    }
+
+    fn with(mut request: Request<Body>, name: HeaderName, value: &str) -> Request<Body> {
+        request.headers_mut().insert(name, value.parse().unwrap());
+        request
+    }
+
+    async fn app_with_book() -> (Router, String) {
+        let mut app = app(Config::ephemeral()).await;
+        let (_, _, body) = call(&mut app, build_create_book_request(new_book_dummy())).await;
+        let id = body["data"]["bookId"].as_str().unwrap().to_string();
+        (app, id)
+    }
+
+    #[tokio::test]
+    async fn get_should_send_the_version_as_etag() {
+        let (mut app, id) = app_with_book().await;
+
+        let (status, etag, body) = call(&mut app, build_get_book_by_id_request(&id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag.as_deref(), Some("\"1\""));
+        assert_eq!(body["data"]["book"]["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn matching_if_none_match_should_be_not_modified() {
+        let (mut app, id) = app_with_book().await;
+
+        for tags in ["\"1\"", "W/\"1\"", "\"7\", \"1\"", "*"] {
+            let request = with(
+                build_get_book_by_id_request(&id),
+                header::IF_NONE_MATCH,
+                tags,
+            );
+            let (status, etag, body) = call(&mut app, request).await;
+
+            assert_eq!(status, StatusCode::NOT_MODIFIED, "{}", tags);
+            assert_eq!(etag.as_deref(), Some("\"1\""));
+            assert_eq!(body, Value::Null);
+        }
+
+        let request = with(
+            build_get_book_by_id_request(&id),
+            header::IF_NONE_MATCH,
+            "\"2\"",
+        );
+        let (status, _, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["book"]["name"], "Buku A");
+    }
+
+    #[tokio::test]
+    async fn writes_should_move_the_etag_on() {
+        let (mut app, id) = app_with_book().await;
+
+        let (status, etag, _) = call(
+            &mut app,
+            build_update_book_request(&id, update_book_dummy()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag.as_deref(), Some("\"2\""));
+
+        let patch = json!({ "readPage": 10 });
+        let (status, etag, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag.as_deref(), Some("\"3\""));
+        assert_eq!(body["data"]["book"]["version"], 3);
+
+        let (_, etag, _) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(etag.as_deref(), Some("\"3\""));
+    }
+
+    #[tokio::test]
+    async fn put_with_stale_if_match_should_be_precondition_failed() {
+        let (mut app, id) = app_with_book().await;
+        let request = with(
+            build_update_book_request(&id, update_book_dummy()),
+            header::IF_MATCH,
+            "\"1\"",
+        );
+        let (status, _, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let mut book = update_book_dummy();
+        book["name"] = json!("Buku Usang");
+        let request = with(
+            build_update_book_request(&id, book),
+            header::IF_MATCH,
+            "\"1\"",
+        );
+        let (status, _, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(
+            body["message"],
+            "Gagal memperbarui buku. Versi buku sudah berubah"
+        );
+        let (_, etag, body) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(etag.as_deref(), Some("\"2\""));
+        assert_ne!(body["data"]["book"]["name"], "Buku Usang");
+    }
+
+    #[tokio::test]
+    async fn patch_should_need_a_strong_matching_etag() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "readPage": 10 });
+        let request = with(
+            build_patch_book_request(&id, patch),
+            header::IF_MATCH,
+            "W/\"1\"",
+        );
+        let (status, _, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+        let patch = json!({ "readPage": 10 });
+        let request = with(
+            build_patch_book_request(&id, patch),
+            header::IF_MATCH,
+            "\"5\", \"1\"",
+        );
+        let (status, etag, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(etag.as_deref(), Some("\"2\""));
+        assert_eq!(body["data"]["book"]["readPage"], 10);
+    }
+
+    #[tokio::test]
+    async fn delete_with_stale_if_match_should_keep_the_book() {
+        let (mut app, id) = app_with_book().await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 10 })),
+        )
+        .await;
+
+        let request = with(build_delete_book_request(&id), header::IF_MATCH, "\"1\"");
+        let (status, _, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(
+            body["message"],
+            "Buku gagal dihapus. Versi buku sudah berubah"
+        );
+        let (status, _, _) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let request = with(build_delete_book_request(&id), header::IF_MATCH, "\"2\"");
+        let (status, _, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Buku berhasil dihapus");
+    }
+
+    #[tokio::test]
+    async fn if_match_on_missing_book_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = Uuid::new_v4().to_string();
+
+        let request = with(
+            build_update_book_request(&id, update_book_dummy()),
+            header::IF_MATCH,
+            "*",
+        );
+        let (status, _, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let request = with(build_delete_book_request(&id), header::IF_MATCH, "*");
+        let (status, _, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["message"], "Buku gagal dihapus. Id tidak ditemukan");
+    }
+}
@@ -0,0 +1,282 @@
+#[cfg(test)]
+mod book_events {
+    use std::time::Duration;
+
+    use axum::{
+        Router,
+        body::{Body, Bytes},
+        http::{Request, StatusCode, header},
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tokio_tungstenite::tungstenite::{Message, client::IntoClientRequest};
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::{AppState, router},
+        config::Config,
+        services::{
+            auth::scope::Scope,
+            book::test::{
+                TEST_USER, as_user, bearer, bearer_with, build_book_events_request,
+                build_create_book_request, build_delete_book_request, build_patch_book_request,
+                get_ready_service, new_book_dummy,
+            },
+        },
+    };
+
+    async fn app() -> Router {
+        router(AppState::new(&Config::ephemeral()).await.unwrap())
+    }
+
+    async fn call(app: &mut Router, request: Request<Body>) -> Value {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn create_book(app: &mut Router) -> String {
+        let body = call(app, build_create_book_request(new_book_dummy())).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    /// Reads a Server-Sent Events body one event at a time.
+    struct EventReader {
+        body: Body,
+        buffer: String,
+    }
+
+    impl EventReader {
+        async fn open(app: &mut Router, request: Request<Body>) -> Self {
+            let response = get_ready_service(app).await.call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers()[header::CONTENT_TYPE],
+                "text/event-stream"
+            );
+            EventReader {
+                body: response.into_body(),
+                buffer: String::new(),
+            }
+        }
+
+        /// Id, name and data of the next event, skipping keep-alive comments.
+        async fn next(&mut self) -> (u64, String, Value) {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let block: String = self.buffer.drain(..end + 2).collect();
+                    let field = |name: &str| {
+                        block
+                            .lines()
+                            .find_map(|line| line.strip_prefix(name))
+                            .map(str::to_string)
+                    };
+                    let Some(data) = field("data: ") else {
+                        continue;
+                    };
+                    return (
+                        field("id: ").unwrap().parse().unwrap(),
+                        field("event: ").unwrap(),
+                        serde_json::from_str(&data).unwrap(),
+                    );
+                }
+                let frame = tokio::time::timeout(Duration::from_secs(5), self.body.frame())
+                    .await
+                    .expect("No event within 5 seconds")
+                    .unwrap()
+                    .unwrap();
+                let data: Bytes = frame.into_data().unwrap();
+                self.buffer.push_str(std::str::from_utf8(&data).unwrap());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn sse_should_stream_changes_to_own_books() {
+        let mut app = app().await;
+        let mut events = EventReader::open(&mut app, build_book_events_request(None)).await;
+
+        let foreign = as_user(build_create_book_request(new_book_dummy()), Uuid::new_v4());
+        call(&mut app, foreign).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 10 })),
+        )
+        .await;
+        call(&mut app, build_delete_book_request(&id)).await;
+
+        let (first, name, data) = events.next().await;
+        assert_eq!(first, 2);
+        assert_eq!(name, "created");
+        assert_eq!(data["kind"], "created");
+        assert_eq!(data["bookId"], id);
+        assert_eq!(data["book"]["name"], "Buku A");
+        let (second, name, data) = events.next().await;
+        assert_eq!(second, 3);
+        assert_eq!(name, "updated");
+        assert_eq!(data["book"]["readPage"], 10);
+        let (_, name, data) = events.next().await;
+        assert_eq!(name, "deleted");
+        assert_eq!(data["bookId"], id);
+    }
+
+    #[tokio::test]
+    async fn sse_should_resume_after_last_event_id() {
+        let mut app = app().await;
+        create_book(&mut app).await;
+        let second = create_book(&mut app).await;
+
+        let request = build_book_events_request(Some("1"));
+        let mut events = EventReader::open(&mut app, request).await;
+        let third = create_book(&mut app).await;
+
+        let (id, _, data) = events.next().await;
+        assert_eq!(id, 2);
+        assert_eq!(data["bookId"], second);
+        let (id, _, data) = events.next().await;
+        assert_eq!(id, 3);
+        assert_eq!(data["bookId"], third);
+    }
+
+    #[tokio::test]
+    async fn sse_should_need_the_books_read_scope() {
+        let mut app = app().await;
+        let mut request = build_book_events_request(None);
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            bearer_with(TEST_USER, vec![Scope::BooksWrite])
+                .parse()
+                .unwrap(),
+        );
+
+        let response = get_ready_service(&mut app)
+            .await
+            .call(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    type Socket = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    /// Serves `app` on a local port and opens a WebSocket to its event
+    /// stream as [`TEST_USER`].
+    async fn connect(app: Router) -> Socket {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut request = format!("ws://{}/books/events/ws", addr)
+            .into_client_request()
+            .unwrap();
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, bearer(TEST_USER).parse().unwrap());
+        let (socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        socket
+    }
+
+    async fn send(socket: &mut Socket, message: Value) {
+        socket
+            .send(Message::Text(message.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    async fn receive(socket: &mut Socket) -> Value {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+                .await
+                .expect("No message within 5 seconds")
+                .unwrap()
+                .unwrap();
+            if let Message::Text(text) = message {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn websocket_should_send_every_change_until_subscribed() {
+        let mut app = app().await;
+        let mut socket = connect(app.clone()).await;
+
+        let first = create_book(&mut app).await;
+        let message = receive(&mut socket).await;
+        assert_eq!(message["type"], "event");
+        assert_eq!(message["kind"], "created");
+        assert_eq!(message["bookId"], first);
+
+        send(
+            &mut socket,
+            json!({ "type": "subscribe", "kinds": ["deleted"] }),
+        )
+        .await;
+        let message = receive(&mut socket).await;
+        assert_eq!(message["type"], "subscribed");
+        assert_eq!(message["kinds"], json!(["deleted"]));
+
+        let second = create_book(&mut app).await;
+        call(&mut app, build_delete_book_request(&second)).await;
+        let message = receive(&mut socket).await;
+        assert_eq!(message["type"], "event");
+        assert_eq!(message["kind"], "deleted");
+        assert_eq!(message["bookId"], second);
+    }
+
+    #[tokio::test]
+    async fn websocket_should_filter_by_book_and_catch_up() {
+        let mut app = app().await;
+        let watched = create_book(&mut app).await;
+        create_book(&mut app).await;
+        call(
+            &mut app,
+            build_patch_book_request(&watched, json!({ "readPage": 10 })),
+        )
+        .await;
+        let mut socket = connect(app.clone()).await;
+
+        let subscription = json!({ "type": "subscribe", "bookIds": [watched], "lastEventId": 0 });
+        send(&mut socket, subscription).await;
+        assert_eq!(receive(&mut socket).await["type"], "subscribed");
+        let message = receive(&mut socket).await;
+        assert_eq!(message["id"], 1);
+        assert_eq!(message["kind"], "created");
+        let message = receive(&mut socket).await;
+        assert_eq!(message["id"], 3);
+        assert_eq!(message["kind"], "updated");
+
+        create_book(&mut app).await;
+        call(&mut app, build_delete_book_request(&watched)).await;
+        let message = receive(&mut socket).await;
+        assert_eq!(message["kind"], "deleted");
+        assert_eq!(message["bookId"], watched);
+    }
+
+    #[tokio::test]
+    async fn websocket_should_answer_invalid_messages_with_an_error() {
+        let mut socket = connect(app().await).await;
+
+        for message in [
+            json!({ "type": "unsubscribe" }),
+            json!({ "type": "subscribe", "kinds": ["burned"] }),
+        ] {
+            send(&mut socket, message).await;
+            let reply = receive(&mut socket).await;
+
+            assert_eq!(reply["type"], "error");
+            assert!(
+                reply["message"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("Invalid message.")
+            );
+        }
+    }
+}
@@ -7,6 +7,7 @@ mod get_all_books {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_get_books_request, get_ready_service, new_book_dummy,
         },
@@ -14,7 +15,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn response_code_should_be_200() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_get_books_request();
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -23,7 +24,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_get_books_request();
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -35,7 +36,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_get_books_request();
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -46,7 +47,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_get_books_request();
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -59,7 +60,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn response_body_data_object_should_have_an_array_books_and_contains_one_items() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -78,7 +79,7 @@ mod get_all_books {
 
     #[tokio::test]
     async fn the_books_should_have_contains_only_id_name_and_publisher_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -111,6 +112,7 @@ mod get_detail_books_with_correct_id {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_get_book_by_id_request, build_get_books_request,
             get_ready_service, new_book_dummy,
@@ -119,7 +121,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn responce_code_should_be_200() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -140,7 +142,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -164,7 +166,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -188,7 +190,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -213,7 +215,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn response_body_data_object_should_contain_book_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
@@ -237,7 +239,7 @@ mod get_detail_books_with_correct_id {
 
     #[tokio::test]
     async fn the_book_object_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
 
         let book_payload = new_book_dummy();
         let request = build_create_book_request(book_payload.clone());
@@ -279,6 +281,7 @@ mod get_detail_books_with_invalid_id {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_get_book_by_id_request, get_ready_service,
             new_book_dummy,
@@ -287,7 +290,7 @@ mod get_detail_books_with_invalid_id {
 
     #[tokio::test]
     async fn response_code_should_be_404() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -301,7 +304,7 @@ mod get_detail_books_with_invalid_id {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -318,7 +321,7 @@ mod get_detail_books_with_invalid_id {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -334,7 +337,7 @@ mod get_detail_books_with_invalid_id {
 
     #[tokio::test]
     async fn response_body_object_should_contain_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -350,3 +353,255 @@ mod get_detail_books_with_invalid_id {
         assert_eq!(body["message"], "Buku tidak ditemukan");
     }
 }
+
+#[cfg(test)]
+mod get_books_with_pagination {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            build_create_book_request, build_get_books_with_query_request, get_ready_service,
+            new_book_dummy,
+        },
+    };
+
+    async fn app_with_books(names: &[&str]) -> Router {
+        let mut app = app(Config::ephemeral()).await;
+        for name in names {
+            let mut book = new_book_dummy();
+            book["name"] = json!(name);
+            let request = build_create_book_request(book);
+            let ready_service = get_ready_service(&mut app).await;
+            let response = ready_service.call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        app
+    }
+
+    async fn get(app: &mut Router, query: &str) -> (StatusCode, Value) {
+        let request = build_get_books_with_query_request(query);
+        let ready_service = get_ready_service(app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn meta_should_report_page_limit_and_total() {
+        let mut app = app_with_books(&["Buku A", "Buku B", "Buku C"]).await;
+
+        let (status, body) = get(&mut app, "limit=2").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["books"].as_array().unwrap().len(), 2);
+        assert_eq!(body["meta"]["page"], 1);
+        assert_eq!(body["meta"]["limit"], 2);
+        assert_eq!(body["meta"]["total"], 3);
+        assert_eq!(body["meta"]["totalPages"], 2);
+        assert_eq!(body["meta"]["nextPage"], 2);
+    }
+
+    #[tokio::test]
+    async fn last_page_should_have_no_next_page() {
+        let mut app = app_with_books(&["Buku A", "Buku B", "Buku C"]).await;
+
+        let (_, body) = get(&mut app, "page=2&limit=2").await;
+
+        assert_eq!(body["data"]["books"].as_array().unwrap().len(), 1);
+        assert!(body["meta"]["nextPage"].is_null());
+    }
+
+    #[tokio::test]
+    async fn books_should_follow_sort_and_order() {
+        let mut app = app_with_books(&["beta", "Alpha", "charlie"]).await;
+
+        let (_, body) = get(&mut app, "sort=name&order=desc").await;
+
+        let names: Vec<&str> = body["data"]["books"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|book| book["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["charlie", "beta", "Alpha"]);
+    }
+
+    #[tokio::test]
+    async fn limit_out_of_range_should_be_400() {
+        let mut app = app_with_books(&[]).await;
+
+        for query in ["limit=0", "limit=101"] {
+            let (status, body) = get(&mut app, query).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["status"], "fail");
+            assert_eq!(
+                body["message"],
+                "Gagal menampilkan buku. limit harus antara 1 dan 100"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_sort_should_be_400_json() {
+        let mut app = app_with_books(&[]).await;
+
+        let request = build_get_books_with_query_request("sort=pages");
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "fail");
+    }
+}
+
+#[cfg(test)]
+mod get_books_with_rich_filters {
+    use axum::{Router, http::StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            build_create_book_request, build_get_books_with_query_request, get_ready_service,
+            new_book_dummy,
+        },
+    };
+
+    async fn app_with_books(books: Vec<Value>) -> Router {
+        let mut app = app(Config::ephemeral()).await;
+        for book in books {
+            let request = build_create_book_request(book);
+            let ready_service = get_ready_service(&mut app).await;
+            let response = ready_service.call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        app
+    }
+
+    fn book(name: &str, author: &str, year: i32, page_count: i32, read_page: i32) -> Value {
+        let mut book = new_book_dummy();
+        book["name"] = json!(name);
+        book["author"] = json!(author);
+        book["year"] = json!(year);
+        book["pageCount"] = json!(page_count);
+        book["readPage"] = json!(read_page);
+        book
+    }
+
+    async fn get(app: &mut Router, query: &str) -> (StatusCode, Value) {
+        let request = build_get_books_with_query_request(query);
+        let ready_service = get_ready_service(app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn names(body: &Value) -> Vec<&str> {
+        body["data"]["books"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|book| book["name"].as_str().unwrap())
+            .collect()
+    }
+
+    async fn shelf() -> Router {
+        app_with_books(vec![
+            book("Laskar Pelangi", "Andrea Hirata", 2005, 529, 529),
+            book("Bumi Manusia", "Pramoedya Ananta Toer", 1980, 535, 100),
+            book("Pulang", "Tere Liye", 2015, 400, 0),
+        ])
+        .await
+    }
+
+    #[tokio::test]
+    async fn books_should_be_filtered_by_author_and_year_range() {
+        let mut app = shelf().await;
+
+        let (status, body) = get(&mut app, "author=hirata").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(names(&body), ["Laskar Pelangi"]);
+
+        let (_, body) = get(&mut app, "yearFrom=2000&yearTo=2015&sort=year").await;
+        assert_eq!(names(&body), ["Laskar Pelangi", "Pulang"]);
+        assert_eq!(body["meta"]["total"], 2);
+    }
+
+    #[tokio::test]
+    async fn books_should_be_filtered_by_page_count_and_read_percent() {
+        let mut app = shelf().await;
+
+        let (_, body) = get(&mut app, "pageCountMin=500&readPercentMax=50").await;
+        assert_eq!(names(&body), ["Bumi Manusia"]);
+
+        let (_, body) = get(&mut app, "readPercentMin=100").await;
+        assert_eq!(names(&body), ["Laskar Pelangi"]);
+    }
+
+    #[tokio::test]
+    async fn books_should_be_filtered_by_timestamps() {
+        let mut app = shelf().await;
+
+        let (_, body) = get(&mut app, "insertedAfter=2000-01-01T00:00:00Z").await;
+        assert_eq!(body["meta"]["total"], 3);
+
+        let (_, body) = get(&mut app, "updatedSince=2999-01-01T00:00:00Z").await;
+        assert_eq!(body["meta"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn inverted_range_should_be_400() {
+        let mut app = shelf().await;
+
+        let (status, body) = get(&mut app, "yearFrom=2010&yearTo=2000").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(
+            body["message"],
+            "Gagal menampilkan buku. yearFrom tidak boleh lebih besar dari yearTo"
+        );
+    }
+
+    #[tokio::test]
+    async fn read_percent_out_of_range_should_be_400() {
+        let mut app = shelf().await;
+
+        let (status, body) = get(&mut app, "readPercentMin=120").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["message"],
+            "Gagal menampilkan buku. readPercent harus antara 0 dan 100"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_timestamp_should_be_400() {
+        let mut app = shelf().await;
+
+        let (status, body) = get(&mut app, "insertedAfter=yesterday").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+    }
+}
@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod book_service {
+    use tonic::{Code, Request};
+
+    use crate::{
+        app::AppState,
+        config::Config,
+        services::book::{
+            grpc::{
+                BookGrpcService,
+                proto::{
+                    BookInput, BookSort, CreateBookRequest, DeleteBookRequest, GetBookRequest,
+                    ListBooksRequest, UpdateBookRequest, book_service_server::BookService,
+                },
+            },
+            test::{TEST_USER, bearer},
+        },
+    };
+
+    /// Wraps `message` with the bearer token of [`TEST_USER`].
+    fn authorized<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", bearer(TEST_USER).parse().unwrap());
+        request
+    }
+
+    fn book_input() -> BookInput {
+        BookInput {
+            name: "Buku A".to_string(),
+            year: 2010,
+            author: "John Doe".to_string(),
+            summary: "Lorem ipsum dolor sit amet".to_string(),
+            publisher: "Dicoding Indonesia".to_string(),
+            page_count: 100,
+            read_page: 25,
+            reading: false,
+        }
+    }
+
+    async fn create(service: &BookGrpcService, book: BookInput) -> String {
+        service
+            .create_book(authorized(CreateBookRequest { book: Some(book) }))
+            .await
+            .unwrap()
+            .into_inner()
+            .book_id
+    }
+
+    #[tokio::test]
+    async fn created_book_should_be_retrievable() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        let id = create(&service, book_input()).await;
+
+        let response = service
+            .get_book(authorized(GetBookRequest { id: id.clone() }))
+            .await
+            .unwrap();
+        let book = response.into_inner().book.unwrap();
+        assert_eq!(book.id, id);
+        assert_eq!(book.name, "Buku A");
+        assert_eq!(book.page_count, 100);
+        assert!(!book.finished);
+    }
+
+    #[tokio::test]
+    async fn create_without_name_should_be_invalid_argument() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        let mut book = book_input();
+        book.name = String::new();
+
+        let status = service
+            .create_book(authorized(CreateBookRequest { book: Some(book) }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(
+            status.message(),
+            "Gagal menambahkan buku. Mohon isi nama buku"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_should_apply_filters() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        create(&service, book_input()).await;
+        let mut reading = book_input();
+        reading.name = "Buku B".to_string();
+        reading.reading = true;
+        create(&service, reading).await;
+
+        let response = service
+            .list_books(authorized(ListBooksRequest {
+                reading: Some(true),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let books = response.into_inner().books;
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].name, "Buku B");
+    }
+
+    #[tokio::test]
+    async fn list_should_page_and_report_total() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        for name in ["c", "a", "b"] {
+            let mut book = book_input();
+            book.name = name.to_string();
+            create(&service, book).await;
+        }
+
+        let response = service
+            .list_books(authorized(ListBooksRequest {
+                limit: 2,
+                sort: BookSort::Name.into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let names: Vec<&str> = response.books.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(response.total, 3);
+        assert_eq!(response.next_page, Some(2));
+    }
+
+    #[tokio::test]
+    async fn list_should_apply_rich_filters() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        for (name, year, read_page) in [("old", 1990, 100), ("new", 2020, 10)] {
+            let mut book = book_input();
+            book.name = name.to_string();
+            book.year = year;
+            book.read_page = read_page;
+            create(&service, book).await;
+        }
+
+        let response = service
+            .list_books(authorized(ListBooksRequest {
+                year_from: Some(2000),
+                read_percent_max: Some(50.0),
+                author: Some("doe".to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.total, 1);
+        assert_eq!(response.books[0].name, "new");
+    }
+
+    #[tokio::test]
+    async fn list_with_malformed_timestamp_should_be_invalid_argument() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+
+        let status = service
+            .list_books(authorized(ListBooksRequest {
+                inserted_after: Some("yesterday".to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn update_should_return_updated_book() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        let id = create(&service, book_input()).await;
+        let mut book = book_input();
+        book.name = "Buku Revisi".to_string();
+        book.read_page = 100;
+        book.reading = true;
+
+        let response = service
+            .update_book(authorized(UpdateBookRequest {
+                id,
+                book: Some(book),
+            }))
+            .await
+            .unwrap();
+        let book = response.into_inner().book.unwrap();
+        assert_eq!(book.name, "Buku Revisi");
+        assert!(book.finished);
+    }
+
+    #[tokio::test]
+    async fn get_with_invalid_id_should_be_not_found() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+
+        let status = service
+            .get_book(authorized(GetBookRequest {
+                id: "xxxxx".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn deleted_book_should_not_be_found() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        let id = create(&service, book_input()).await;
+
+        service
+            .delete_book(authorized(DeleteBookRequest { id: id.clone() }))
+            .await
+            .unwrap();
+        let status = service
+            .get_book(authorized(GetBookRequest { id }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn request_without_token_should_be_unauthenticated() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+
+        let status = service
+            .list_books(Request::new(ListBooksRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn other_users_book_should_be_not_found() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+        let id = create(&service, book_input()).await;
+
+        let mut request = Request::new(GetBookRequest { id });
+        request.metadata_mut().insert(
+            "authorization",
+            bearer(uuid::Uuid::from_u128(2)).parse().unwrap(),
+        );
+        let status = service.get_book(request).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+}
@@ -0,0 +1,236 @@
+#[cfg(test)]
+mod book_history {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        AppError,
+        app::{AppState, app, router},
+        config::Config,
+        repos::audit::{AuditEntry, AuditRepo},
+        services::book::test::{
+            TEST_USER, as_user, build_batch_request, build_create_book_request,
+            build_delete_book_request, build_get_book_by_id_request, build_get_history_request,
+            build_patch_book_request, build_restore_book_request, build_update_book_request,
+            get_ready_service, new_book_dummy, update_book_dummy,
+        },
+    };
+
+    /// Audit log that refuses every write.
+    struct BrokenAuditRepo;
+
+    #[async_trait]
+    impl AuditRepo for BrokenAuditRepo {
+        async fn record(&self, _entry: &AuditEntry) -> Result<Uuid, AppError> {
+            Err(AppError::DatabaseError)
+        }
+    }
+
+    async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn create_book(app: &mut Router) -> String {
+        let (_, body) = call(app, build_create_book_request(new_book_dummy())).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    async fn history(app: &mut Router, id: &str) -> Vec<Value> {
+        let (status, body) = call(app, build_get_history_request(id, "")).await;
+        assert_eq!(status, StatusCode::OK);
+        body["data"]["entries"].as_array().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn history_should_list_every_change_newest_first() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_update_book_request(&id, update_book_dummy()),
+        )
+        .await;
+        call(&mut app, build_delete_book_request(&id)).await;
+        call(&mut app, build_restore_book_request(&id)).await;
+
+        let (status, body) = call(&mut app, build_get_history_request(&id, "")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["meta"]["total"], 4);
+        let actions: Vec<&str> = body["data"]["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["action"].as_str().unwrap())
+            .collect();
+        assert_eq!(actions, vec!["restore", "delete", "update", "create"]);
+        for entry in body["data"]["entries"].as_array().unwrap() {
+            assert_eq!(entry["bookId"], id);
+            assert_eq!(entry["actorId"], TEST_USER.to_string());
+            assert_eq!(entry["ownerId"], TEST_USER.to_string());
+            assert!(entry["createdAt"].is_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn entries_should_hold_the_changed_fields() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 10 })),
+        )
+        .await;
+
+        let entries = history(&mut app, &id).await;
+
+        let patched = &entries[0]["changes"];
+        assert_eq!(patched["readPage"], json!({ "before": 25, "after": 10 }));
+        assert_eq!(patched["version"], json!({ "before": 1, "after": 2 }));
+        assert!(patched.get("name").is_none());
+        let created = &entries[1]["changes"];
+        assert_eq!(
+            created["name"],
+            json!({ "before": null, "after": "Buku A" })
+        );
+        assert_eq!(created["id"]["after"], id);
+    }
+
+    #[tokio::test]
+    async fn entries_should_carry_the_request_id() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut request = build_create_book_request(new_book_dummy());
+        request
+            .headers_mut()
+            .insert("x-request-id", "import-42".parse().unwrap());
+        let response = get_ready_service(&mut app)
+            .await
+            .call(request)
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["x-request-id"], "import-42");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        let id = body["data"]["bookId"].as_str().unwrap().to_string();
+
+        let response = get_ready_service(&mut app)
+            .await
+            .call(build_delete_book_request(&id))
+            .await
+            .unwrap();
+        let generated = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let entries = history(&mut app, &id).await;
+        assert_eq!(entries[0]["requestId"], generated);
+        assert_eq!(entries[1]["requestId"], "import-42");
+    }
+
+    #[tokio::test]
+    async fn batch_should_be_recorded_once_committed() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        let missing = Uuid::new_v4().to_string();
+        let payload = json!({
+            "operations": [
+                { "op": "update", "id": id, "book": update_book_dummy() },
+                { "op": "delete", "id": missing }
+            ]
+        });
+        call(&mut app, build_batch_request(payload)).await;
+        assert_eq!(history(&mut app, &id).await.len(), 1);
+
+        let payload = json!({
+            "operations": [
+                { "op": "update", "id": id, "book": update_book_dummy() },
+                { "op": "delete", "id": id }
+            ]
+        });
+        let (status, _) = call(&mut app, build_batch_request(payload)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let entries = history(&mut app, &id).await;
+        let actions: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry["action"].as_str().unwrap())
+            .collect();
+        assert_eq!(actions, vec!["delete", "update", "create"]);
+    }
+
+    #[tokio::test]
+    async fn history_of_unknown_or_foreign_book_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        let stranger = as_user(build_get_history_request(&id, ""), Uuid::new_v4());
+
+        let (status, body) = call(&mut app, stranger).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["message"], "Riwayat buku tidak ditemukan");
+
+        for id in [Uuid::new_v4().to_string(), "xxxxx".to_string()] {
+            let (status, _) = call(&mut app, build_get_history_request(&id, "")).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn history_should_be_paged() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        for page in 1..=4 {
+            call(
+                &mut app,
+                build_patch_book_request(&id, json!({ "readPage": page })),
+            )
+            .await;
+        }
+
+        let (status, body) = call(&mut app, build_get_history_request(&id, "page=2&limit=2")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["meta"]["total"], 5);
+        assert_eq!(body["meta"]["nextPage"], 3);
+        let entries = body["data"]["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["changes"]["readPage"]["after"], 2);
+        assert_eq!(entries[1]["changes"]["readPage"]["after"], 1);
+
+        let (status, _) = call(&mut app, build_get_history_request(&id, "limit=0")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn change_should_go_through_when_the_audit_log_cannot_be_written() {
+        let mut state = AppState::new(&Config::ephemeral()).await.unwrap();
+        state.book.audit = Arc::new(BrokenAuditRepo);
+        let events = state.book.events.clone();
+        let mut app = router(state);
+
+        let (status, body) = call(&mut app, build_create_book_request(new_book_dummy())).await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        let id = body["data"]["bookId"].as_str().unwrap();
+        let (status, body) = call(&mut app, build_get_book_by_id_request(id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["book"]["name"], new_book_dummy()["name"]);
+        let (published, _) = events.subscribe(Some(0));
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].book_id.to_string(), id);
+    }
+}
@@ -6,11 +6,71 @@ use axum::{
 
 use serde_json::{Value, json};
 use tower::ServiceExt;
+use uuid::Uuid;
 
+use crate::{
+    config::Config,
+    repos::user::Role,
+    services::auth::{Claims, keys::Keys, scope::Scope},
+};
+
+pub mod batch;
 pub mod del;
+pub mod etag;
+pub mod events;
 pub mod get;
+pub mod grpc;
+pub mod history;
+pub mod owner;
+pub mod patch;
 pub mod post;
 pub mod put;
+pub mod scope;
+pub mod search;
+pub mod sessions;
+pub mod stats;
+pub mod transfer;
+pub mod trash;
+
+/// User the request builders act as.
+#[allow(dead_code)]
+pub const TEST_USER: Uuid = Uuid::from_u128(1);
+
+/// `Authorization` value for an editor `user`, signed with the secret of
+/// [`Config::ephemeral`].
+#[allow(dead_code)]
+pub fn bearer(user: Uuid) -> String {
+    bearer_as(user, Role::Editor)
+}
+
+/// `Authorization` value for `user` holding the scopes of `role`.
+#[allow(dead_code)]
+pub fn bearer_as(user: Uuid, role: Role) -> String {
+    bearer_with(user, role.scopes())
+}
+
+/// `Authorization` value for `user` holding exactly `scopes`.
+#[allow(dead_code)]
+pub fn bearer_with(user: Uuid, scopes: Vec<Scope>) -> String {
+    let secret = Config::ephemeral().auth.jwt_secret.unwrap();
+    let claims = Claims {
+        sub: user.to_string(),
+        jti: Uuid::new_v4(),
+        exp: 10000000000,
+        scopes,
+    };
+    let token = Keys::from_secret(secret.as_bytes()).sign(&claims).unwrap();
+    format!("Bearer {}", token)
+}
+
+/// Sends `request` as `user` instead of [`TEST_USER`].
+#[allow(dead_code)]
+pub fn as_user(mut request: Request<Body>, user: Uuid) -> Request<Body> {
+    request
+        .headers_mut()
+        .insert(header::AUTHORIZATION, bearer(user).parse().unwrap());
+    request
+}
 
 #[allow(dead_code)]
 pub fn new_book_dummy() -> Value {
@@ -48,11 +108,12 @@ async fn get_ready_service(app: &mut Router) -> &mut Router {
 }
 
 #[allow(dead_code)]
-fn build_create_book_request(payload: Value) -> Request<Body> {
+pub fn build_create_book_request(payload: Value) -> Request<Body> {
     Request::builder()
         .method(Method::POST)
         .uri("/books")
         .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
         .body(Body::from(payload.to_string()))
         .unwrap()
 }
@@ -63,6 +124,29 @@ fn build_get_books_request() -> Request<Body> {
         .method(Method::GET)
         .uri("/books")
         .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_books_with_query_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books?{}", query))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_search_books_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books/search?{}", query))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
         .body(Body::empty())
         .unwrap()
 }
@@ -73,6 +157,7 @@ fn build_get_book_by_id_request(id: &str) -> Request<Body> {
         .method(Method::GET)
         .uri(format!("/books/{}", id))
         .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
         .body(Body::empty())
         .unwrap()
 }
@@ -83,16 +168,134 @@ fn build_update_book_request(id: &str, payload: Value) -> Request<Body> {
         .method(Method::PUT)
         .uri(format!("/books/{}", id))
         .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
         .body(Body::from(payload.to_string()))
         .unwrap()
 }
 
 #[allow(dead_code)]
-fn build_delete_book_request(id: &str) -> Request<Body> {
+pub fn build_delete_book_request(id: &str) -> Request<Body> {
     Request::builder()
         .method(Method::DELETE)
         .uri(format!("/books/{}", id))
         .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_import_books_request(content_type: &str, payload: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/books/import")
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_export_books_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books/export?{}", query))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_batch_request(payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/books/batch")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+pub fn build_patch_book_request(id: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::PATCH)
+        .uri(format!("/books/{}", id))
+        .header(header::CONTENT_TYPE, "application/merge-patch+json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_trash_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books/trash?{}", query))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_stats_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/stats?{}", query))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_restore_book_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/books/{}/restore", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_history_request(id: &str, query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books/{}/history?{}", id, query))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_book_events_request(last_event_id: Option<&str>) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri("/books/events")
+        .header(header::AUTHORIZATION, bearer(TEST_USER));
+    if let Some(id) = last_event_id {
+        request = request.header("last-event-id", id);
+    }
+    request.body(Body::empty()).unwrap()
+}
+
+#[allow(dead_code)]
+pub fn build_create_session_request(id: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/books/{}/sessions", id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_progress_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/books/{}/progress", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
         .body(Body::empty())
         .unwrap()
 }
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod book_ownership {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            as_user, build_create_book_request, build_delete_book_request,
+            build_get_book_by_id_request, build_get_books_request, build_update_book_request,
+            get_ready_service, new_book_dummy, update_book_dummy,
+        },
+    };
+
+    const OTHER_USER: Uuid = Uuid::from_u128(2);
+
+    async fn create_book(app: &mut Router) -> String {
+        let request = build_create_book_request(new_book_dummy());
+        let ready_service = get_ready_service(app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn request_without_token_should_be_401() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut request = build_get_books_request();
+        request.headers_mut().remove(header::AUTHORIZATION);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn other_users_books_should_not_be_listed() {
+        let mut app = app(Config::ephemeral()).await;
+        create_book(&mut app).await;
+
+        let request = as_user(build_get_books_request(), OTHER_USER);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"]["books"].as_array().unwrap().len(), 0);
+        assert_eq!(body["meta"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn other_users_book_should_be_404_on_get() {
+        let mut app = app(Config::ephemeral()).await;
+        let book_id = create_book(&mut app).await;
+
+        let request = as_user(build_get_book_by_id_request(&book_id), OTHER_USER);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn other_users_book_should_be_404_on_update() {
+        let mut app = app(Config::ephemeral()).await;
+        let book_id = create_book(&mut app).await;
+
+        let request = as_user(
+            build_update_book_request(&book_id, update_book_dummy()),
+            OTHER_USER,
+        );
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let request = build_get_book_by_id_request(&book_id);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"]["book"]["name"], new_book_dummy()["name"]);
+    }
+
+    #[tokio::test]
+    async fn other_users_book_should_be_404_on_delete() {
+        let mut app = app(Config::ephemeral()).await;
+        let book_id = create_book(&mut app).await;
+
+        let request = as_user(build_delete_book_request(&book_id), OTHER_USER);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let request = build_get_book_by_id_request(&book_id);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod patch_book {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            as_user, build_create_book_request, build_get_book_by_id_request,
+            build_patch_book_request, get_ready_service, new_book_dummy,
+        },
+    };
+
+    async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    /// Creates a book being read, returning the app and its id.
+    async fn app_with_book() -> (Router, String) {
+        let mut app = app(Config::ephemeral()).await;
+        let mut book = new_book_dummy();
+        book["reading"] = json!(true);
+        let (_, body) = call(&mut app, build_create_book_request(book)).await;
+        let id = body["data"]["bookId"].as_str().unwrap().to_string();
+        (app, id)
+    }
+
+    async fn stored(app: &mut Router, id: &str) -> Value {
+        let (_, body) = call(app, build_get_book_by_id_request(id)).await;
+        body["data"]["book"].clone()
+    }
+
+    #[tokio::test]
+    async fn patch_should_only_change_present_members() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "summary": "", "readPage": 0, "reading": false });
+        let (status, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Buku berhasil diperbarui");
+        assert_eq!(body["data"]["book"]["readPage"], 0);
+        let book = stored(&mut app, &id).await;
+        assert_eq!(book["summary"], "");
+        assert_eq!(book["readPage"], 0);
+        assert_eq!(book["reading"], false);
+        assert_eq!(book["name"], "Buku A");
+        assert_eq!(book["author"], "John Doe");
+        assert_eq!(book["pageCount"], 100);
+    }
+
+    #[tokio::test]
+    async fn patch_should_recompute_finished() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "readPage": 100 });
+        let (status, _) = call(&mut app, build_patch_book_request(&id, patch)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stored(&mut app, &id).await["finished"], true);
+
+        let patch = json!({ "reading": false });
+        call(&mut app, build_patch_book_request(&id, patch)).await;
+        assert_eq!(stored(&mut app, &id).await["finished"], false);
+    }
+
+    #[tokio::test]
+    async fn empty_patch_should_keep_the_book() {
+        let (mut app, id) = app_with_book().await;
+        let before = stored(&mut app, &id).await;
+
+        let (status, _) = call(&mut app, build_patch_book_request(&id, json!({}))).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let after = stored(&mut app, &id).await;
+        assert_eq!(after["name"], before["name"]);
+        assert_eq!(after["readPage"], before["readPage"]);
+    }
+
+    #[tokio::test]
+    async fn null_members_should_be_rejected() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "summary": null });
+        let (status, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+        assert_eq!(
+            body["message"],
+            "Gagal memperbarui buku. summary tidak boleh null"
+        );
+        assert_eq!(
+            stored(&mut app, &id).await["summary"],
+            "Lorem ipsum dolor sit amet"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_members_should_be_rejected() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "finished": true });
+        let (status, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Gagal memperbarui buku. ")
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_result_should_be_rejected() {
+        let (mut app, id) = app_with_book().await;
+
+        let patch = json!({ "pageCount": 10 });
+        let (status, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["message"],
+            "Gagal memperbarui buku. readPage tidak boleh lebih besar dari pageCount"
+        );
+        let patch = json!({ "name": "" });
+        let (_, body) = call(&mut app, build_patch_book_request(&id, patch)).await;
+        assert_eq!(
+            body["message"],
+            "Gagal memperbarui buku. Mohon isi nama buku"
+        );
+        assert_eq!(stored(&mut app, &id).await["pageCount"], 100);
+    }
+
+    #[tokio::test]
+    async fn missing_or_foreign_book_should_be_404() {
+        let (mut app, id) = app_with_book().await;
+
+        let request = build_patch_book_request(&Uuid::new_v4().to_string(), json!({}));
+        let (status, body) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body["message"],
+            "Gagal memperbarui buku. Id tidak ditemukan"
+        );
+
+        let request = as_user(build_patch_book_request(&id, json!({})), Uuid::from_u128(2));
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
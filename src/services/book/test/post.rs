@@ -7,12 +7,13 @@ mod add_book_with_complete_data {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{build_create_book_request, get_ready_service, new_book_dummy},
     };
 
     #[tokio::test]
     async fn status_should_be_201() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -21,7 +22,7 @@ mod add_book_with_complete_data {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -33,7 +34,7 @@ mod add_book_with_complete_data {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -44,7 +45,7 @@ mod add_book_with_complete_data {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -66,6 +67,7 @@ mod add_book_without_name {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{build_create_book_request, get_ready_service, new_book_dummy},
     };
 
@@ -80,7 +82,7 @@ mod add_book_without_name {
 
     #[tokio::test]
     async fn status_should_be_400() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_no_name());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -89,7 +91,7 @@ mod add_book_without_name {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_no_name());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -101,7 +103,7 @@ mod add_book_without_name {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_no_name());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -112,7 +114,7 @@ mod add_book_without_name {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_no_name());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -137,6 +139,7 @@ mod add_book_with_page_more_than_page_count {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{build_create_book_request, get_ready_service, new_book_dummy},
     };
 
@@ -152,7 +155,7 @@ mod add_book_with_page_more_than_page_count {
 
     #[tokio::test]
     async fn status_should_be_400() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_overflow_page());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -161,7 +164,7 @@ mod add_book_with_page_more_than_page_count {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_overflow_page());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -173,7 +176,7 @@ mod add_book_with_page_more_than_page_count {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_overflow_page());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -184,7 +187,7 @@ mod add_book_with_page_more_than_page_count {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_overflow_page());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -6,6 +6,7 @@ mod update_book_with_complete_data {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_get_book_by_id_request, build_update_book_request,
             get_ready_service, new_book_dummy, update_book_dummy,
@@ -14,7 +15,7 @@ mod update_book_with_complete_data {
 
     #[tokio::test]
     async fn status_code_should_be_200() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -32,7 +33,7 @@ mod update_book_with_complete_data {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -53,7 +54,7 @@ mod update_book_with_complete_data {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -73,7 +74,7 @@ mod update_book_with_complete_data {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -95,7 +96,7 @@ mod update_book_with_complete_data {
 
     #[tokio::test]
     async fn when_get_details_books_book_object_should_contain_updated_values() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -139,6 +140,7 @@ mod update_book_without_name {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_update_book_request, get_ready_service,
             new_book_dummy, update_book_dummy,
@@ -156,7 +158,7 @@ mod update_book_without_name {
 
     #[tokio::test]
     async fn status_code_should_be_400() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -174,7 +176,7 @@ mod update_book_without_name {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -195,7 +197,7 @@ mod update_book_without_name {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -215,7 +217,7 @@ mod update_book_without_name {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -248,6 +250,7 @@ mod update_book_with_page_read_more_than_page_count {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_update_book_request, get_ready_service,
             new_book_dummy, update_book_dummy,
@@ -265,7 +268,7 @@ mod update_book_with_page_read_more_than_page_count {
 
     #[tokio::test]
     async fn status_code_should_be_400() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -283,7 +286,7 @@ mod update_book_with_page_read_more_than_page_count {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -304,7 +307,7 @@ mod update_book_with_page_read_more_than_page_count {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -324,7 +327,7 @@ mod update_book_with_page_read_more_than_page_count {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -357,6 +360,7 @@ mod update_book_with_invalid_id {
 
     use crate::{
         app::app,
+        config::Config,
         services::book::test::{
             build_create_book_request, build_update_book_request, get_ready_service,
             new_book_dummy, update_book_dummy,
@@ -365,7 +369,7 @@ mod update_book_with_invalid_id {
 
     #[tokio::test]
     async fn status_code_should_be_404() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -379,7 +383,7 @@ mod update_book_with_invalid_id {
 
     #[tokio::test]
     async fn response_header_should_be_application_json() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -396,7 +400,7 @@ mod update_book_with_invalid_id {
 
     #[tokio::test]
     async fn response_body_should_be_an_object() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -412,7 +416,7 @@ mod update_book_with_invalid_id {
 
     #[tokio::test]
     async fn response_body_should_have_correct_property_and_value() {
-        let mut app = app().await;
+        let mut app = app(Config::ephemeral()).await;
         let request = build_create_book_request(new_book_dummy());
         let ready_service = get_ready_service(&mut app).await;
         let response = ready_service.call(request).await.unwrap();
@@ -432,3 +436,69 @@ mod update_book_with_invalid_id {
         assert!(!body.as_object().unwrap().contains_key("data"));
     }
 }
+
+#[cfg(test)]
+mod update_book_as_full_replacement {
+    use axum::http::StatusCode;
+    use http_body_util::BodyExt;
+    use serde_json::json;
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            build_create_book_request, build_get_book_by_id_request, build_update_book_request,
+            get_ready_service, new_book_dummy, update_book_dummy,
+        },
+    };
+
+    #[tokio::test]
+    async fn zero_and_empty_values_should_be_written() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut book = new_book_dummy();
+        book["reading"] = json!(true);
+        let request = build_create_book_request(book);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = body["data"]["bookId"].as_str().unwrap();
+
+        let mut update = update_book_dummy();
+        update["summary"] = json!("");
+        update["readPage"] = json!(0);
+        update["reading"] = json!(false);
+        let request = build_update_book_request(id, update);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = build_get_book_by_id_request(id);
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let book = &body["data"]["book"];
+        assert_eq!(book["summary"], "");
+        assert_eq!(book["readPage"], 0);
+        assert_eq!(book["reading"], false);
+    }
+
+    #[tokio::test]
+    async fn missing_fields_should_be_rejected() {
+        let mut app = app(Config::ephemeral()).await;
+        let request = build_create_book_request(new_book_dummy());
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = body["data"]["bookId"].as_str().unwrap();
+
+        let request = build_update_book_request(id, json!({ "name": "Buku Revisi" }));
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert!(response.status().is_client_error());
+    }
+}
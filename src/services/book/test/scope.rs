@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod book_scopes {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tonic::Code;
+    use tower::Service;
+
+    use crate::{
+        app::{AppState, app},
+        config::Config,
+        repos::user::Role,
+        services::book::{
+            grpc::{
+                BookGrpcService,
+                proto::{CreateBookRequest, ListBooksRequest, book_service_server::BookService},
+            },
+            test::{
+                TEST_USER, bearer_as, build_create_book_request, build_delete_book_request,
+                build_get_books_request, build_update_book_request, get_ready_service,
+                new_book_dummy, update_book_dummy,
+            },
+        },
+    };
+
+    fn as_reader(mut request: Request<Body>) -> Request<Body> {
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            bearer_as(TEST_USER, Role::Reader).parse().unwrap(),
+        );
+        request
+    }
+
+    fn grpc_as_reader<T>(message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request.metadata_mut().insert(
+            "authorization",
+            bearer_as(TEST_USER, Role::Reader).parse().unwrap(),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn reader_should_list_books() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = as_reader(build_get_books_request());
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn reader_should_not_create_books() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = as_reader(build_create_book_request(new_book_dummy()));
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["message"], "Insufficient permissions");
+    }
+
+    #[tokio::test]
+    async fn reader_should_not_update_or_delete_books() {
+        let mut app = app(Config::ephemeral()).await;
+        let request = build_create_book_request(new_book_dummy());
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        let book_id = body["data"]["bookId"].as_str().unwrap();
+
+        let request = as_reader(build_update_book_request(book_id, update_book_dummy()));
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = as_reader(build_delete_book_request(book_id));
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn grpc_reader_should_be_permission_denied_on_writes() {
+        let service = BookGrpcService::new(AppState::new(&Config::ephemeral()).await.unwrap().book);
+
+        let status = service
+            .create_book(grpc_as_reader(CreateBookRequest { book: None }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+
+        let request = grpc_as_reader(ListBooksRequest::default());
+        assert!(service.list_books(request).await.is_ok());
+    }
+}
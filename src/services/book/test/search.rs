@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod search_books {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            build_create_book_request, build_search_books_request, get_ready_service,
+            new_book_dummy,
+        },
+    };
+
+    async fn app_with_books(books: &[(&str, &str)]) -> Router {
+        let mut app = app(Config::ephemeral()).await;
+        for (name, summary) in books {
+            let mut book = new_book_dummy();
+            book["name"] = json!(name);
+            book["summary"] = json!(summary);
+            let request = build_create_book_request(book);
+            let ready_service = get_ready_service(&mut app).await;
+            let response = ready_service.call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        app
+    }
+
+    async fn search(app: &mut Router, query: &str) -> (StatusCode, Value) {
+        let request = build_search_books_request(query);
+        let ready_service = get_ready_service(app).await;
+        let response = ready_service.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn search_should_return_ranked_books_with_snippets() {
+        let mut app = app_with_books(&[
+            ("Buku Masak", "Resep rendang dan soto"),
+            ("Rendang", "Sejarah rendang dari Minangkabau"),
+        ])
+        .await;
+
+        let request = build_search_books_request("q=rendang");
+        let ready_service = get_ready_service(&mut app).await;
+        let response = ready_service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "success");
+        let books = body["data"]["books"].as_array().unwrap();
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0]["name"], "Rendang");
+        assert!(books[0]["id"].is_string());
+        assert_eq!(books[0]["publisher"], "Dicoding Indonesia");
+        assert!(books[0]["snippet"].as_str().unwrap().contains("<mark>"));
+        assert_eq!(body["meta"]["total"], 2);
+    }
+
+    #[tokio::test]
+    async fn search_should_page_results() {
+        let mut app = app_with_books(&[("Rust A", ""), ("Rust B", ""), ("Rust C", "")]).await;
+
+        let (status, body) = search(&mut app, "q=rust&page=2&limit=2").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["books"].as_array().unwrap().len(), 1);
+        assert_eq!(body["meta"]["page"], 2);
+        assert_eq!(body["meta"]["totalPages"], 2);
+        assert!(body["meta"]["nextPage"].is_null());
+    }
+
+    #[tokio::test]
+    async fn search_without_match_should_be_empty() {
+        let mut app = app_with_books(&[("Rust A", "")]).await;
+
+        let (status, body) = search(&mut app, "q=python").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"]["books"].as_array().unwrap().is_empty());
+        assert_eq!(body["meta"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn search_without_words_should_be_400() {
+        let mut app = app_with_books(&[]).await;
+
+        for query in ["", "q=", "q=%22%28%2A"] {
+            let (status, body) = search(&mut app, query).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "query `{}`", query);
+            assert_eq!(body["status"], "fail");
+            assert_eq!(
+                body["message"],
+                "Gagal mencari buku. Mohon isi kata kunci pencarian"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn search_with_invalid_limit_should_be_400() {
+        let mut app = app_with_books(&[]).await;
+
+        let (status, body) = search(&mut app, "q=rust&limit=0").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+    }
+}
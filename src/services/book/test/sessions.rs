@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod book_sessions {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+    };
+    use chrono::{DateTime, Duration, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        AppError,
+        app::{AppState, app, router},
+        config::Config,
+        repos::{
+            book::{Book, BookRepo},
+            session::SessionFilter,
+        },
+        services::{
+            auth::test::call,
+            book::test::{
+                TEST_USER, build_create_book_request, build_create_session_request,
+                build_get_book_by_id_request, build_get_progress_request, build_patch_book_request,
+                get_ready_service, new_book_dummy,
+            },
+        },
+    };
+
+    /// Books changed by someone else between every read and update.
+    struct RacedBookRepo(Arc<dyn BookRepo>);
+
+    #[async_trait]
+    impl BookRepo for RacedBookRepo {
+        async fn get_book_by_id(&self, owner: Uuid, id: Uuid) -> Result<Option<Book>, AppError> {
+            self.0.get_book_by_id(owner, id).await
+        }
+        async fn update_book(&self, book: &Book) -> Result<i64, AppError> {
+            let mut concurrent = self
+                .0
+                .get_book_by_id(book.owner_id, book.id)
+                .await?
+                .unwrap();
+            concurrent.name = "Buku B".to_string();
+            self.0.update_book(&concurrent).await?;
+            self.0.update_book(book).await
+        }
+    }
+
+    /// Creates a 100 page book not yet being read, returning its id.
+    async fn create_book(app: &mut Router) -> String {
+        let mut book = new_book_dummy();
+        book["readPage"] = json!(0);
+        let (_, body) = call(app, build_create_book_request(book)).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    /// Half an hour from `start_page` to `end_page`, ending `hours_ago`.
+    fn session(start_page: i32, end_page: i32, hours_ago: i64) -> Value {
+        let ended_at: DateTime<Utc> = Utc::now() - Duration::hours(hours_ago);
+        json!({
+            "startPage": start_page,
+            "endPage": end_page,
+            "startedAt": ended_at - Duration::minutes(30),
+            "endedAt": ended_at
+        })
+    }
+
+    async fn book(app: &mut Router, id: &str) -> Value {
+        let (_, body) = call(app, build_get_book_by_id_request(id)).await;
+        body["data"]["book"].clone()
+    }
+
+    #[tokio::test]
+    async fn session_should_move_the_book_to_its_end_page() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+
+        let (status, body) = call(
+            &mut app,
+            build_create_session_request(&id, session(0, 40, 1)),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Sesi membaca berhasil ditambahkan");
+        assert!(body["data"]["sessionId"].is_string());
+        assert_eq!(body["data"]["readPage"], 40);
+        assert_eq!(body["data"]["reading"], true);
+        assert_eq!(body["data"]["finished"], false);
+        let book = book(&mut app, &id).await;
+        assert_eq!(book["readPage"], 40);
+        assert_eq!(book["reading"], true);
+        assert_eq!(book["version"], 2);
+    }
+
+    #[tokio::test]
+    async fn session_reaching_the_last_page_should_finish_the_book() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_create_session_request(&id, session(0, 60, 2)),
+        )
+        .await;
+
+        let (_, body) = call(
+            &mut app,
+            build_create_session_request(&id, session(60, 100, 1)),
+        )
+        .await;
+
+        assert_eq!(body["data"]["finished"], true);
+        assert_eq!(book(&mut app, &id).await["finished"], true);
+    }
+
+    #[tokio::test]
+    async fn finishing_session_should_date_the_finish_by_its_end() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        let session = session(0, 100, 24 * 60);
+
+        call(&mut app, build_create_session_request(&id, session.clone())).await;
+
+        let book = book(&mut app, &id).await;
+        let finished_at: DateTime<Utc> =
+            serde_json::from_value(book["finishedAt"].clone()).unwrap();
+        let ended_at: DateTime<Utc> = serde_json::from_value(session["endedAt"].clone()).unwrap();
+        assert_eq!(book["finished"], true);
+        assert_eq!(finished_at, ended_at);
+    }
+
+    #[tokio::test]
+    async fn earlier_session_should_leave_the_book_as_it_is() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_create_session_request(&id, session(20, 50, 1)),
+        )
+        .await;
+
+        let (status, body) = call(
+            &mut app,
+            build_create_session_request(&id, session(0, 20, 5)),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["data"]["readPage"], 50);
+        let book = book(&mut app, &id).await;
+        assert_eq!(book["readPage"], 50);
+        assert_eq!(book["version"], 2);
+    }
+
+    #[tokio::test]
+    async fn invalid_session_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        let mut reversed = session(0, 10, 1);
+        reversed["startedAt"] = json!(Utc::now() - Duration::minutes(10));
+        reversed["endedAt"] = json!(Utc::now() - Duration::minutes(20));
+        let mut future = session(0, 10, 0);
+        future["endedAt"] = json!(Utc::now() + Duration::hours(1));
+
+        for (payload, message) in [
+            (session(-1, 10, 1), "startPage tidak boleh negatif"),
+            (
+                session(30, 10, 1),
+                "startPage tidak boleh lebih besar dari endPage",
+            ),
+            (
+                session(0, 101, 1),
+                "endPage tidak boleh lebih besar dari pageCount",
+            ),
+            (reversed, "startedAt harus sebelum endedAt"),
+            (future, "endedAt tidak boleh di masa depan"),
+        ] {
+            let (status, body) = call(&mut app, build_create_session_request(&id, payload)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["status"], "fail");
+            assert_eq!(
+                body["message"],
+                format!("Gagal menambahkan sesi membaca. {}", message)
+            );
+        }
+        assert_eq!(book(&mut app, &id).await["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn session_on_unknown_book_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for id in [Uuid::new_v4().to_string(), "xxxxx".to_string()] {
+            let (status, body) = call(
+                &mut app,
+                build_create_session_request(&id, session(0, 10, 1)),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(
+                body["message"],
+                "Gagal menambahkan sesi membaca. Id tidak ditemukan"
+            );
+            let (status, _) = call(&mut app, build_get_progress_request(&id)).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn session_with_stale_if_match_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "year": 2012 })),
+        )
+        .await;
+
+        let mut request = build_create_session_request(&id, session(0, 10, 1));
+        request
+            .headers_mut()
+            .insert(header::IF_MATCH, "\"1\"".parse().unwrap());
+        let response = tower::Service::call(get_ready_service(&mut app).await, request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn progress_should_chart_the_sessions() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app).await;
+        for (start, end, hours_ago) in [(10, 25, 3), (0, 10, 5), (25, 50, 1)] {
+            call(
+                &mut app,
+                build_create_session_request(&id, session(start, end, hours_ago)),
+            )
+            .await;
+        }
+
+        let (status, body) = call(&mut app, build_get_progress_request(&id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let progress = &body["data"]["progress"];
+        assert_eq!(progress["bookId"], id);
+        assert_eq!(progress["pageCount"], 100);
+        assert_eq!(progress["readPage"], 50);
+        assert_eq!(progress["percent"], 50.0);
+        assert_eq!(progress["finished"], false);
+        assert_eq!(progress["sessions"], 3);
+        assert_eq!(progress["pagesRead"], 50);
+        assert_eq!(progress["secondsRead"], 3 * 30 * 60);
+        let pages: Vec<i64> = progress["series"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|point| point["page"].as_i64().unwrap())
+            .collect();
+        assert_eq!(pages, vec![10, 25, 50]);
+        assert_eq!(progress["series"][1]["pagesRead"], 15);
+    }
+
+    #[tokio::test]
+    async fn progress_without_sessions_should_be_empty() {
+        let mut app = app(Config::ephemeral()).await;
+        let (_, body) = call(&mut app, build_create_book_request(new_book_dummy())).await;
+        let id = body["data"]["bookId"].as_str().unwrap();
+
+        let (status, body) = call(&mut app, build_get_progress_request(id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let progress = &body["data"]["progress"];
+        assert_eq!(progress["readPage"], 25);
+        assert_eq!(progress["percent"], 25.0);
+        assert_eq!(progress["sessions"], 0);
+        assert_eq!(progress["series"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn session_should_not_be_kept_when_the_book_changed_meanwhile() {
+        let mut state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let id = create_book(&mut app).await;
+        state.book.repo = Arc::new(RacedBookRepo(state.book.repo.clone()));
+        let mut raced = router(state.clone());
+
+        let request = build_create_session_request(&id, session(0, 40, 1));
+        let (status, _) = call(&mut raced, request).await;
+
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+        let sessions = state
+            .book
+            .sessions
+            .get_sessions(TEST_USER, &SessionFilter::default())
+            .await
+            .unwrap();
+        assert!(sessions.is_empty());
+        let book = book(&mut app, &id).await;
+        assert_eq!(book["name"], "Buku B");
+        assert_eq!(book["readPage"], 0);
+    }
+}
@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod book_stats {
+    use axum::{Router, http::StatusCode};
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        app::{AppState, app, router},
+        config::Config,
+        services::{
+            auth::test::call,
+            book::test::{
+                TEST_USER, as_user, build_create_book_request, build_create_session_request,
+                build_get_stats_request, build_patch_book_request, new_book_dummy,
+            },
+        },
+    };
+
+    /// Creates a book by `author`, finished when `finished`, returning its id.
+    async fn create_book(app: &mut Router, author: &str, finished: bool) -> String {
+        let mut book = new_book_dummy();
+        book["author"] = json!(author);
+        if finished {
+            book["readPage"] = json!(100);
+            book["reading"] = json!(true);
+        }
+        let (status, body) = call(app, build_create_book_request(book)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn stats_should_aggregate_the_shelf() {
+        let mut app = app(Config::ephemeral()).await;
+        create_book(&mut app, "Tere Liye", true).await;
+        create_book(&mut app, "Tere Liye", false).await;
+        create_book(&mut app, "Dee Lestari", false).await;
+
+        let (status, body) = call(&mut app, build_get_stats_request("")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        let stats = &body["data"]["stats"];
+        assert_eq!(stats["totalBooks"], 3);
+        assert_eq!(stats["finishedBooks"], 1);
+        assert_eq!(stats["currentlyReading"], 0);
+        assert_eq!(stats["pagesReached"], 150);
+        assert_eq!(
+            stats["periods"],
+            json!([{
+                "period": Utc::now().format("%Y-%m").to_string(),
+                "booksFinished": 1,
+                "pagesRead": 0
+            }])
+        );
+        assert!(stats["averageCompletionSecs"].is_i64());
+        assert_eq!(
+            stats["topAuthors"],
+            json!([
+                { "name": "Tere Liye", "books": 2 },
+                { "name": "Dee Lestari", "books": 1 }
+            ])
+        );
+        assert_eq!(
+            stats["topPublishers"],
+            json!([{ "name": "Dicoding Indonesia", "books": 3 }])
+        );
+    }
+
+    #[tokio::test]
+    async fn stats_should_follow_period_and_top() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app, "Tere Liye", false).await;
+        create_book(&mut app, "Dee Lestari", false).await;
+        let ended_at = Utc::now() - Duration::minutes(5);
+        let session = json!({
+            "startPage": 25,
+            "endPage": 60,
+            "startedAt": ended_at - Duration::minutes(30),
+            "endedAt": ended_at
+        });
+        call(&mut app, build_create_session_request(&id, session)).await;
+
+        let (status, body) = call(&mut app, build_get_stats_request("period=year&top=1")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let stats = &body["data"]["stats"];
+        assert_eq!(
+            stats["periods"],
+            json!([{
+                "period": ended_at.format("%Y").to_string(),
+                "booksFinished": 0,
+                "pagesRead": 35
+            }])
+        );
+        assert_eq!(stats["pagesReached"], 60 + 25);
+        assert_eq!(stats["topAuthors"].as_array().unwrap().len(), 1);
+        assert_eq!(stats["averageCompletionSecs"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn editing_a_finished_book_should_keep_the_period_it_was_finished_in() {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let id = create_book(&mut app, "Tere Liye", true).await;
+        let book_id = Uuid::parse_str(&id).unwrap();
+        let mut book = state
+            .book
+            .repo
+            .get_book_by_id(TEST_USER, book_id)
+            .await
+            .unwrap()
+            .unwrap();
+        book.inserted_at = Utc.with_ymd_and_hms(2024, 1, 18, 12, 0, 0).unwrap();
+        book.finished_at = Some(Utc.with_ymd_and_hms(2024, 1, 20, 12, 0, 0).unwrap());
+        state.book.repo.save_book(&book).await.unwrap();
+
+        let request = build_patch_book_request(&id, json!({ "summary": "Direvisi" }));
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, body) = call(&mut app, build_get_stats_request("")).await;
+
+        let stats = &body["data"]["stats"];
+        assert_eq!(
+            stats["periods"],
+            json!([{ "period": "2024-01", "booksFinished": 1, "pagesRead": 0 }])
+        );
+        assert_eq!(
+            stats["averageCompletionSecs"],
+            Duration::days(2).num_seconds()
+        );
+    }
+
+    #[tokio::test]
+    async fn stats_should_only_count_the_callers_books() {
+        let mut app = app(Config::ephemeral()).await;
+        create_book(&mut app, "Tere Liye", true).await;
+
+        let request = as_user(build_get_stats_request(""), Uuid::new_v4());
+        let (status, body) = call(&mut app, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["stats"]["totalBooks"], 0);
+        assert_eq!(body["data"]["stats"]["periods"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn stats_with_invalid_query_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for query in ["top=0", "top=51", "period=week"] {
+            let (status, body) = call(&mut app, build_get_stats_request(query)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["status"], "fail");
+            assert!(
+                body["message"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("Gagal menampilkan statistik.")
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod book_transfer {
+    use axum::{
+        Router,
+        http::{StatusCode, header},
+        response::Response,
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::Service;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::book::test::{
+            as_user, build_create_book_request, build_export_books_request,
+            build_import_books_request, get_ready_service, new_book_dummy,
+        },
+    };
+
+    const CSV: &str = "\
+name,year,author,summary,publisher,pageCount,readPage,reading
+Buku A,2010,John Doe,\"Lorem ipsum, dolor\",Dicoding,100,25,false
+,2011,Jane Doe,Tanpa nama,Dicoding,100,25,false
+Buku C,2012,Jane Doe,Halaman lebih,Dicoding,100,200,false
+Buku D,tahun,Jane Doe,Tahun salah,Dicoding,100,25,false
+Buku E,2013,\"Jane
+Doe\",Dua baris,Dicoding,50,50,true
+";
+
+    async fn send(app: &mut Router, request: axum::http::Request<axum::body::Body>) -> Response {
+        get_ready_service(app).await.call(request).await.unwrap()
+    }
+
+    async fn json_body(response: Response) -> Value {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn text_body(response: Response) -> String {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn csv_import_should_add_valid_rows_and_report_the_rest() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let response = send(&mut app, build_import_books_request("text/csv", CSV)).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Buku berhasil diimpor");
+        assert_eq!(body["data"]["imported"], 2);
+        assert_eq!(body["data"]["failed"], 3);
+        let errors = body["data"]["errors"].as_array().unwrap();
+        assert_eq!(errors[0]["line"], 3);
+        assert_eq!(
+            errors[0]["message"],
+            "Gagal menambahkan buku. Mohon isi nama buku"
+        );
+        assert_eq!(errors[1]["line"], 4);
+        assert_eq!(
+            errors[1]["message"],
+            "Gagal menambahkan buku. readPage tidak boleh lebih besar dari pageCount"
+        );
+        assert_eq!(errors[2]["line"], 5);
+        assert!(
+            errors[2]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Gagal menambahkan buku. ")
+        );
+    }
+
+    #[tokio::test]
+    async fn ndjson_import_should_report_rows_by_line() {
+        let mut app = app(Config::ephemeral()).await;
+        let mut invalid = new_book_dummy();
+        invalid["readPage"] = json!(500);
+        let payload = format!("{}\n\nnot json\n{}\n", new_book_dummy(), invalid);
+
+        let request = build_import_books_request("application/x-ndjson", &payload);
+        let body = json_body(send(&mut app, request).await).await;
+
+        assert_eq!(body["data"]["imported"], 1);
+        let lines: Vec<&Value> = body["data"]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| &error["line"])
+            .collect();
+        assert_eq!(lines, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn import_of_other_formats_should_be_415() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let request = build_import_books_request("application/json", "[]");
+        let response = send(&mut app, request).await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(json_body(response).await["status"], "fail");
+    }
+
+    #[tokio::test]
+    async fn csv_export_should_stream_full_books() {
+        let mut app = app(Config::ephemeral()).await;
+        send(&mut app, build_import_books_request("text/csv", CSV)).await;
+
+        let response = send(&mut app, build_export_books_request("format=csv")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"books.csv\""
+        );
+        let text = text_body(response).await;
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(headers.len(), 15);
+        assert_eq!(&headers[2], "name");
+        assert_eq!(&headers[10], "finished");
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][2], "Buku A");
+        assert_eq!(&rows[0][5], "Lorem ipsum, dolor");
+        assert_eq!(&rows[1][4], "Jane\nDoe");
+        assert_eq!(&rows[1][10], "true");
+    }
+
+    #[tokio::test]
+    async fn ndjson_export_should_stream_one_book_per_line() {
+        let mut app = app(Config::ephemeral()).await;
+        send(&mut app, build_create_book_request(new_book_dummy())).await;
+
+        let response = send(&mut app, build_export_books_request("format=ndjson")).await;
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+        let text = text_body(response).await;
+        let books: Vec<Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0]["name"], "Buku A");
+        assert_eq!(books[0]["pageCount"], 100);
+        assert!(books[0]["ownerId"].is_string());
+        assert!(books[0]["insertedAt"].is_string());
+    }
+
+    #[tokio::test]
+    async fn export_should_round_trip_through_import() {
+        let mut app = app(Config::ephemeral()).await;
+        send(&mut app, build_import_books_request("text/csv", CSV)).await;
+        let exported = text_body(send(&mut app, build_export_books_request("")).await).await;
+
+        let other = uuid::Uuid::from_u128(2);
+        let request = as_user(build_import_books_request("text/csv", &exported), other);
+        let body = json_body(send(&mut app, request).await).await;
+
+        assert_eq!(body["data"]["imported"], 2);
+        assert_eq!(body["data"]["failed"], 0);
+        let request = as_user(build_export_books_request("format=ndjson"), other);
+        let text = text_body(send(&mut app, request).await).await;
+        let names: Vec<String> = text
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .map(|book| book["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["Buku A", "Buku E"]);
+    }
+
+    #[tokio::test]
+    async fn export_of_empty_shelf_should_only_have_a_header() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let text = text_body(send(&mut app, build_export_books_request("format=csv")).await).await;
+
+        assert!(text.starts_with("id,ownerId,name,"));
+        assert_eq!(text.lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn export_with_unknown_format_should_be_400() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let response = send(&mut app, build_export_books_request("format=xml")).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
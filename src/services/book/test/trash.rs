@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod book_trash {
+    use std::time::Duration;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::{
+        app::{AppState, app, router, trash_purge},
+        config::{Config, TrashConfig},
+        repos::user::Role,
+        services::book::test::{
+            TEST_USER, bearer_as, build_create_book_request, build_delete_book_request,
+            build_get_book_by_id_request, build_get_trash_request, build_restore_book_request,
+            get_ready_service, new_book_dummy,
+        },
+    };
+
+    async fn call(app: &mut Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = get_ready_service(app).await.call(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    /// Creates a book and deletes it, returning its id.
+    async fn trash_book(app: &mut Router) -> String {
+        let (_, body) = call(app, build_create_book_request(new_book_dummy())).await;
+        let id = body["data"]["bookId"].as_str().unwrap().to_string();
+        let (status, _) = call(app, build_delete_book_request(&id)).await;
+        assert_eq!(status, StatusCode::OK);
+        id
+    }
+
+    #[tokio::test]
+    async fn deleted_book_should_only_be_listed_in_trash() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = trash_book(&mut app).await;
+
+        let (status, _) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = call(&mut app, build_get_trash_request("")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        let books = body["data"]["books"].as_array().unwrap();
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0]["id"], id);
+        assert_eq!(books[0]["name"], "Buku A");
+        assert!(books[0]["deletedAt"].is_string());
+        assert_eq!(body["meta"]["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn restored_book_should_be_back_on_the_shelf() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = trash_book(&mut app).await;
+
+        let (status, body) = call(&mut app, build_restore_book_request(&id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Buku berhasil dipulihkan");
+        assert_eq!(body["data"]["bookId"], id);
+        let (status, body) = call(&mut app, build_get_book_by_id_request(&id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["book"]["name"], "Buku A");
+        let (_, body) = call(&mut app, build_get_trash_request("")).await;
+        assert_eq!(body["meta"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn restore_outside_the_trash_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = trash_book(&mut app).await;
+        call(&mut app, build_restore_book_request(&id)).await;
+
+        for id in [id, Uuid::new_v4().to_string(), "xxxxx".to_string()] {
+            let (status, body) = call(&mut app, build_restore_book_request(&id)).await;
+
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body["status"], "fail");
+            assert_eq!(body["message"], "Buku gagal dipulihkan. Id tidak ditemukan");
+        }
+    }
+
+    #[tokio::test]
+    async fn reader_should_list_but_not_restore_the_trash() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = trash_book(&mut app).await;
+        let as_reader = |mut request: Request<Body>| {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                bearer_as(TEST_USER, Role::Reader).parse().unwrap(),
+            );
+            request
+        };
+
+        let (status, _) = call(&mut app, as_reader(build_get_trash_request(""))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&mut app, as_reader(build_restore_book_request(&id))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn trash_with_invalid_paging_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let (status, body) = call(&mut app, build_get_trash_request("limit=0")).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["status"], "fail");
+    }
+
+    #[tokio::test]
+    async fn purge_should_empty_the_trash_past_retention() {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let id = trash_book(&mut app).await;
+        let config = TrashConfig {
+            retention_secs: 0,
+            purge_interval_secs: 3600,
+        };
+
+        let purge = tokio::spawn(trash_purge(&state, &config).unwrap());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        purge.abort();
+
+        let (_, body) = call(&mut app, build_get_trash_request("")).await;
+        assert_eq!(body["meta"]["total"], 0);
+        let (status, _) = call(&mut app, build_restore_book_request(&id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn purge_should_keep_books_within_retention() {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let id = trash_book(&mut app).await;
+
+        let purge = tokio::spawn(trash_purge(&state, &TrashConfig::default()).unwrap());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        purge.abort();
+
+        let (status, _) = call(&mut app, build_restore_book_request(&id)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn purge_without_interval_should_be_refused() {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let config = TrashConfig {
+            purge_interval_secs: 0,
+            ..TrashConfig::default()
+        };
+
+        assert!(trash_purge(&state, &config).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Bulk import and export of a shelf as CSV or JSON Lines. Both directions
+//! stream, so shelves of any size move without being held in memory.
+
+use std::io::{self, BufRead, BufReader};
+use std::sync::LazyLock;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Query, State, rejection::QueryRejection},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio_stream::{StreamExt, once};
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::Book;
+use crate::services::audit::request_id;
+use crate::services::auth::scope::{Authorized, BooksRead, BooksWrite};
+
+use super::BookState;
+use super::handler::BookParams;
+
+/// Columns of an exported CSV, taken from how the serializer writes a
+/// [`Book`] so they always line up with [`csv_row`].
+static CSV_HEADER: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let placeholder = Book {
+        id: Uuid::nil(),
+        owner_id: Uuid::nil(),
+        name: String::new(),
+        year: 0,
+        author: String::new(),
+        summary: String::new(),
+        publisher: String::new(),
+        page_count: 0,
+        read_page: 0,
+        reading: false,
+        finished: false,
+        finished_at: None,
+        updated_at: DateTime::UNIX_EPOCH,
+        inserted_at: DateTime::UNIX_EPOCH,
+        version: 0,
+    };
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .serialize(&placeholder)
+        .expect("serializing a book to memory");
+    let mut header = writer.into_inner().expect("flushing to memory");
+    let end = header
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(0, |at| at + 1);
+    header.truncate(end);
+    header
+});
+
+/// Rows parsed ahead of the ones being saved.
+const ROW_BUFFER: usize = 64;
+
+#[derive(Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+impl TransferFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "text/csv; charset=utf-8",
+            TransferFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "csv",
+            TransferFormat::Ndjson => "ndjson",
+        }
+    }
+
+    fn from_content_type(value: &str) -> Option<Self> {
+        let essence = value.split(';').next().unwrap_or_default().trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "text/csv" => Some(TransferFormat::Csv),
+            "application/x-ndjson" | "application/jsonl" => Some(TransferFormat::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// csv or ndjson, defaults to csv
+    format: Option<TransferFormat>,
+}
+
+/// Why the row starting on `line` was skipped.
+#[derive(Serialize, ToSchema)]
+pub struct RowError {
+    line: u64,
+    message: String,
+}
+
+/// A row of the upload, or why it couldn't be read as a book.
+struct ParsedRow {
+    line: u64,
+    params: Result<BookParams, String>,
+}
+
+fn unreadable() -> AppError {
+    let message = "Gagal mengimpor buku. Isi berkas tidak dapat dibaca".to_string();
+    AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+}
+
+/// Parses `body` on a blocking thread, the CSV reader being synchronous.
+/// The channel ends with an error if the body itself fails.
+fn parse_rows(format: TransferFormat, body: Body) -> mpsc::Receiver<Result<ParsedRow, AppError>> {
+    let chunks = body
+        .into_data_stream()
+        .map(|chunk| chunk.map_err(io::Error::other));
+    let reader = SyncIoBridge::new(StreamReader::new(chunks));
+    let (sender, receiver) = mpsc::channel(ROW_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        let result = match format {
+            TransferFormat::Csv => parse_csv(reader, &sender),
+            TransferFormat::Ndjson => parse_ndjson(reader, &sender),
+        };
+        if result.is_err() {
+            let _ = sender.blocking_send(Err(unreadable()));
+        }
+    });
+    receiver
+}
+
+/// Stops early, without an error, once the receiver is gone.
+fn parse_csv(
+    reader: impl io::Read,
+    sender: &mpsc::Sender<Result<ParsedRow, AppError>>,
+) -> io::Result<()> {
+    let mut csv = csv::Reader::from_reader(reader);
+    let headers = csv.headers().map_err(io::Error::other)?.clone();
+    let mut record = csv::StringRecord::new();
+    loop {
+        let line = csv.position().line();
+        let params = match csv.read_record(&mut record) {
+            Ok(false) => return Ok(()),
+            Ok(true) => record
+                .deserialize::<BookParams>(Some(&headers))
+                .map_err(|e| match e.kind() {
+                    csv::ErrorKind::Deserialize { err, .. } => err.to_string(),
+                    _ => e.to_string(),
+                }),
+            Err(e) if e.is_io_error() => return Err(e.into()),
+            Err(e) => Err(e.to_string()),
+        };
+        if sender
+            .blocking_send(Ok(ParsedRow { line, params }))
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Blank lines are skipped but still counted.
+fn parse_ndjson(
+    reader: impl io::Read,
+    sender: &mpsc::Sender<Result<ParsedRow, AppError>>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    let mut line = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(());
+        }
+        line += 1;
+        if buffer.trim_ascii().is_empty() {
+            continue;
+        }
+        let params = serde_json::from_slice::<BookParams>(&buffer).map_err(|e| e.to_string());
+        if sender
+            .blocking_send(Ok(ParsedRow { line, params }))
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Adds every valid row as a new book of the caller, with a fresh id, and
+/// reports the rest by line. Columns and fields follow `POST /books`, the
+/// extra ones of an export are ignored.
+#[utoipa::path(
+    post,
+    path = "/books/import",
+    request_body(
+        content = String,
+        content_type = "text/csv",
+        description = "CSV with a header row, or JSON Lines as application/x-ndjson"
+    ),
+    responses(
+        (status = 200, description = "Buku berhasil diimpor, with the rows that failed"),
+        (status = 400, description = "Unreadable body"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+        (status = 415, description = "Neither CSV nor JSON Lines"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
+    )
+)]
+pub async fn import_books(
+    State(state): State<BookState>,
+    claims: Authorized<BooksWrite>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let format = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(TransferFormat::from_content_type)
+        .ok_or_else(|| {
+            let message =
+                "Gagal mengimpor buku. Content-Type harus text/csv atau application/x-ndjson";
+            AppError::ClientFail(StatusCode::UNSUPPORTED_MEDIA_TYPE, message.to_string())
+        })?;
+
+    let mut rows = parse_rows(format, body);
+    let mut imported: Vec<Uuid> = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(row) = rows.recv().await {
+        let ParsedRow { line, params } = row?;
+        let params = match params {
+            Ok(params) => params,
+            Err(error) => {
+                let message = format!("Gagal menambahkan buku. {}", error);
+                errors.push(RowError { line, message });
+                continue;
+            }
+        };
+        match params.validate("menambahkan") {
+            Ok(()) => {
+                let book = params.into_book(owner);
+                imported.push(state.repo.save_book(&book).await?);
+                state
+                    .changed(
+                        owner,
+                        AuditAction::Create,
+                        None,
+                        Some(&book),
+                        request_id(&headers),
+                    )
+                    .await;
+            }
+            Err(AppError::ClientFail(_, message)) => errors.push(RowError { line, message }),
+            Err(error) => return Err(error),
+        }
+    }
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Buku berhasil diimpor",
+        "data": {
+            "imported": imported.len(),
+            "failed": errors.len(),
+            "bookIds": imported,
+            "errors": errors
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+fn csv_row(book: &Book) -> io::Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    writer.serialize(book)?;
+    writer.into_inner().map_err(|e| e.into_error())
+}
+
+/// Every book of the caller in full, oldest first.
+#[utoipa::path(
+    get,
+    path = "/books/export",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "The shelf as CSV or JSON Lines"),
+        (status = 400, description = "Invalid format"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn export_books(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    query: Result<Query<ExportQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal mengekspor buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+    let format = query.format.unwrap_or_default();
+
+    // Errors past this point can only cut the response short.
+    let books = state
+        .repo
+        .stream_books(owner)
+        .await?
+        .map(|book| book.map_err(io::Error::other));
+    let body = match format {
+        TransferFormat::Csv => {
+            let rows = books.map(|book| csv_row(&book?));
+            Body::from_stream(once(Ok(CSV_HEADER.clone())).chain(rows))
+        }
+        TransferFormat::Ndjson => Body::from_stream(books.map(|book| {
+            let mut line = serde_json::to_vec(&book?)?;
+            line.push(b'\n');
+            Ok::<_, io::Error>(line)
+        })),
+    };
+
+    let disposition = format!("attachment; filename=\"books.{}\"", format.extension());
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+    ];
+
+    Ok((StatusCode::OK, headers, body))
+}
@@ -0,0 +1,150 @@
+//! Deleted books wait in the trash, where they can be listed and restored
+//! until [`purge_trash`] removes them for good.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State, rejection::QueryRejection},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::AppError;
+use crate::repos::audit::AuditAction;
+use crate::repos::book::BookRepo;
+use crate::services::audit::request_id;
+use crate::services::auth::scope::{Authorized, BooksDelete, BooksRead};
+
+use super::BookState;
+use super::handler::{page_meta, page_request};
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TrashQuery {
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Books per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/books/trash",
+    params(TrashQuery),
+    responses(
+        (status = 200, description = "Deleted books, most recently deleted first"),
+        (status = 400, description = "Invalid paging"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_trash(
+    State(state): State<BookState>,
+    claims: Authorized<BooksRead>,
+    query: Result<Query<TrashQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Query(query) = query.map_err(|rejection| {
+        let message = format!("Gagal menampilkan buku. {}", rejection.body_text());
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+    let page = page_request(query.page, query.limit, None, None)?;
+
+    let books = state.repo.get_trash(owner, &page).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "books": books.items
+        },
+        "meta": page_meta(&page, books.total)
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+/// Puts a deleted book back on the shelf as it was. Needs the same scope as
+/// deleting it.
+#[utoipa::path(
+    post,
+    path = "/books/{id}/restore",
+    responses(
+        (status = 200, description = "Buku berhasil dipulihkan"),
+        (status = 404, description = "Buku gagal dipulihkan. Id tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:delete scope"),
+    ),
+    params(
+        ("id" = String, Path, description = "ID of the deleted book"),
+    ),
+    security(
+        ("bearerAuth" = ["books:delete"]),
+        ("apiKeyAuth" = ["books:delete"])
+    )
+)]
+pub async fn restore_book(
+    State(state): State<BookState>,
+    claims: Authorized<BooksDelete>,
+    Path(id): Path<String>,
+    request_headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let book_id = Uuid::parse_str(&id).map_err(|_| {
+        let message = "Buku gagal dipulihkan. Id tidak ditemukan".to_string();
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    })?;
+
+    let owner = claims.user_id()?;
+    let restored_id = state.repo.restore_book(owner, book_id).await?;
+    if let Some(book) = state.repo.get_book_by_id(owner, restored_id).await? {
+        state
+            .changed(
+                owner,
+                AuditAction::Restore,
+                None,
+                Some(&book),
+                request_id(&request_headers),
+            )
+            .await;
+    }
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Buku berhasil dipulihkan",
+        "data": {
+            "bookId": restored_id
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+/// Every `interval`, starting right away, removes for good the books deleted
+/// more than `retention` ago. Runs until the process exits, a failed purge
+/// is logged and retried on the next round.
+pub async fn purge_trash(
+    repo: Arc<dyn BookRepo>,
+    retention: Duration,
+    interval: std::time::Duration,
+) {
+    let mut rounds = tokio::time::interval(interval);
+    loop {
+        rounds.tick().await;
+        match repo.purge_trash(Utc::now() - retention).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Purged {} books from the trash", count),
+            Err(error) => tracing::warn!("Failed to purge the trash: {}", error),
+        }
+    }
+}
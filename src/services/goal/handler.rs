@@ -0,0 +1,237 @@
+use axum::{
+    Json,
+    extract::{Path, State, rejection::JsonRejection},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::goal::{Goal, GoalMetric},
+    services::auth::scope::{Authorized, BooksRead, BooksWrite},
+};
+
+use super::{GoalProgress, GoalState, counted};
+
+/// A goal over a calendar `year`, or from `startsAt` until `endsAt`.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalParams {
+    metric: GoalMetric,
+    /// Books to finish or pages to read, at least 1
+    target: u32,
+    /// A year in UTC, in place of `startsAt` and `endsAt`
+    year: Option<i32>,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+}
+
+impl GoalParams {
+    /// The start and end of the period.
+    fn period(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+        let fail = |message: &str| {
+            let message = format!("Gagal menambahkan target membaca. {}", message);
+            Err(AppError::ClientFail(StatusCode::BAD_REQUEST, message))
+        };
+
+        if self.target == 0 {
+            return fail("target minimal 1");
+        }
+        let (starts_at, ends_at) = match (self.year, self.starts_at, self.ends_at) {
+            (Some(year), None, None) => {
+                let new_year = |year| Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single();
+                let next_year = year.checked_add(1).and_then(new_year);
+                match (new_year(year), next_year) {
+                    (Some(starts_at), Some(ends_at)) if year > 0 => (starts_at, ends_at),
+                    _ => return fail("year tidak valid"),
+                }
+            }
+            (None, Some(starts_at), Some(ends_at)) => (starts_at, ends_at),
+            _ => return fail("Mohon isi year, atau startsAt dan endsAt"),
+        };
+        if starts_at >= ends_at {
+            return fail("startsAt harus sebelum endsAt");
+        }
+        Ok((starts_at, ends_at))
+    }
+}
+
+fn not_found() -> AppError {
+    let message = "Target membaca tidak ditemukan".to_string();
+    AppError::ClientFail(StatusCode::NOT_FOUND, message)
+}
+
+/// Sets a goal for the caller, counting what they read within the period
+/// whenever it was read.
+#[utoipa::path(
+    post,
+    path = "/goals",
+    request_body = GoalParams,
+    responses(
+        (status = 201, description = "Target membaca berhasil ditambahkan"),
+        (status = 400, description = "Gagal menambahkan target membaca"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
+    )
+)]
+pub async fn create_goal(
+    State(state): State<GoalState>,
+    claims: Authorized<BooksWrite>,
+    params: Result<Json<GoalParams>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let owner = claims.user_id()?;
+    let Json(params) = params.map_err(|rejection| {
+        let message = format!(
+            "Gagal menambahkan target membaca. {}",
+            rejection.body_text()
+        );
+        AppError::ClientFail(StatusCode::BAD_REQUEST, message)
+    })?;
+    let (starts_at, ends_at) = params.period()?;
+
+    let goal = Goal {
+        id: Uuid::new_v4(),
+        owner_id: owner,
+        metric: params.metric,
+        target: params.target,
+        starts_at,
+        ends_at,
+        created_at: Utc::now(),
+    };
+    let id = state.repo.create_goal(&goal).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Target membaca berhasil ditambahkan",
+        "data": {
+            "goalId": id
+        }
+    }));
+
+    Ok((StatusCode::CREATED, headers, body))
+}
+
+/// Goals of the caller, oldest first, without their progress.
+#[utoipa::path(
+    get,
+    path = "/goals",
+    responses(
+        (status = 200, description = "Goals of the caller"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_goals(
+    State(state): State<GoalState>,
+    claims: Authorized<BooksRead>,
+) -> Result<impl IntoResponse, AppError> {
+    let goals = state.repo.get_goals(claims.user_id()?).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "goals": goals
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+/// A goal of the caller with its progress so far and whether the pace keeps
+/// it on track.
+#[utoipa::path(
+    get,
+    path = "/goals/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the goal"),
+    ),
+    responses(
+        (status = 200, description = "The goal and its progress", body = GoalProgress),
+        (status = 404, description = "Target membaca tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:read"]),
+        ("apiKeyAuth" = ["books:read"])
+    )
+)]
+pub async fn get_goal(
+    State(state): State<GoalState>,
+    claims: Authorized<BooksRead>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let goal_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let owner = claims.user_id()?;
+
+    let goal = state
+        .repo
+        .get_goal(owner, goal_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let current = counted(&state, &goal).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "goal": goal,
+            "progress": GoalProgress::new(&goal, current, Utc::now())
+        }
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/goals/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the goal"),
+    ),
+    responses(
+        (status = 200, description = "Target membaca berhasil dihapus"),
+        (status = 404, description = "Target membaca gagal dihapus. Id tidak ditemukan"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the books:write scope"),
+    ),
+    security(
+        ("bearerAuth" = ["books:write"]),
+        ("apiKeyAuth" = ["books:write"])
+    )
+)]
+pub async fn delete_goal(
+    State(state): State<GoalState>,
+    claims: Authorized<BooksWrite>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let goal_id = Uuid::parse_str(&id).map_err(|_| {
+        let message = "Target membaca gagal dihapus. Id tidak ditemukan".to_string();
+        AppError::ClientFail(StatusCode::NOT_FOUND, message)
+    })?;
+
+    state.repo.delete_goal(claims.user_id()?, goal_id).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Target membaca berhasil dihapus"
+    }));
+
+    Ok((StatusCode::OK, headers, body))
+}
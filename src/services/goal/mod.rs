@@ -0,0 +1,131 @@
+//! Reading goals, a number of books to finish or pages to read within a
+//! period, managed at `/goals`. Progress isn't stored but worked out from
+//! the shelf and the reading sessions whenever a goal is read, along with a
+//! projection of where the current pace ends up.
+
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    AppError,
+    repos::{
+        book::{BookFilter, BookRepo, PageRequest},
+        goal::{Goal, GoalMetric, GoalRepo},
+        session::{SessionFilter, SessionRepo},
+    },
+    services::auth::AuthState,
+};
+
+pub mod handler;
+pub mod test;
+
+#[derive(Clone)]
+pub struct GoalState {
+    pub repo: Arc<dyn GoalRepo>,
+    pub books: Arc<dyn BookRepo>,
+    pub sessions: Arc<dyn SessionRepo>,
+    pub auth: AuthState,
+}
+
+impl FromRef<GoalState> for AuthState {
+    fn from_ref(state: &GoalState) -> AuthState {
+        state.auth.clone()
+    }
+}
+
+#[derive(Serialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GoalStatus {
+    /// The target is reached.
+    Achieved,
+    /// At least as far as an even pace would be by now.
+    OnTrack,
+    /// Short of an even pace, or of the target once the period is over.
+    Behind,
+}
+
+/// Where a goal stands, percentages and paces rounded to one decimal.
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    /// Books finished or pages read within the period so far
+    pub current: u64,
+    pub target: u32,
+    /// `current` as a percentage of `target`
+    pub percent: f64,
+    /// Share of the period gone by, as a percentage
+    pub elapsed_percent: f64,
+    /// What an even pace reaching `target` would have reached by now
+    pub expected: f64,
+    /// What the pace so far reaches by the end, unset before the period
+    /// starts
+    pub projected: Option<f64>,
+    pub status: GoalStatus,
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+impl GoalProgress {
+    /// Progress of `goal` at `now` with `current` counted so far.
+    pub fn new(goal: &Goal, current: u64, now: DateTime<Utc>) -> Self {
+        let period = (goal.ends_at - goal.starts_at).num_milliseconds() as f64;
+        let elapsed = ((now - goal.starts_at).num_milliseconds() as f64 / period).clamp(0.0, 1.0);
+        let target = f64::from(goal.target);
+        let expected = target * elapsed;
+        let status = if current >= u64::from(goal.target) {
+            GoalStatus::Achieved
+        } else if current as f64 >= expected {
+            GoalStatus::OnTrack
+        } else {
+            GoalStatus::Behind
+        };
+        GoalProgress {
+            current,
+            target: goal.target,
+            percent: round(current as f64 * 100.0 / target),
+            elapsed_percent: round(elapsed * 100.0),
+            expected: round(expected),
+            projected: (elapsed > 0.0).then(|| round(current as f64 / elapsed)),
+            status,
+        }
+    }
+}
+
+/// What counts towards `goal` so far: the books finished within the period
+/// and still finished, or the pages read in the sessions ending within it.
+pub async fn counted(state: &GoalState, goal: &Goal) -> Result<u64, AppError> {
+    match goal.metric {
+        GoalMetric::Books => {
+            let filter = BookFilter {
+                finished: Some(true),
+                finished_since: Some(goal.starts_at),
+                finished_before: Some(goal.ends_at),
+                ..BookFilter::default()
+            };
+            let page = PageRequest {
+                limit: 1,
+                ..PageRequest::default()
+            };
+            let books = state.books.get_books(goal.owner_id, &filter, &page).await?;
+            Ok(books.total)
+        }
+        GoalMetric::Pages => {
+            let filter = SessionFilter {
+                since: Some(goal.starts_at),
+                until: Some(goal.ends_at),
+                ..SessionFilter::default()
+            };
+            let sessions = state.sessions.get_sessions(goal.owner_id, &filter).await?;
+            Ok(sessions
+                .iter()
+                .map(|session| session.pages_read().max(0) as u64)
+                .sum())
+        }
+    }
+}
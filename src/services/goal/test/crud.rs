@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod goal_crud {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        repos::user::Role,
+        services::{
+            auth::test::call,
+            book::test::{TEST_USER, as_user, bearer_as},
+            goal::test::{
+                build_create_goal_request, build_delete_goal_request, build_get_goal_request,
+                build_get_goals_request,
+            },
+        },
+    };
+
+    /// Creates a goal of the test user, returning its id.
+    async fn create(app: &mut Router, payload: Value) -> String {
+        let (status, body) = call(app, build_create_goal_request(payload)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        body["data"]["goalId"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn yearly_goal_should_span_the_calendar_year() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let (status, body) = call(
+            &mut app,
+            build_create_goal_request(json!({ "metric": "books", "target": 24, "year": 2030 })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Target membaca berhasil ditambahkan");
+        let id = body["data"]["goalId"].as_str().unwrap();
+        let (status, body) = call(&mut app, build_get_goal_request(id)).await;
+        assert_eq!(status, StatusCode::OK);
+        let goal = &body["data"]["goal"];
+        assert_eq!(goal["id"], id);
+        assert_eq!(goal["ownerId"], TEST_USER.to_string());
+        assert_eq!(goal["metric"], "books");
+        assert_eq!(goal["target"], 24);
+        assert_eq!(goal["startsAt"], "2030-01-01T00:00:00Z");
+        assert_eq!(goal["endsAt"], "2031-01-01T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn goals_should_be_listed_for_their_owner_only() {
+        let mut app = app(Config::ephemeral()).await;
+        let first = create(
+            &mut app,
+            json!({ "metric": "books", "target": 12, "year": 2030 }),
+        )
+        .await;
+        let second = create(
+            &mut app,
+            json!({
+                "metric": "pages",
+                "target": 500,
+                "startsAt": "2030-06-01T00:00:00Z",
+                "endsAt": "2030-07-01T00:00:00Z"
+            }),
+        )
+        .await;
+
+        let (status, body) = call(&mut app, build_get_goals_request()).await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<&str> = body["data"]["goals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|goal| goal["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, [first.as_str(), second.as_str()]);
+
+        let other = Uuid::new_v4();
+        let (_, body) = call(&mut app, as_user(build_get_goals_request(), other)).await;
+        assert_eq!(body["data"]["goals"], json!([]));
+        let (status, _) = call(&mut app, as_user(build_get_goal_request(&first), other)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn invalid_goal_should_fail() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for payload in [
+            json!({ "metric": "books", "target": 0, "year": 2030 }),
+            json!({ "metric": "minutes", "target": 10, "year": 2030 }),
+            json!({ "metric": "books", "target": 10 }),
+            json!({ "metric": "books", "target": 10, "year": 2030, "startsAt": "2030-01-01T00:00:00Z" }),
+            json!({ "metric": "books", "target": 10, "year": 0 }),
+            json!({ "metric": "books", "target": 10, "year": i32::MAX }),
+            json!({
+                "metric": "pages",
+                "target": 10,
+                "startsAt": "2030-02-01T00:00:00Z",
+                "endsAt": "2030-01-01T00:00:00Z"
+            }),
+        ] {
+            let (status, body) = call(&mut app, build_create_goal_request(payload)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["status"], "fail");
+            assert!(
+                body["message"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("Gagal menambahkan target membaca.")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_goal_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for id in [Uuid::new_v4().to_string(), "xxxxx".to_string()] {
+            let (status, body) = call(&mut app, build_get_goal_request(&id)).await;
+
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body["message"], "Target membaca tidak ditemukan");
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_goal_should_be_gone() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create(
+            &mut app,
+            json!({ "metric": "books", "target": 12, "year": 2030 }),
+        )
+        .await;
+
+        let (status, body) = call(&mut app, build_delete_goal_request(&id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Target membaca berhasil dihapus");
+        let (status, _) = call(&mut app, build_get_goal_request(&id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, body) = call(&mut app, build_delete_goal_request(&id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body["message"],
+            "Target membaca gagal dihapus. Id tidak ditemukan"
+        );
+    }
+
+    #[tokio::test]
+    async fn reader_should_read_but_not_set_goals() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create(
+            &mut app,
+            json!({ "metric": "books", "target": 12, "year": 2030 }),
+        )
+        .await;
+        let as_reader = |mut request: Request<Body>| {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                bearer_as(TEST_USER, Role::Reader).parse().unwrap(),
+            );
+            request
+        };
+
+        let (status, _) = call(&mut app, as_reader(build_get_goal_request(&id))).await;
+        assert_eq!(status, StatusCode::OK);
+        let payload = json!({ "metric": "books", "target": 12, "year": 2030 });
+        let (status, _) = call(&mut app, as_reader(build_create_goal_request(payload))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call(&mut app, as_reader(build_delete_goal_request(&id))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
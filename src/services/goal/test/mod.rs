@@ -0,0 +1,51 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+use serde_json::Value;
+
+use crate::services::book::test::{TEST_USER, bearer};
+
+pub mod crud;
+pub mod progress;
+
+#[allow(dead_code)]
+fn build_create_goal_request(payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/goals")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_goals_request() -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri("/goals")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_goal_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/goals/{}", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_delete_goal_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("/goals/{}", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
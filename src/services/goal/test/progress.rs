@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod goal_progress {
+    use axum::{Router, http::StatusCode};
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        app::{AppState, app, router},
+        config::Config,
+        repos::goal::{Goal, GoalMetric},
+        services::{
+            auth::test::call,
+            book::test::{
+                TEST_USER, build_create_book_request, build_create_session_request,
+                build_patch_book_request, new_book_dummy,
+            },
+            goal::{
+                GoalProgress, GoalStatus,
+                test::{build_create_goal_request, build_get_goal_request},
+            },
+        },
+    };
+
+    /// Creates a 100 page book, finished when `finished`, returning its id.
+    async fn create_book(app: &mut Router, finished: bool) -> String {
+        let mut book = new_book_dummy();
+        book["readPage"] = json!(if finished { 100 } else { 0 });
+        book["reading"] = json!(finished);
+        let (_, body) = call(app, build_create_book_request(book)).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    /// Creates a goal over the two days around now and returns its progress.
+    async fn progress_of(app: &mut Router, metric: &str, target: u32) -> Value {
+        let now = Utc::now();
+        let payload = json!({
+            "metric": metric,
+            "target": target,
+            "startsAt": now - Duration::days(1),
+            "endsAt": now + Duration::days(1)
+        });
+        let (_, body) = call(app, build_create_goal_request(payload)).await;
+        let id = body["data"]["goalId"].as_str().unwrap();
+        let (status, body) = call(app, build_get_goal_request(id)).await;
+        assert_eq!(status, StatusCode::OK);
+        body["data"]["progress"].clone()
+    }
+
+    #[tokio::test]
+    async fn books_goal_should_count_finished_books() {
+        let mut app = app(Config::ephemeral()).await;
+        create_book(&mut app, true).await;
+        create_book(&mut app, true).await;
+        create_book(&mut app, false).await;
+
+        let progress = progress_of(&mut app, "books", 3).await;
+
+        assert_eq!(progress["current"], 2);
+        assert_eq!(progress["target"], 3);
+        assert_eq!(progress["percent"], 66.7);
+        assert_eq!(progress["status"], "onTrack");
+    }
+
+    #[tokio::test]
+    async fn books_goal_should_skip_books_finished_before_but_edited_within() {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let mut app = router(state.clone());
+        let id = create_book(&mut app, true).await;
+        let book_id = Uuid::parse_str(&id).unwrap();
+        let mut book = state
+            .book
+            .repo
+            .get_book_by_id(TEST_USER, book_id)
+            .await
+            .unwrap()
+            .unwrap();
+        book.finished_at = Some(Utc::now() - Duration::days(10));
+        state.book.repo.save_book(&book).await.unwrap();
+        let request = build_patch_book_request(&id, json!({ "summary": "Direvisi" }));
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let progress = progress_of(&mut app, "books", 1).await;
+
+        assert_eq!(progress["current"], 0);
+    }
+
+    #[tokio::test]
+    async fn pages_goal_should_count_session_pages() {
+        let mut app = app(Config::ephemeral()).await;
+        let id = create_book(&mut app, false).await;
+        let ended_at = Utc::now() - Duration::hours(1);
+        for (start_page, end_page, hours) in [(0, 30, 3), (30, 40, 1)] {
+            let ended_at = ended_at - Duration::hours(hours - 1);
+            let session = json!({
+                "startPage": start_page,
+                "endPage": end_page,
+                "startedAt": ended_at - Duration::minutes(30),
+                "endedAt": ended_at
+            });
+            let (status, _) = call(&mut app, build_create_session_request(&id, session)).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let progress = progress_of(&mut app, "pages", 500).await;
+
+        assert_eq!(progress["current"], 40);
+        assert_eq!(progress["status"], "behind");
+    }
+
+    #[tokio::test]
+    async fn goal_should_not_count_reading_outside_its_period() {
+        let mut app = app(Config::ephemeral()).await;
+        create_book(&mut app, true).await;
+        let payload = json!({ "metric": "books", "target": 1, "year": 2000 });
+        let (_, body) = call(&mut app, build_create_goal_request(payload)).await;
+        let id = body["data"]["goalId"].as_str().unwrap();
+
+        let (_, body) = call(&mut app, build_get_goal_request(id)).await;
+
+        let progress = &body["data"]["progress"];
+        assert_eq!(progress["current"], 0);
+        assert_eq!(progress["elapsedPercent"], 100.0);
+        assert_eq!(progress["status"], "behind");
+    }
+
+    fn goal(target: u32) -> Goal {
+        Goal {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            metric: GoalMetric::Books,
+            target,
+            starts_at: Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+            ends_at: Utc.with_ymd_and_hms(2030, 1, 11, 0, 0, 0).unwrap(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn progress_should_project_the_pace_so_far() {
+        let goal = goal(10);
+        let day = |day| Utc.with_ymd_and_hms(2030, 1, day, 0, 0, 0).unwrap();
+
+        let behind = GoalProgress::new(&goal, 3, day(5));
+        assert_eq!(behind.elapsed_percent, 40.0);
+        assert_eq!(behind.expected, 4.0);
+        assert_eq!(behind.projected, Some(7.5));
+        assert_eq!(behind.status, GoalStatus::Behind);
+
+        let on_track = GoalProgress::new(&goal, 4, day(5));
+        assert_eq!(on_track.percent, 40.0);
+        assert_eq!(on_track.projected, Some(10.0));
+        assert_eq!(on_track.status, GoalStatus::OnTrack);
+
+        let achieved = GoalProgress::new(&goal, 12, day(9));
+        assert_eq!(achieved.percent, 120.0);
+        assert_eq!(achieved.status, GoalStatus::Achieved);
+    }
+
+    #[test]
+    fn progress_before_the_period_should_have_no_projection() {
+        let goal = goal(10);
+
+        let progress = GoalProgress::new(&goal, 0, goal.starts_at - Duration::days(1));
+
+        assert_eq!(progress.elapsed_percent, 0.0);
+        assert_eq!(progress.expected, 0.0);
+        assert_eq!(progress.projected, None);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+    }
+}
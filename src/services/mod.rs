@@ -1,19 +1,135 @@
-use utoipa::OpenApi;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
 
+pub mod admin;
+pub mod api_key;
+pub mod audit;
 pub mod auth;
 pub mod book;
+pub mod goal;
+pub mod webhook;
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         book::handler::create_book,
         book::handler::get_books,
+        book::handler::search_books,
         book::handler::get_book_by_id,
         book::handler::update_book,
+        book::handler::patch_book,
         book::handler::delete_book,
+        book::batch::batch_books,
+        book::transfer::import_books,
+        book::transfer::export_books,
+        book::trash::get_trash,
+        book::trash::restore_book,
+        book::history::get_book_history,
+        book::sessions::create_session,
+        book::sessions::get_book_progress,
+        book::stats::get_shelf_stats,
+        book::events::book_events,
+        book::events::book_events_ws,
+        auth::handler::register,
+        auth::handler::login,
+        auth::handler::refresh,
+        auth::handler::logout,
         auth::handler::authorize,
-        auth::handler::protected
+        auth::handler::jwks,
+        auth::handler::protected,
+        admin::handler::get_users,
+        admin::handler::update_user_role,
+        api_key::handler::create_api_key,
+        api_key::handler::get_api_keys,
+        api_key::handler::delete_api_key,
+        audit::handler::get_audit,
+        goal::handler::create_goal,
+        goal::handler::get_goals,
+        goal::handler::get_goal,
+        goal::handler::delete_goal,
+        webhook::handler::create_webhook,
+        webhook::handler::get_webhooks,
+        webhook::handler::get_webhook,
+        webhook::handler::update_webhook,
+        webhook::handler::delete_webhook,
+        webhook::handler::get_dead_letters,
+        webhook::handler::retry_delivery
     ),
-    components(schemas(book::handler::BookParams, book::handler::BooksQuery, auth::AuthParams))
+    components(schemas(
+        book::handler::BookParams,
+        book::handler::BookPatch,
+        book::handler::BooksQuery,
+        book::handler::SearchQuery,
+        crate::repos::book::BookSort,
+        crate::repos::book::SortOrder,
+        book::batch::BatchOperation,
+        book::batch::BatchParams,
+        book::batch::BatchResult,
+        book::transfer::TransferFormat,
+        book::transfer::ExportQuery,
+        book::transfer::RowError,
+        book::trash::TrashQuery,
+        book::history::HistoryQuery,
+        book::sessions::SessionParams,
+        book::sessions::ProgressPoint,
+        book::sessions::BookProgress,
+        book::stats::StatsQuery,
+        crate::repos::book::stats::StatsPeriod,
+        crate::repos::book::stats::PeriodStats,
+        crate::repos::book::stats::NameCount,
+        crate::repos::book::stats::ShelfStats,
+        book::events::BookEventKind,
+        book::events::EventFilter,
+        auth::AuthParams,
+        auth::Credentials,
+        auth::RefreshParams,
+        auth::TokenPair,
+        auth::scope::Scope,
+        crate::repos::user::Role,
+        admin::handler::UserView,
+        admin::handler::RoleParams,
+        api_key::handler::ApiKeyParams,
+        api_key::handler::ApiKeyView,
+        audit::handler::AuditQuery,
+        crate::repos::audit::AuditAction,
+        crate::repos::audit::AuditEntry,
+        goal::handler::GoalParams,
+        goal::GoalStatus,
+        goal::GoalProgress,
+        crate::repos::goal::GoalMetric,
+        crate::repos::goal::Goal,
+        webhook::handler::WebhookParams,
+        webhook::handler::WebhookView,
+        webhook::handler::DeadLetterQuery,
+        crate::repos::webhook::WebhookEvent,
+        crate::repos::webhook::DeliveryStatus,
+        crate::repos::webhook::WebhookDelivery
+    )),
+    modifiers(&SecurityAddon)
 )]
 pub struct ApiDoc;
+
+/// Declares the `bearerAuth` and `apiKeyAuth` schemes the protected paths
+/// refer to.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearerAuth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "apiKeyAuth",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+    }
+}
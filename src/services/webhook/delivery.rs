@@ -0,0 +1,200 @@
+//! Sending queued deliveries. Every request carries the Unix time it was
+//! sent as `X-Bookshelf-Timestamp` and is signed with the webhook's secret as
+//! `X-Bookshelf-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`,
+//! so receivers can check it came from this server and turn away old ones
+//! replayed later.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use futures_util::{StreamExt, stream};
+use hmac::{Hmac, Mac};
+use reqwest::{Url, header::CONTENT_TYPE, redirect};
+use sha2::Sha256;
+
+use crate::AppError;
+use crate::repos::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookRepo};
+
+use super::target::{PublicResolver, TargetPolicy};
+
+pub const EVENT_HEADER: &str = "X-Bookshelf-Event";
+pub const DELIVERY_HEADER: &str = "X-Bookshelf-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Bookshelf-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Bookshelf-Timestamp";
+
+/// Deliveries sent per round at most, the rest wait for the next one.
+pub const DELIVERY_BATCH: u32 = 100;
+
+/// Deliveries in flight at once within a round.
+pub const CONCURRENT_DELIVERIES: usize = 8;
+
+/// Doublings of the retry delay at most, so it stays in range.
+const MAX_BACKOFF_DOUBLINGS: u32 = 16;
+
+#[derive(Clone, Debug)]
+pub struct DeliveryPolicy {
+    /// Attempts before a delivery is dead.
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled after every further failure.
+    pub retry_base: Duration,
+    pub batch: u32,
+    pub targets: TargetPolicy,
+}
+
+impl DeliveryPolicy {
+    /// When to try again after `attempts` failed ones, `None` once the
+    /// delivery should be given up on.
+    fn retry_delay(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let doublings = attempts.saturating_sub(1).min(MAX_BACKOFF_DOUBLINGS);
+        Some(self.retry_base * (1 << doublings))
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `<timestamp>.<body>` keyed
+/// by `secret`.
+pub fn signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A client for [`deliver_due`] giving receivers `timeout` to answer. It
+/// only connects where `targets` allows and doesn't follow redirects, which
+/// could lead anywhere.
+pub fn client(
+    timeout: std::time::Duration,
+    targets: TargetPolicy,
+) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver(targets)))
+        .build()
+}
+
+/// Posts `delivery` to `webhook`, any 2xx answer counts as delivered.
+async fn attempt(
+    client: &reqwest::Client,
+    targets: &TargetPolicy,
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+) -> Result<(), String> {
+    // Addresses in the URL itself are never resolved, so they're checked
+    // here.
+    let url = Url::parse(&webhook.url).map_err(|error| error.to_string())?;
+    if !targets.allows_url(&url) {
+        return Err("Target address isn't allowed".to_string());
+    }
+    let body = delivery.payload.to_string();
+    let timestamp = Utc::now().timestamp();
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, delivery.event.as_str())
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            signature(&webhook.secret, timestamp, body.as_bytes()),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(response.status().to_string())
+    }
+}
+
+/// Sends `delivery` unless its webhook is gone and stores the outcome.
+async fn deliver(
+    repo: &dyn WebhookRepo,
+    client: &reqwest::Client,
+    policy: &DeliveryPolicy,
+    mut delivery: WebhookDelivery,
+) -> Result<(), AppError> {
+    // Deliveries go along with their webhook, one still listed was queued
+    // just before it was deleted and is given up on so it stops coming back.
+    let Some(webhook) = repo
+        .get_webhook(delivery.owner_id, delivery.webhook_id)
+        .await?
+    else {
+        delivery.status = DeliveryStatus::Dead;
+        delivery.last_error = Some("Webhook deleted".to_string());
+        return repo.update_delivery(&delivery).await;
+    };
+
+    delivery.attempts += 1;
+    match attempt(client, &policy.targets, &webhook, &delivery).await {
+        Ok(()) => {
+            delivery.status = DeliveryStatus::Delivered;
+            delivery.delivered_at = Some(Utc::now());
+            delivery.last_error = None;
+        }
+        Err(error) => {
+            match policy.retry_delay(delivery.attempts) {
+                Some(delay) => delivery.next_attempt_at = Utc::now() + delay,
+                None => {
+                    tracing::warn!(
+                        "Giving up on delivery {} to webhook {} after {} attempts: {}",
+                        delivery.id,
+                        webhook.id,
+                        delivery.attempts,
+                        error
+                    );
+                    delivery.status = DeliveryStatus::Dead;
+                }
+            }
+            delivery.last_error = Some(error);
+        }
+    }
+    repo.update_delivery(&delivery).await
+}
+
+/// One round: attempts every delivery due now, [`CONCURRENT_DELIVERIES`] at
+/// a time so a slow receiver holds up no one else, and stores the outcomes.
+/// Returns how many were attempted, or the first failure to store one once
+/// all are done.
+pub async fn deliver_due(
+    repo: &dyn WebhookRepo,
+    client: &reqwest::Client,
+    policy: &DeliveryPolicy,
+) -> Result<usize, AppError> {
+    let due = repo.get_due_deliveries(Utc::now(), policy.batch).await?;
+    let attempted = due.len();
+    let mut outcomes = stream::iter(due)
+        .map(|delivery| deliver(repo, client, policy, delivery))
+        .buffer_unordered(CONCURRENT_DELIVERIES);
+    let mut failure = None;
+    while let Some(outcome) = outcomes.next().await {
+        if let Err(error) = outcome {
+            failure.get_or_insert(error);
+        }
+    }
+    failure.map_or(Ok(attempted), Err)
+}
+
+/// Every `interval`, starting right away, sends the deliveries due. Runs
+/// until the process exits, a failed round is logged and the deliveries it
+/// missed are picked up by the next one.
+pub async fn deliver_webhooks(
+    repo: Arc<dyn WebhookRepo>,
+    client: reqwest::Client,
+    policy: DeliveryPolicy,
+    interval: std::time::Duration,
+) {
+    let mut rounds = tokio::time::interval(interval);
+    loop {
+        rounds.tick().await;
+        if let Err(error) = deliver_due(repo.as_ref(), &client, &policy).await {
+            tracing::warn!("Failed to deliver webhooks: {}", error);
+        }
+    }
+}
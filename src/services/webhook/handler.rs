@@ -0,0 +1,414 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State, rejection::QueryRejection},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    AppError,
+    repos::{
+        book::MAX_PAGE_LIMIT,
+        webhook::{DeliveryStatus, Webhook, WebhookEvent, webhook_not_found},
+    },
+    services::{
+        auth::{
+            Claims,
+            scope::{Authorized, Scope, WebhooksManage},
+            token::generate_webhook_secret,
+        },
+        book::handler::{page_meta, page_request},
+    },
+};
+
+use super::{WebhookState, target::TargetPolicy};
+
+const MAX_URL_LEN: usize = 2048;
+
+/// Where to send which events. Deliveries carry the book, so subscribing
+/// also needs the `books:read` scope.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookParams {
+    /// An http or https URL at a public address receiving a POST per event
+    url: String,
+    /// At least one event
+    events: Vec<WebhookEvent>,
+}
+
+/// A stored webhook. Its `secret` is only returned when created.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookView {
+    id: Uuid,
+    url: String,
+    events: Vec<WebhookEvent>,
+    created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+}
+
+impl From<Webhook> for WebhookView {
+    fn from(webhook: Webhook) -> Self {
+        WebhookView {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            created_at: webhook.created_at,
+            secret: None,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterQuery {
+    /// Page number starting at 1, defaults to 1
+    page: Option<u32>,
+    /// Deliveries per page, between 1 and 100, defaults to 20
+    limit: Option<u32>,
+}
+
+fn bad_request(message: &str) -> AppError {
+    AppError::ClientFail(StatusCode::BAD_REQUEST, message.to_string())
+}
+
+/// The URL and events of `params`, each event listed once.
+fn validate(
+    claims: &Claims,
+    targets: &TargetPolicy,
+    params: WebhookParams,
+) -> Result<(String, Vec<WebhookEvent>), AppError> {
+    claims.require(Scope::BooksRead)?;
+    let url = params.url.trim();
+    let parsed = Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+    let Some(parsed) = parsed.filter(|_| url.len() <= MAX_URL_LEN) else {
+        return Err(bad_request("URL must be an http or https URL"));
+    };
+    if !targets.allows_url(&parsed) {
+        return Err(bad_request(
+            "URL must not point at a loopback, link-local or private address",
+        ));
+    }
+    let mut events = Vec::new();
+    for event in params.events {
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    if events.is_empty() {
+        return Err(bad_request("Events must list at least one event"));
+    }
+    Ok((url.to_string(), events))
+}
+
+fn parse_id(id: &str, message: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(id).map_err(|_| bad_request(message))
+}
+
+/// The secret signing the deliveries is only ever returned by this call.
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = WebhookParams,
+    responses(
+        (status = 201, description = "Webhook created"),
+        (status = 400, description = "Invalid URL or events"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage or books:read scope"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage", "books:read"]),
+        ("apiKeyAuth" = ["webhooks:manage", "books:read"])
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    Json(params): Json<WebhookParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let (url, events) = validate(&claims, &state.targets, params)?;
+    let secret = generate_webhook_secret();
+    let webhook = Webhook {
+        id: Uuid::new_v4(),
+        owner_id: claims.user_id()?,
+        url,
+        secret: secret.clone(),
+        events,
+        created_at: Utc::now(),
+    };
+    state.repo.create_webhook(&webhook).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Webhook created",
+        "data": {
+            "webhook": WebhookView {
+                secret: Some(secret),
+                ..WebhookView::from(webhook)
+            }
+        }
+    }));
+
+    Ok((StatusCode::CREATED, headers, body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses(
+        (status = 200, description = "Webhooks of the caller, oldest first"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage scope"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage"]),
+        ("apiKeyAuth" = ["webhooks:manage"])
+    )
+)]
+pub async fn get_webhooks(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhooks: Vec<WebhookView> = state
+        .repo
+        .get_webhooks(claims.user_id()?)
+        .await?
+        .into_iter()
+        .map(WebhookView::from)
+        .collect();
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "webhooks": webhooks
+        }
+    }));
+
+    Ok((headers, body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "The webhook"),
+        (status = 400, description = "Invalid id"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage scope"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage"]),
+        ("apiKeyAuth" = ["webhooks:manage"])
+    )
+)]
+pub async fn get_webhook(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let id = parse_id(&id, "Invalid webhook id")?;
+    let webhook = state
+        .repo
+        .get_webhook(claims.user_id()?, id)
+        .await?
+        .ok_or_else(webhook_not_found)?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "webhook": WebhookView::from(webhook)
+        }
+    }));
+
+    Ok((headers, body))
+}
+
+/// Replaces the URL and events, the secret stays the same.
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    request_body = WebhookParams,
+    params(
+        ("id" = String, Path, description = "ID of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "Webhook updated"),
+        (status = 400, description = "Invalid id, URL or events"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage or books:read scope"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage", "books:read"]),
+        ("apiKeyAuth" = ["webhooks:manage", "books:read"])
+    )
+)]
+pub async fn update_webhook(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    Path(id): Path<String>,
+    Json(params): Json<WebhookParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let id = parse_id(&id, "Invalid webhook id")?;
+    let (url, events) = validate(&claims, &state.targets, params)?;
+    let owner = claims.user_id()?;
+    let mut webhook = state
+        .repo
+        .get_webhook(owner, id)
+        .await?
+        .ok_or_else(webhook_not_found)?;
+    webhook.url = url;
+    webhook.events = events;
+    state.repo.update_webhook(&webhook).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Webhook updated",
+        "data": {
+            "webhook": WebhookView::from(webhook)
+        }
+    }));
+
+    Ok((headers, body))
+}
+
+/// Deliveries still queued for the webhook are dropped along with it.
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "Webhook deleted"),
+        (status = 400, description = "Invalid id"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage scope"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage"]),
+        ("apiKeyAuth" = ["webhooks:manage"])
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let id = parse_id(&id, "Invalid webhook id")?;
+    state.repo.delete_webhook(claims.user_id()?, id).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Webhook deleted"
+    }));
+
+    Ok((headers, body))
+}
+
+/// Deliveries given up on after too many failed attempts, most recently
+/// queued first, with the error of their last attempt.
+#[utoipa::path(
+    get,
+    path = "/webhooks/dead-letters",
+    params(DeadLetterQuery),
+    responses(
+        (status = 200, description = "Dead deliveries, newest first"),
+        (status = 400, description = "Invalid paging"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage scope"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage"]),
+        ("apiKeyAuth" = ["webhooks:manage"])
+    )
+)]
+pub async fn get_dead_letters(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    query: Result<Query<DeadLetterQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let invalid_query = |message: String| AppError::ClientFail(StatusCode::BAD_REQUEST, message);
+    let Query(query) = query
+        .map_err(|rejection| invalid_query(format!("Invalid query. {}", rejection.body_text())))?;
+    let page = page_request(query.page, query.limit, None, None).map_err(|_| {
+        invalid_query(format!(
+            "Invalid query. page must be at least 1 and limit between 1 and {}",
+            MAX_PAGE_LIMIT
+        ))
+    })?;
+
+    let deliveries = state
+        .repo
+        .get_deliveries(claims.user_id()?, Some(DeliveryStatus::Dead), &page)
+        .await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "data": {
+            "deliveries": deliveries.items
+        },
+        "meta": page_meta(&page, deliveries.total)
+    }));
+
+    Ok((headers, body))
+}
+
+/// Queues a dead delivery again, it gets as many attempts as a new one.
+#[utoipa::path(
+    post,
+    path = "/webhooks/deliveries/{id}/retry",
+    params(
+        ("id" = String, Path, description = "ID of the dead delivery"),
+    ),
+    responses(
+        (status = 200, description = "Delivery queued again"),
+        (status = 400, description = "Invalid id"),
+        (status = 401, description = "Invalid or missing token"),
+        (status = 403, description = "Token lacks the webhooks:manage scope"),
+        (status = 404, description = "Dead delivery not found"),
+    ),
+    security(
+        ("bearerAuth" = ["webhooks:manage"]),
+        ("apiKeyAuth" = ["webhooks:manage"])
+    )
+)]
+pub async fn retry_delivery(
+    State(state): State<WebhookState>,
+    claims: Authorized<WebhooksManage>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let id = parse_id(&id, "Invalid delivery id")?;
+    state.repo.retry_delivery(claims.user_id()?, id).await?;
+
+    let headers = [(header::CONTENT_TYPE, "application/json; charset=utf-8")];
+    let body = Json(json!({
+        "status": "success",
+        "message": "Delivery queued again",
+        "data": {
+            "deliveryId": id
+        }
+    }));
+
+    Ok((headers, body))
+}
@@ -0,0 +1,126 @@
+//! Webhooks notified of changes to the books on a shelf, managed at
+//! `/webhooks`. Book mutations queue a delivery per matching webhook through
+//! [`BookState::changed`](crate::services::book::BookState::changed) and
+//! [`delivery::deliver_webhooks`] sends them, retrying failed ones with
+//! exponential backoff until they are given up on as dead letters.
+
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    repos::{
+        audit::AuditAction,
+        book::Book,
+        webhook::{DeliveryStatus, WebhookDelivery, WebhookEvent, WebhookRepo},
+    },
+    services::auth::AuthState,
+};
+
+use target::TargetPolicy;
+
+pub mod delivery;
+pub mod handler;
+pub mod target;
+pub mod test;
+
+#[derive(Clone)]
+pub struct WebhookState {
+    pub repo: Arc<dyn WebhookRepo>,
+    pub targets: TargetPolicy,
+    pub auth: AuthState,
+}
+
+impl FromRef<WebhookState> for AuthState {
+    fn from_ref(state: &WebhookState) -> AuthState {
+        state.auth.clone()
+    }
+}
+
+/// What webhooks are told of a book going from `before` to `after`. A
+/// restored book is back on the shelf, which reads as created, and a change
+/// finishing a book is also sent as `book.finished`.
+pub fn events_of(
+    action: AuditAction,
+    before: Option<&Book>,
+    after: Option<&Book>,
+) -> Vec<WebhookEvent> {
+    let mut events = vec![match action {
+        AuditAction::Create | AuditAction::Restore => WebhookEvent::BookCreated,
+        AuditAction::Update => WebhookEvent::BookUpdated,
+        AuditAction::Delete => WebhookEvent::BookDeleted,
+    }];
+    let finished = after.is_some_and(|book| book.finished);
+    let was_finished = before.is_some_and(|book| book.finished);
+    if finished && !was_finished {
+        events.push(WebhookEvent::BookFinished);
+    }
+    events
+}
+
+/// Queues a delivery of the change to every webhook of the book's owner
+/// subscribed to one of its events. The change is already stored by then,
+/// so failures are logged rather than returned.
+pub async fn enqueue(
+    repo: &dyn WebhookRepo,
+    action: AuditAction,
+    before: Option<&Book>,
+    after: Option<&Book>,
+) {
+    let Some(book) = after.or(before) else {
+        return;
+    };
+    let webhooks = match repo.get_webhooks(book.owner_id).await {
+        Ok(webhooks) => webhooks,
+        Err(error) => {
+            tracing::error!(
+                "Failed to look up the webhooks of book {}: {}",
+                book.id,
+                error
+            );
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for event in events_of(action, before, after) {
+        for webhook in webhooks
+            .iter()
+            .filter(|webhook| webhook.events.contains(&event))
+        {
+            let id = Uuid::new_v4();
+            let delivery = WebhookDelivery {
+                id,
+                webhook_id: webhook.id,
+                owner_id: webhook.owner_id,
+                event,
+                payload: json!({
+                    "id": id,
+                    "event": event,
+                    "createdAt": now,
+                    "data": {
+                        "book": book
+                    }
+                }),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: now,
+                last_error: None,
+                created_at: now,
+                delivered_at: None,
+            };
+            if let Err(error) = repo.enqueue_delivery(&delivery).await {
+                tracing::error!(
+                    "Failed to queue {} of book {} for webhook {}: {}",
+                    event.as_str(),
+                    book.id,
+                    webhook.id,
+                    error
+                );
+            }
+        }
+    }
+}
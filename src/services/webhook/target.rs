@@ -0,0 +1,114 @@
+//! Where webhooks may send to. Loopback, link-local, private and other
+//! non-public addresses are refused unless their host is allowed, when a
+//! webhook is registered and again whenever a delivery connects, so a name
+//! later resolving to one of them can't reach it either.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct TargetPolicy {
+    /// Lowercase hosts exempt from the address check, IPv6 ones without
+    /// brackets.
+    allowed_hosts: Arc<Vec<String>>,
+}
+
+impl TargetPolicy {
+    pub fn new(allowed_hosts: &[String]) -> Self {
+        TargetPolicy {
+            allowed_hosts: Arc::new(allowed_hosts.iter().map(|host| normalize(host)).collect()),
+        }
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        self.allowed_hosts.contains(&normalize(host))
+    }
+
+    /// Whether `url` may be sent to as far as can be told without resolving
+    /// it: an allowed host, a public address or a name other than
+    /// `localhost`. Names are checked as they resolve by [`PublicResolver`].
+    pub fn allows_url(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str().map(normalize) else {
+            return false;
+        };
+        if self.allows_host(&host) {
+            return true;
+        }
+        match host.parse::<IpAddr>() {
+            Ok(ip) => is_public(ip),
+            Err(_) => host != "localhost" && !host.ends_with(".localhost"),
+        }
+    }
+}
+
+fn normalize(host: &str) -> String {
+    host.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Whether `ip` is reachable on the internet at large, rather than only
+/// from this machine or its network.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    let this_network = first == 0;
+    let shared = first == 100 && (64..128).contains(&second);
+    !(this_network
+        || shared
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast())
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    let unique_local = first & 0xfe00 == 0xfc00;
+    let link_local = first & 0xffc0 == 0xfe80;
+    !(unique_local || link_local || ip.is_unspecified() || ip.is_loopback() || ip.is_multicast())
+}
+
+/// Resolves the names deliveries are sent to, keeping only the public
+/// addresses of hosts that aren't allowed. The check happens as the
+/// connection is made, so a name can't pass it and then resolve elsewhere.
+pub struct PublicResolver(pub TargetPolicy);
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.0.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?;
+            let addrs: Vec<SocketAddr> = if policy.allows_host(&host) {
+                addrs.collect()
+            } else {
+                addrs.filter(|addr| is_public(addr.ip())).collect()
+            };
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
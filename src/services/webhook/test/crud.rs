@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod webhook_crud {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::{
+        app::app,
+        config::Config,
+        services::{
+            auth::{
+                scope::Scope,
+                test::{call, get_ready_service},
+            },
+            book::test::{bearer, bearer_with},
+            webhook::test::{
+                build_create_webhook_request, build_delete_webhook_request,
+                build_get_webhook_request, build_get_webhooks_request,
+                build_update_webhook_request,
+            },
+        },
+    };
+
+    fn finished_hook() -> Value {
+        json!({ "url": "http://127.0.0.1:9/hook", "events": ["book.finished"] })
+    }
+
+    /// Creates a webhook of the test user, returning its `data.webhook`.
+    async fn create(app: &mut Router, payload: Value) -> Value {
+        let (status, body) = call(app, build_create_webhook_request(payload)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        body["data"]["webhook"].clone()
+    }
+
+    fn authorized(mut request: Request<Body>, authorization: &str) -> Request<Body> {
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, authorization.parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn created_webhook_should_return_its_secret_once() {
+        let mut app = app(Config::ephemeral()).await;
+
+        let (status, body) = call(&mut app, build_create_webhook_request(finished_hook())).await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["message"], "Webhook created");
+        let webhook = &body["data"]["webhook"];
+        assert_eq!(webhook["url"], "http://127.0.0.1:9/hook");
+        assert_eq!(webhook["events"], json!(["book.finished"]));
+        assert!(webhook["secret"].as_str().unwrap().starts_with("whsec_"));
+        let id = webhook["id"].as_str().unwrap();
+
+        let (status, body) = call(&mut app, build_get_webhooks_request()).await;
+        assert_eq!(status, StatusCode::OK);
+        let webhooks = body["data"]["webhooks"].as_array().unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0]["id"], id);
+        assert!(webhooks[0].get("secret").is_none());
+
+        let (status, body) = call(&mut app, build_get_webhook_request(id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["webhook"]["events"], json!(["book.finished"]));
+        assert!(body["data"]["webhook"].get("secret").is_none());
+    }
+
+    #[tokio::test]
+    async fn invalid_webhook_should_be_rejected() {
+        let mut app = app(Config::ephemeral()).await;
+
+        for (payload, message) in [
+            (
+                json!({ "url": "ftp://example.com/hook", "events": ["book.created"] }),
+                "URL must be an http or https URL",
+            ),
+            (
+                json!({ "url": "not a url", "events": ["book.created"] }),
+                "URL must be an http or https URL",
+            ),
+            (
+                json!({ "url": "https://example.com/hook", "events": [] }),
+                "Events must list at least one event",
+            ),
+        ] {
+            let (status, body) = call(&mut app, build_create_webhook_request(payload)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["status"], "fail");
+            assert_eq!(body["message"], message);
+        }
+
+        let unknown = json!({ "url": "https://example.com/hook", "events": ["book.read"] });
+        let ready_service = get_ready_service(&mut app).await;
+        let response = tower::Service::call(ready_service, build_create_webhook_request(unknown))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let (_, body) = call(&mut app, build_get_webhooks_request()).await;
+        assert_eq!(body["data"]["webhooks"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn internal_url_should_be_rejected() {
+        let mut app = app(Config::ephemeral()).await;
+        let created = create(&mut app, finished_hook()).await;
+        let id = created["id"].as_str().unwrap();
+
+        for url in [
+            "http://localhost:8080/hook",
+            "http://api.localhost/hook",
+            "http://127.0.0.2/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://0x7f000002/hook",
+            "http://[::1]/hook",
+            "http://[::ffff:10.0.0.5]/hook",
+            "http://[fd00::1]/hook",
+        ] {
+            let payload = json!({ "url": url, "events": ["book.created"] });
+            let (status, body) =
+                call(&mut app, build_create_webhook_request(payload.clone())).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+            assert_eq!(
+                body["message"],
+                "URL must not point at a loopback, link-local or private address"
+            );
+            let (status, _) = call(&mut app, build_update_webhook_request(id, payload)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", url);
+        }
+
+        let (_, body) = call(&mut app, build_get_webhooks_request()).await;
+        let webhooks = body["data"]["webhooks"].as_array().unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0]["url"], created["url"]);
+    }
+
+    #[tokio::test]
+    async fn updated_webhook_should_replace_url_and_events() {
+        let mut app = app(Config::ephemeral()).await;
+        let created = create(&mut app, finished_hook()).await;
+        let id = created["id"].as_str().unwrap();
+
+        let payload = json!({
+            "url": "https://example.com/hook",
+            "events": ["book.created", "book.deleted", "book.created"]
+        });
+        let (status, body) = call(&mut app, build_update_webhook_request(id, payload)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Webhook updated");
+        let (_, body) = call(&mut app, build_get_webhook_request(id)).await;
+        let webhook = &body["data"]["webhook"];
+        assert_eq!(webhook["url"], "https://example.com/hook");
+        assert_eq!(webhook["events"], json!(["book.created", "book.deleted"]));
+        assert_eq!(webhook["createdAt"], created["createdAt"]);
+    }
+
+    #[tokio::test]
+    async fn deleted_webhook_should_be_gone() {
+        let mut app = app(Config::ephemeral()).await;
+        let created = create(&mut app, finished_hook()).await;
+        let id = created["id"].as_str().unwrap();
+
+        let (status, body) = call(&mut app, build_delete_webhook_request(id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Webhook deleted");
+
+        let (status, body) = call(&mut app, build_get_webhook_request(id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["message"], "Webhook not found");
+        let (status, _) = call(&mut app, build_delete_webhook_request(id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, body) = call(&mut app, build_delete_webhook_request("xxxxx")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["message"], "Invalid webhook id");
+    }
+
+    #[tokio::test]
+    async fn other_users_webhook_should_be_not_found() {
+        let mut app = app(Config::ephemeral()).await;
+        let created = create(&mut app, finished_hook()).await;
+        let id = created["id"].as_str().unwrap();
+        let other = bearer(Uuid::new_v4());
+
+        let requests = [
+            build_get_webhook_request(id),
+            build_update_webhook_request(id, finished_hook()),
+            build_delete_webhook_request(id),
+        ];
+        for request in requests {
+            let (status, _) = call(&mut app, authorized(request, &other)).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+        }
+
+        let (_, body) = call(&mut app, authorized(build_get_webhooks_request(), &other)).await;
+        assert_eq!(body["data"]["webhooks"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn subscribing_should_need_the_books_read_scope() {
+        let mut app = app(Config::ephemeral()).await;
+        let user = Uuid::new_v4();
+        let manage_only = bearer_with(user, vec![Scope::WebhooksManage]);
+        let read_only = bearer_with(user, vec![Scope::BooksRead]);
+
+        let request = authorized(build_create_webhook_request(finished_hook()), &manage_only);
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let request = authorized(build_get_webhooks_request(), &manage_only);
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::OK);
+        let request = authorized(build_get_webhooks_request(), &read_only);
+        let (status, _) = call(&mut app, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
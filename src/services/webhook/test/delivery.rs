@@ -0,0 +1,437 @@
+#[cfg(test)]
+mod webhook_delivery {
+    use std::{
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicU16, Ordering},
+        },
+        time::Duration as StdDuration,
+    };
+
+    use axum::{
+        Router,
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+    };
+    use chrono::{Duration, Utc};
+    use hmac::{Hmac, Mac};
+    use reqwest::dns::Resolve;
+    use serde_json::{Value, json};
+    use sha2::Sha256;
+    use uuid::Uuid;
+
+    use crate::{
+        app::{AppState, router, webhook_delivery},
+        config::{Config, WebhookConfig},
+        repos::{
+            book::PageRequest,
+            webhook::{DeliveryStatus, WebhookDelivery, WebhookEvent},
+        },
+        services::{
+            auth::test::call,
+            book::test::{
+                TEST_USER, build_create_book_request, build_delete_book_request,
+                build_patch_book_request, new_book_dummy,
+            },
+            webhook::{
+                delivery::{DELIVERY_BATCH, DeliveryPolicy, deliver_due},
+                target::{PublicResolver, TargetPolicy},
+                test::{
+                    build_create_webhook_request, build_delete_webhook_request,
+                    build_get_dead_letters_request, build_retry_delivery_request,
+                },
+            },
+        },
+    };
+
+    /// A local stand-in for a webhook receiver, answering every POST with
+    /// `status` and keeping what it was sent.
+    #[derive(Clone)]
+    struct Receiver {
+        url: String,
+        status: Arc<AtomicU16>,
+        received: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    }
+
+    impl Receiver {
+        async fn start() -> Self {
+            let status = Arc::new(AtomicU16::new(200));
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let app = Router::new()
+                .route("/hook", post(receive))
+                .with_state((status.clone(), received.clone()));
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            Receiver {
+                url: format!("http://{}/hook", addr),
+                status,
+                received,
+            }
+        }
+
+        fn answer(&self, status: StatusCode) {
+            self.status.store(status.as_u16(), Ordering::SeqCst);
+        }
+
+        fn received(&self) -> Vec<(HeaderMap, Bytes)> {
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    type Received = (Arc<AtomicU16>, Arc<Mutex<Vec<(HeaderMap, Bytes)>>>);
+
+    async fn receive(
+        State((status, received)): State<Received>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        received.lock().unwrap().push((headers, body));
+        StatusCode::from_u16(status.load(Ordering::SeqCst)).unwrap()
+    }
+
+    /// URL of a receiver that takes every POST and never answers.
+    async fn start_hanging_receiver() -> String {
+        let app = Router::new().route("/hook", post(std::future::pending::<StatusCode>));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/hook", addr)
+    }
+
+    fn policy(max_attempts: u32) -> DeliveryPolicy {
+        DeliveryPolicy {
+            max_attempts,
+            retry_base: Duration::hours(1),
+            batch: DELIVERY_BATCH,
+            targets: TargetPolicy::new(&["127.0.0.1".to_string()]),
+        }
+    }
+
+    /// Subscribes the test user's `events` to `receiver`, returning the
+    /// signing secret.
+    async fn subscribe(app: &mut Router, receiver: &Receiver, events: Value) -> String {
+        let payload = json!({ "url": receiver.url, "events": events });
+        let (status, body) = call(app, build_create_webhook_request(payload)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        body["data"]["webhook"]["secret"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    async fn create_book(app: &mut Router) -> String {
+        let (_, body) = call(app, build_create_book_request(new_book_dummy())).await;
+        body["data"]["bookId"].as_str().unwrap().to_string()
+    }
+
+    /// One delivery round with `policy`, returning how many were attempted.
+    async fn deliver(state: &AppState, policy: &DeliveryPolicy) -> usize {
+        deliver_due(state.webhook.repo.as_ref(), &reqwest::Client::new(), policy)
+            .await
+            .unwrap()
+    }
+
+    /// The test user's latest delivery.
+    async fn latest_delivery(state: &AppState) -> WebhookDelivery {
+        state
+            .webhook
+            .repo
+            .get_deliveries(TEST_USER, None, &PageRequest::default())
+            .await
+            .unwrap()
+            .items
+            .remove(0)
+    }
+
+    async fn setup() -> (AppState, Router, Receiver) {
+        let state = AppState::new(&Config::ephemeral()).await.unwrap();
+        let app = router(state.clone());
+        (state, app, Receiver::start().await)
+    }
+
+    #[tokio::test]
+    async fn delivery_should_be_signed_with_the_webhook_secret() {
+        let (state, mut app, receiver) = setup().await;
+        let secret = subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        let id = create_book(&mut app).await;
+
+        assert_eq!(deliver(&state, &policy(3)).await, 1);
+
+        let received = receiver.received();
+        assert_eq!(received.len(), 1);
+        let (headers, body) = &received[0];
+        assert_eq!(headers["x-bookshelf-event"], "book.created");
+        let timestamp: i64 = headers["x-bookshelf-timestamp"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((Utc::now().timestamp() - timestamp).abs() <= 5);
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(body);
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert_eq!(headers["x-bookshelf-signature"], expected.as_str());
+        let payload: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["event"], "book.created");
+        assert_eq!(
+            payload["id"],
+            headers["x-bookshelf-delivery"].to_str().unwrap()
+        );
+        assert_eq!(payload["data"]["book"]["id"], id);
+        assert_eq!(payload["data"]["book"]["name"], "Buku A");
+
+        assert_eq!(deliver(&state, &policy(3)).await, 0);
+        assert_eq!(receiver.received().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn finished_webhook_should_only_hear_of_finishing_changes() {
+        let (state, mut app, receiver) = setup().await;
+        subscribe(&mut app, &receiver, json!(["book.finished"])).await;
+        let id = create_book(&mut app).await;
+        call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 50, "reading": true })),
+        )
+        .await;
+        let (status, _) = call(
+            &mut app,
+            build_patch_book_request(&id, json!({ "readPage": 100 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        call(&mut app, build_delete_book_request(&id)).await;
+
+        assert_eq!(deliver(&state, &policy(3)).await, 1);
+
+        let received = receiver.received();
+        let (headers, body) = &received[0];
+        assert_eq!(headers["x-bookshelf-event"], "book.finished");
+        let payload: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["data"]["book"]["finished"], true);
+        assert_eq!(payload["data"]["book"]["readPage"], 100);
+    }
+
+    #[tokio::test]
+    async fn failing_delivery_should_back_off_until_dead() {
+        let (state, mut app, receiver) = setup().await;
+        receiver.answer(StatusCode::INTERNAL_SERVER_ERROR);
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        create_book(&mut app).await;
+
+        for (attempts, backoff) in [(1, Duration::hours(1)), (2, Duration::hours(2))] {
+            assert_eq!(deliver(&state, &policy(3)).await, 1);
+            assert_eq!(deliver(&state, &policy(3)).await, 0);
+
+            let mut delivery = latest_delivery(&state).await;
+            assert_eq!(delivery.status, DeliveryStatus::Pending);
+            assert_eq!(delivery.attempts, attempts);
+            assert_eq!(
+                delivery.last_error.as_deref(),
+                Some("500 Internal Server Error")
+            );
+            let wait = delivery.next_attempt_at - Utc::now();
+            assert!(wait > backoff - Duration::minutes(1) && wait <= backoff);
+
+            // Skips the wait.
+            delivery.next_attempt_at = Utc::now();
+            state.webhook.repo.update_delivery(&delivery).await.unwrap();
+        }
+        assert_eq!(deliver(&state, &policy(3)).await, 1);
+        assert_eq!(receiver.received().len(), 3);
+        assert_eq!(latest_delivery(&state).await.status, DeliveryStatus::Dead);
+
+        let (status, body) = call(&mut app, build_get_dead_letters_request("")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["meta"]["total"], 1);
+        let dead = &body["data"]["deliveries"][0];
+        assert_eq!(dead["status"], "dead");
+        assert_eq!(dead["attempts"], 3);
+        assert_eq!(dead["event"], "book.created");
+        assert_eq!(dead["lastError"], "500 Internal Server Error");
+    }
+
+    #[tokio::test]
+    async fn retried_dead_letter_should_be_delivered() {
+        let (state, mut app, receiver) = setup().await;
+        receiver.answer(StatusCode::SERVICE_UNAVAILABLE);
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        create_book(&mut app).await;
+        deliver(&state, &policy(1)).await;
+        let (_, body) = call(&mut app, build_get_dead_letters_request("")).await;
+        let id = body["data"]["deliveries"][0]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        receiver.answer(StatusCode::NO_CONTENT);
+        let (status, body) = call(&mut app, build_retry_delivery_request(&id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Delivery queued again");
+        let (_, body) = call(&mut app, build_get_dead_letters_request("")).await;
+        assert_eq!(body["meta"]["total"], 0);
+        assert_eq!(deliver(&state, &policy(1)).await, 1);
+        assert_eq!(receiver.received().len(), 2);
+        let delivery = latest_delivery(&state).await;
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert!(delivery.delivered_at.is_some());
+
+        let (status, body) = call(&mut app, build_retry_delivery_request(&id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["message"], "Dead delivery not found");
+        let (status, _) = call(&mut app, build_retry_delivery_request("xxxxx")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call(&mut app, build_get_dead_letters_request("limit=0")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn deleted_webhook_should_not_be_delivered() {
+        let (state, mut app, receiver) = setup().await;
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        let (_, body) = call(
+            &mut app,
+            build_create_webhook_request(
+                json!({ "url": receiver.url, "events": ["book.created"] }),
+            ),
+        )
+        .await;
+        let deleted = body["data"]["webhook"]["id"].as_str().unwrap().to_string();
+        create_book(&mut app).await;
+
+        call(&mut app, build_delete_webhook_request(&deleted)).await;
+
+        assert_eq!(deliver(&state, &policy(3)).await, 1);
+        assert_eq!(receiver.received().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn delivery_of_a_deleted_webhook_should_be_given_up_on() {
+        let (state, _, _) = setup().await;
+        let now = Utc::now();
+        let orphan = WebhookDelivery {
+            id: Uuid::new_v4(),
+            webhook_id: Uuid::new_v4(),
+            owner_id: TEST_USER,
+            event: WebhookEvent::BookCreated,
+            payload: json!({}),
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        };
+        state.webhook.repo.enqueue_delivery(&orphan).await.unwrap();
+
+        assert_eq!(deliver(&state, &policy(3)).await, 1);
+
+        let delivery = latest_delivery(&state).await;
+        assert_eq!(delivery.status, DeliveryStatus::Dead);
+        assert_eq!(delivery.attempts, 0);
+        assert_eq!(delivery.last_error.as_deref(), Some("Webhook deleted"));
+        assert_eq!(deliver(&state, &policy(3)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn hanging_receiver_should_not_hold_up_the_others() {
+        let (state, mut app, receiver) = setup().await;
+        let hanging = start_hanging_receiver().await;
+        let payload = json!({ "url": hanging, "events": ["book.created"] });
+        let (status, body) = call(&mut app, build_create_webhook_request(payload)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let hanging_id = body["data"]["webhook"]["id"].as_str().unwrap().to_string();
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        create_book(&mut app).await;
+        // Makes the hanging delivery the first one due.
+        let deliveries = state
+            .webhook
+            .repo
+            .get_deliveries(TEST_USER, None, &PageRequest::default())
+            .await
+            .unwrap()
+            .items;
+        let mut first = deliveries
+            .into_iter()
+            .find(|delivery| delivery.webhook_id.to_string() == hanging_id)
+            .unwrap();
+        first.next_attempt_at = Utc::now() - Duration::minutes(1);
+        state.webhook.repo.update_delivery(&first).await.unwrap();
+
+        let round = tokio::spawn(async move { deliver(&state, &policy(3)).await });
+        for _ in 0..50 {
+            if !receiver.received().is_empty() {
+                break;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+
+        assert_eq!(receiver.received().len(), 1);
+        assert!(!round.is_finished());
+        round.abort();
+    }
+
+    #[tokio::test]
+    async fn delivery_to_an_address_no_longer_allowed_should_not_be_sent() {
+        let (state, mut app, receiver) = setup().await;
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        create_book(&mut app).await;
+        let policy = DeliveryPolicy {
+            targets: TargetPolicy::default(),
+            ..policy(3)
+        };
+
+        assert_eq!(deliver(&state, &policy).await, 1);
+
+        assert!(receiver.received().is_empty());
+        let delivery = latest_delivery(&state).await;
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(
+            delivery.last_error.as_deref(),
+            Some("Target address isn't allowed")
+        );
+    }
+
+    #[tokio::test]
+    async fn resolver_should_keep_only_public_addresses_of_names_not_allowed() {
+        let refused = PublicResolver(TargetPolicy::default());
+        assert!(refused.resolve("localhost".parse().unwrap()).await.is_err());
+
+        let allowed = PublicResolver(TargetPolicy::new(&["LOCALHOST".to_string()]));
+        let addrs: Vec<_> = allowed
+            .resolve("localhost".parse().unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+        assert!(!addrs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn delivery_job_should_send_queued_deliveries() {
+        let (state, mut app, receiver) = setup().await;
+        subscribe(&mut app, &receiver, json!(["book.created"])).await;
+        create_book(&mut app).await;
+
+        let job = tokio::spawn(webhook_delivery(&state, &WebhookConfig::default()).unwrap());
+        for _ in 0..50 {
+            if !receiver.received().is_empty() {
+                break;
+            }
+            tokio::time::sleep(StdDuration::from_millis(20)).await;
+        }
+        job.abort();
+
+        assert_eq!(receiver.received().len(), 1);
+        let zero_interval = WebhookConfig {
+            poll_interval_secs: 0,
+            ..WebhookConfig::default()
+        };
+        assert!(webhook_delivery(&state, &zero_interval).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, header},
+};
+use serde_json::Value;
+
+use crate::services::book::test::{TEST_USER, bearer};
+
+pub mod crud;
+pub mod delivery;
+
+#[allow(dead_code)]
+fn build_create_webhook_request(payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_webhooks_request() -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri("/webhooks")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_webhook_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/webhooks/{}", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_update_webhook_request(id: &str, payload: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::PUT)
+        .uri(format!("/webhooks/{}", id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::from(payload.to_string()))
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_delete_webhook_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("/webhooks/{}", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_get_dead_letters_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/webhooks/dead-letters?{}", query))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[allow(dead_code)]
+fn build_retry_delivery_request(id: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/webhooks/deliveries/{}/retry", id))
+        .header(header::AUTHORIZATION, bearer(TEST_USER))
+        .body(Body::empty())
+        .unwrap()
+}
@@ -19,6 +19,10 @@ pub enum AppError {
     TokenCreation,
     #[error("Invalid token")]
     InvalidToken,
+    #[error("Insufficient permissions")]
+    Forbidden,
+    #[error("Password hashing error")]
+    PasswordHashing,
 }
 
 impl IntoResponse for AppError {
@@ -35,6 +39,10 @@ impl IntoResponse for AppError {
                 (StatusCode::INTERNAL_SERVER_ERROR, "error", self.to_string())
             }
             AppError::InvalidToken => (StatusCode::UNAUTHORIZED, "fail", self.to_string()),
+            AppError::Forbidden => (StatusCode::FORBIDDEN, "fail", self.to_string()),
+            AppError::PasswordHashing => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "error", self.to_string())
+            }
         };
 
         (
@@ -45,3 +53,24 @@ impl IntoResponse for AppError {
             .into_response()
     }
 }
+
+impl From<AppError> for tonic::Status {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::ClientFail(status, message) => match status {
+                StatusCode::BAD_REQUEST => tonic::Status::invalid_argument(message),
+                StatusCode::NOT_FOUND => tonic::Status::not_found(message),
+                StatusCode::CONFLICT => tonic::Status::already_exists(message),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE => tonic::Status::invalid_argument(message),
+                _ => tonic::Status::failed_precondition(message),
+            },
+            AppError::DatabaseError | AppError::TokenCreation | AppError::PasswordHashing => {
+                tonic::Status::internal(error.to_string())
+            }
+            AppError::WrongCredentials | AppError::MissingCredentials | AppError::InvalidToken => {
+                tonic::Status::unauthenticated(error.to_string())
+            }
+            AppError::Forbidden => tonic::Status::permission_denied(error.to_string()),
+        }
+    }
+}
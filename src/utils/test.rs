@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod grpc_status {
+    use axum::http::StatusCode;
+    use tonic::Code;
+
+    use crate::utils::error::AppError;
+
+    fn code_of(status: StatusCode) -> Code {
+        tonic::Status::from(AppError::ClientFail(status, "Gagal".to_string())).code()
+    }
+
+    #[test]
+    fn client_failures_should_map_to_matching_codes() {
+        assert_eq!(code_of(StatusCode::BAD_REQUEST), Code::InvalidArgument);
+        assert_eq!(code_of(StatusCode::NOT_FOUND), Code::NotFound);
+        assert_eq!(code_of(StatusCode::CONFLICT), Code::AlreadyExists);
+        assert_eq!(
+            code_of(StatusCode::PRECONDITION_FAILED),
+            Code::FailedPrecondition
+        );
+        assert_eq!(
+            code_of(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            Code::InvalidArgument
+        );
+    }
+}